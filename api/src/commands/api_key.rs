@@ -0,0 +1,106 @@
+use clap::{Args, Subcommand, builder::PossibleValuesParser};
+use sqlx::{Execute, Pool, Postgres};
+use tracing::{debug, info};
+
+use crate::services::auth::{self, Scope};
+
+const CREATE_API_KEY_QUERY: &str = "\
+    INSERT INTO api_keys (name, hash, scopes) \
+    VALUES ($1, $2, $3)";
+
+const REVOKE_API_KEY_QUERY: &str = "\
+    UPDATE api_keys \
+    SET revoked_at = NOW() \
+    WHERE name = $1 \
+    AND revoked_at IS NULL";
+
+const LIST_API_KEYS_QUERY: &str = "\
+    SELECT name, scopes, created_at, revoked_at \
+    FROM api_keys \
+    ORDER BY created_at ASC";
+
+#[derive(Args)]
+pub struct ApiKeyArgs {
+    #[command(subcommand)]
+    pub command: ApiKeyCommand,
+}
+
+#[derive(Subcommand)]
+pub enum ApiKeyCommand {
+    /// Create a key, printed once as only its hash is stored
+    Create {
+        /// Unique name of the key, reported in the usage metrics
+        #[arg(long)]
+        name: String,
+
+        /// Scopes granted to the key
+        #[arg(long, required = true, value_delimiter = ',', value_parser = PossibleValuesParser::new(Scope::ALL.map(|s| s.as_str())))]
+        scopes: Vec<String>,
+    },
+
+    /// Revoke a key, refused by the running servers within a minute
+    Revoke {
+        #[arg(long)]
+        name: String,
+    },
+
+    /// List the keys with their scopes
+    List,
+}
+
+/* ///////////////////////// */
+/* //// API Key Command //// */
+/* ///////////////////////// */
+
+pub async fn run(args: ApiKeyArgs, db: &Pool<Postgres>) -> Result<(), Box<dyn std::error::Error>> {
+    match args.command {
+        ApiKeyCommand::Create { name, scopes } => {
+            let key = format!("m1_{}", hex::encode(rand::random::<[u8; 32]>()));
+
+            let query = sqlx::query(CREATE_API_KEY_QUERY)
+                .bind(&name)
+                .bind(auth::hash_key(&key))
+                .bind(&scopes);
+            debug!("SQL query - {}", query.sql());
+            query.execute(db).await?;
+
+            info!("API key '{}' created with scopes {:?}", name, scopes);
+            println!("{}", key);
+        }
+        ApiKeyCommand::Revoke { name } => {
+            let query = sqlx::query(REVOKE_API_KEY_QUERY).bind(&name);
+            debug!("SQL query - {}", query.sql());
+
+            if query.execute(db).await?.rows_affected() == 0 {
+                return Err(format!("No active API key named '{}'", name).into());
+            }
+
+            info!("API key '{}' revoked", name);
+        }
+        ApiKeyCommand::List => {
+            let query = sqlx::query_as::<
+                _,
+                (
+                    String,
+                    Vec<String>,
+                    chrono::DateTime<chrono::Utc>,
+                    Option<chrono::DateTime<chrono::Utc>>,
+                ),
+            >(LIST_API_KEYS_QUERY);
+            debug!("SQL query - {}", query.sql());
+
+            for (name, scopes, created_at, revoked_at) in query.fetch_all(db).await? {
+                let status = revoked_at.map_or("active".to_string(), |d| format!("revoked {}", d));
+                println!(
+                    "{}\t{}\tcreated {}\t{}",
+                    name,
+                    scopes.join(","),
+                    created_at,
+                    status
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,277 @@
+use std::time::Duration;
+
+use chrono::Datelike;
+use clap::{Args, builder::PossibleValuesParser};
+use metrics_one_queue::models::SESSION_FEEDS;
+use sqlx::{Execute, Pool, Postgres};
+use tracing::{debug, info, instrument, warn};
+
+use crate::services::jobs;
+
+const MEETING_KEYS_QUERY: &str = "SELECT key FROM meetings WHERE year = $1";
+
+const PAST_SESSIONS_QUERY: &str = "\
+    SELECT sessions.key, sessions.path \
+    FROM sessions \
+    JOIN meetings ON meetings.key = sessions.meeting_key \
+    WHERE meetings.year = $1 \
+    AND sessions.end_date < NOW() \
+    ORDER BY sessions.start_date ASC";
+
+// Jobs queued for longer than 'JOB_TTL' seconds are counted as lost, as when registering them
+const JOBS_PROGRESS_QUERY: &str = "\
+    SELECT \
+        CASE \
+            WHEN status = 'queued' AND updated_at < NOW() - make_interval(secs => $2) THEN 'lost' \
+            ELSE status \
+        END AS status, \
+        COUNT(*) \
+    FROM jobs \
+    WHERE id = ANY($1) \
+    GROUP BY 1";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Args)]
+pub struct BackfillArgs {
+    /// First season to backfill
+    #[arg(long, default_value_t = 2018)]
+    pub from: i32,
+
+    /// Last season to backfill, the current one by default
+    #[arg(long)]
+    pub to: Option<i32>,
+
+    /// Session feeds to fetch, all of them by default
+    #[arg(long, value_delimiter = ',', value_parser = PossibleValuesParser::new(SESSION_FEEDS))]
+    pub feeds: Option<Vec<String>>,
+
+    /// Seconds to wait for the calendar of a season to be stored
+    #[arg(long, default_value_t = 300)]
+    pub timeout: u64,
+}
+
+/* ////////////////////////// */
+/* //// Backfill Command //// */
+/* ////////////////////////// */
+
+// Jobs are tracked by key in the 'jobs' table, so an interrupted backfill resumes where it stopped
+// Keys are shared with the API and the scheduler, the jobs they have in flight being waited for
+// Only the jobs failed or lost by the worker are sent again, the queued ones still being in the queue
+// Jobs never reported by the worker are given up on once expired, so the backfill always ends
+#[instrument(name = "[Command] Backfill", skip_all, err)]
+pub async fn run(
+    args: BackfillArgs,
+    db: &Pool<Postgres>,
+    rabbitmq: &lapin::Channel,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let time = std::time::Instant::now();
+
+    let to = args.to.unwrap_or(chrono::Utc::now().year());
+    if args.from > to {
+        return Err(format!("Invalid seasons range {}..{}", args.from, to).into());
+    }
+
+    // Feeds are sorted so the same selection always gives the same job keys
+    let feeds = args.feeds.map(|mut feeds| {
+        feeds.sort();
+        feeds.dedup();
+        feeds
+    });
+
+    let mut session_jobs = Vec::new();
+    for year in args.from..=to {
+        // Sessions are only known once the calendar of the season is stored
+        if let Err(err) =
+            backfill_meetings(db, rabbitmq, year, Duration::from_secs(args.timeout)).await
+        {
+            warn!(error = %err, "Skipping sessions of {}", year);
+            continue;
+        }
+
+        session_jobs.extend(backfill_sessions(db, rabbitmq, year, feeds.as_deref()).await?);
+    }
+
+    info!(
+        "{} session jobs tracked in {:?}, waiting for them to be processed",
+        session_jobs.len(),
+        time.elapsed()
+    );
+
+    let nb_failed = wait_for_sessions(db, &session_jobs).await?;
+    if nb_failed > 0 {
+        return Err(format!(
+            "{} sessions failed or were lost, run the backfill again to retry them",
+            nb_failed
+        )
+        .into());
+    }
+
+    info!("Backfill completed in {:?}", time.elapsed());
+
+    Ok(())
+}
+
+async fn backfill_meetings(
+    db: &Pool<Postgres>,
+    rabbitmq: &lapin::Channel,
+    year: i32,
+    timeout: Duration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let job = jobs::register_job(
+        db,
+        &metrics_one_queue::models::Meetings::job_key(year),
+        false,
+    )
+    .await?;
+    if job.is_done() {
+        debug!("Meetings of {} already fetched", year);
+        return Ok(());
+    }
+    let id = job.id;
+
+    if job.is_new {
+        // Meetings already stored are left out by the worker
+        let query = sqlx::query_scalar::<_, i32>(MEETING_KEYS_QUERY).bind(year);
+        debug!("SQL query - {}", query.sql());
+        let keys = query.fetch_all(db).await?;
+
+        let payload = metrics_one_queue::models::Meetings {
+            keys,
+            year,
+            job: Some(id),
+        };
+        publish(
+            db,
+            rabbitmq,
+            id,
+            metrics_one_queue::MEETINGS_QUEUE,
+            &payload,
+        )
+        .await?;
+    }
+
+    // Polled until the worker reports the job
+    let time = std::time::Instant::now();
+    loop {
+        match jobs::job_status(db, id).await?.as_deref() {
+            Some("done") => break,
+            Some("failed") => {
+                return Err(format!("Meetings of {} failed to be fetched", year).into());
+            }
+            _ if time.elapsed() > timeout => {
+                return Err(format!("Meetings of {} not fetched in {:?}", year, timeout).into());
+            }
+            _ => tokio::time::sleep(POLL_INTERVAL).await,
+        }
+    }
+
+    info!("Meetings of {} fetched in {:?}", year, time.elapsed());
+
+    Ok(())
+}
+
+// Returns the ids of the jobs to wait for, the ones in flight included
+async fn backfill_sessions(
+    db: &Pool<Postgres>,
+    rabbitmq: &lapin::Channel,
+    year: i32,
+    feeds: Option<&[String]>,
+) -> Result<Vec<i32>, Box<dyn std::error::Error>> {
+    let query = sqlx::query_as::<_, (i32, String)>(PAST_SESSIONS_QUERY).bind(year);
+    debug!("SQL query - {}", query.sql());
+    let sessions = query.fetch_all(db).await?;
+
+    let mut ids = Vec::new();
+    let mut nb_sent = 0;
+    for (key, path) in sessions {
+        let job_key = metrics_one_queue::models::Session::job_key(key, feeds);
+        let job = jobs::register_job(db, &job_key, false).await?;
+        if job.is_done() {
+            continue;
+        }
+
+        if job.is_new {
+            let payload = metrics_one_queue::models::Session {
+                key,
+                path,
+                feeds: feeds.map(|f| f.to_vec()),
+                job: Some(job.id),
+            };
+            publish(
+                db,
+                rabbitmq,
+                job.id,
+                metrics_one_queue::SESSIONS_QUEUE,
+                &payload,
+            )
+            .await?;
+            nb_sent += 1;
+        }
+
+        ids.push(job.id);
+    }
+
+    info!(
+        "{} session jobs of {} sent to the queue, {} already in flight",
+        nb_sent,
+        year,
+        ids.len() - nb_sent
+    );
+
+    Ok(ids)
+}
+
+// Returns the number of failed or lost jobs once none is left in the queue
+async fn wait_for_sessions(
+    db: &Pool<Postgres>,
+    ids: &[i32],
+) -> Result<i64, Box<dyn std::error::Error>> {
+    loop {
+        let query = sqlx::query_as::<_, (String, i64)>(JOBS_PROGRESS_QUERY)
+            .bind(ids)
+            .bind(jobs::JOB_TTL);
+        debug!("SQL query - {}", query.sql());
+        let progress = query.fetch_all(db).await?;
+
+        let count = |status: &str| {
+            progress
+                .iter()
+                .find(|(s, _)| s == status)
+                .map_or(0, |(_, n)| *n)
+        };
+
+        info!(
+            "{}/{} sessions fetched, {} failed, {} lost",
+            count("done"),
+            ids.len(),
+            count("failed"),
+            count("lost")
+        );
+
+        if count("queued") == 0 {
+            return Ok(count("failed") + count("lost"));
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+// A job that never reached the queue is marked as failed, to be sent again on the next run
+async fn publish<T>(
+    db: &Pool<Postgres>,
+    rabbitmq: &lapin::Channel,
+    id: i32,
+    queue: &str,
+    payload: &T,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    T: serde::Serialize,
+{
+    if let Err(err) = metrics_one_queue::publish(rabbitmq, queue, payload).await {
+        jobs::update_job(db, id, false, Some(err.to_string())).await?;
+        return Err(err);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+pub mod api_key;
+pub mod backfill;
+
+use clap::{Parser, Subcommand};
+use sqlx::{Pool, Postgres};
+
+// Without command, the API serves HTTP and gRPC requests
+#[derive(Parser)]
+#[command(version, about = "MetricsOne API")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Manage the API keys of the HTTP and gRPC services
+    ApiKey(api_key::ApiKeyArgs),
+
+    /// Send the fetch jobs of past seasons to the queue, skipping the ones already done
+    Backfill(backfill::BackfillArgs),
+}
+
+pub async fn run(
+    command: Command,
+    db: &Pool<Postgres>,
+    rabbitmq: &lapin::Channel,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        Command::ApiKey(args) => api_key::run(args, db).await,
+        Command::Backfill(args) => backfill::run(args, db, rabbitmq).await,
+    }
+}
@@ -1,3 +1,4 @@
+mod commands;
 mod models;
 mod services;
 mod settings;
@@ -5,24 +6,35 @@ mod settings;
 use std::sync::Arc;
 
 use actix_cors::Cors;
-use actix_web::{App, HttpServer, web::Data};
+use actix_web::{
+    App, HttpServer,
+    middleware::from_fn,
+    web::{self, Data},
+};
+use clap::Parser;
+use metrics_one_queue::models::LiveUpdate;
 use services::grpc::InsertServiceHandler;
 use settings::ENV;
 use sqlx::{Pool, Postgres, postgres::PgPoolOptions};
+use tokio::sync::broadcast;
 use tonic::transport::Server;
 use tracing::{debug, error, info, info_span};
 use tracing_actix_web::TracingLogger;
 
 use metrics_one_grpc::proto::insert_service_server::InsertServiceServer;
-use metrics_one_utils::utils;
+use metrics_one_utils::{tls, utils};
 
 pub struct AppState {
     db: Arc<Pool<Postgres>>,
     rabbitmq: Arc<lapin::Channel>,
+    live: broadcast::Sender<Arc<LiveUpdate>>,
+    rate_limits: Arc<dyn services::rate_limit::RateLimitStore>,
 }
 
 #[actix_web::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = commands::Cli::parse();
+
     dotenv::dotenv().ok();
 
     let _otel_guard =
@@ -71,11 +83,35 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             })?,
         );
 
+        metrics_one_queue::declare_queue(&channel, metrics_one_queue::SESSIONS_QUEUE).await?;
+        metrics_one_queue::declare_queue(&channel, metrics_one_queue::TRACK_MAPS_QUEUE).await?;
+
         info!("RabbitMQ consumer setup completed",);
 
         channel
     };
 
+    // One-off commands run without serving requests
+    if let Some(command) = cli.command {
+        return commands::run(command, &db_pool, &rabbitmq_channel).await;
+    }
+
+    // API keys are checked from memory, reloaded periodically to follow the changes
+    services::auth::init_api_keys(&db_pool)
+        .await
+        .inspect_err(|err| {
+            error!(error = ?err, "Failed to load API keys");
+        })?;
+    tokio::spawn(services::auth::refresh_api_keys(db_pool.clone()));
+
+    // Counters of the rate limits, shared by every HTTP worker
+    let rate_limits = services::rate_limit::from_settings(db_pool.clone());
+    tokio::spawn(services::rate_limit::purge_counters(rate_limits.clone()));
+
+    // Live updates are dispatched to the HTTP subscribers
+    let (live_sender, _) = broadcast::channel(services::live::LIVE_CAPACITY);
+    tokio::spawn(services::live::forward_updates(live_sender.clone()));
+
     // Get shutdown signals for gRPC and HTTP servers graceful shutdown
     let shutdown_signal = utils::get_shutdown_signals();
 
@@ -92,17 +128,35 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             db: db_pool.clone(),
         };
 
-        // Starting gRPC service
-        info!("gRPC service listening on http://{}", addr);
+        let router = Server::builder().add_service(InsertServiceServer::with_interceptor(
+            insert_service,
+            services::auth::require_ingest,
+        ));
 
-        tokio::spawn(
-            Server::builder()
-                .add_service(InsertServiceServer::with_interceptor(
-                    insert_service,
-                    metrics_one_grpc::interceptor::tracing::tracing_extractor,
+        // Starting gRPC service
+        match &ENV.server.grpc_tls {
+            Some(tls) => {
+                let ca = tls.ca.as_deref().filter(|ca| !ca.is_empty());
+                let config = tls::server_config(&tls.cert, &tls.key, ca).map_err(|err| {
+                    error!(error = %err, "Failed to load TLS certificates");
+                    err as Box<dyn std::error::Error>
+                })?;
+                let listener = tokio::net::TcpListener::bind(addr).await?;
+
+                info!(
+                    mtls = ca.is_some(),
+                    "gRPC service listening on https://{}", addr
+                );
+                tokio::spawn(router.serve_with_incoming_shutdown(
+                    tls::tls_incoming(listener, config),
+                    shutdown_signal,
                 ))
-                .serve_with_shutdown(addr, shutdown_signal),
-        )
+            }
+            None => {
+                info!("gRPC service listening on http://{}", addr);
+                tokio::spawn(router.serve_with_shutdown(addr, shutdown_signal))
+            }
+        }
     };
 
     // Starting HTTP service
@@ -117,22 +171,50 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 let cors = Cors::default()
                     .allowed_origin("http://localhost:3000")
                     .allowed_methods(vec!["GET", "POST", "OPTIONS"])
-                    .allowed_headers(vec!["Content-Type"])
+                    .allowed_headers(vec!["Content-Type", "Authorization"])
                     .supports_credentials();
 
                 App::new()
                     .app_data(Data::new(AppState {
                         db: db_pool.clone(),
                         rabbitmq: rabbitmq_channel.clone(),
+                        live: live_sender.clone(),
+                        rate_limits: rate_limits.clone(),
                     }))
+                    .wrap(from_fn(services::auth::require_read))
+                    .wrap(from_fn(services::rate_limit::limit_rate))
                     .wrap(cors)
                     .wrap(TracingLogger::default())
                     .service(services::http::fetch_drivers)
                     .service(services::http::fetch_driver_by_name)
                     .service(services::http::fetch_teams)
                     .service(services::http::fetch_team_by_name)
+                    .service(services::http::fetch_team_head_to_head)
                     .service(services::http::fetch_meetings)
+                    .service(services::http::fetch_track_map)
                     .service(services::http::fetch_sessions)
+                    .service(services::http::fetch_lap_chart)
+                    .service(services::http::fetch_gaps)
+                    .service(services::http::fetch_live)
+                    .service(services::http::fetch_bests)
+                    .service(services::http::fetch_speed_traps)
+                    .service(services::http::fetch_compare)
+                    .service(services::http::fetch_pit_stops)
+                    .service(services::http::fetch_race_control)
+                    .service(services::http::fetch_replay)
+                    .service(services::http::fetch_team_radio)
+                    .service(services::http::fetch_track_status)
+                    .service(services::http::fetch_weather)
+                    .service(
+                        web::scope("/admin")
+                            .wrap(from_fn(services::auth::require_admin))
+                            .service(services::http::admin_fetch_meetings)
+                            .service(services::http::admin_fetch_track_map)
+                            .service(services::http::admin_fetch_session)
+                            .service(services::http::admin_reingest_session)
+                            .service(services::http::admin_fetch_queues)
+                            .service(services::http::admin_requeue_dead_letters),
+                    )
             })
             .bind(addr.clone())
             .inspect_err(|err| {
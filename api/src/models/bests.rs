@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+// Times are in milliseconds
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BestTime {
+    pub driver_number: i32,
+    pub lap_number: i32,
+    pub time: i32,
+}
+
+// Ideal lap is the sum of best sectors, only given when all of them are known
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DriverBests {
+    pub driver_number: i32,
+    pub fastest_lap: Option<BestTime>,
+    pub best_sectors: [Option<BestTime>; 3],
+    pub ideal_lap: Option<i32>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SessionBests {
+    pub fastest_lap: Option<BestTime>,
+    pub best_sectors: [Option<BestTime>; 3],
+    pub ideal_lap: Option<i32>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Bests {
+    pub session: SessionBests,
+    pub drivers: Vec<DriverBests>,
+}
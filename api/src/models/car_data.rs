@@ -0,0 +1,44 @@
+use metrics_one_macros::SqlNames;
+use serde::{Deserialize, Serialize};
+use sqlx::{self, FromRow};
+
+// Samples of a lap, 'time' being the offset in milliseconds from the start of the lap
+// Lap time is only given when read along with the lap
+#[derive(Serialize, Deserialize, FromRow, SqlNames)]
+#[sql_names(table_name = "car_data_laps")]
+pub struct CarDataLap {
+    pub session_key: i32,
+    pub driver_number: i32,
+    pub lap_number: i32,
+    pub time: Vec<i32>,
+    pub speed: Vec<i32>,
+    pub throttle: Vec<i32>,
+    pub brake: Vec<i32>,
+    pub rpm: Vec<i32>,
+    pub gear: Vec<i32>,
+    pub drs: Vec<i32>,
+
+    #[sql_names(skip)]
+    #[sqlx(default)]
+    pub lap_time: Option<i32>,
+}
+
+// Traces resampled on the distance axis of a comparison
+// Delta is the cumulative time difference in milliseconds to the reference lap
+#[derive(Serialize, Deserialize)]
+pub struct LapTrace {
+    pub driver_number: i32,
+    pub lap_number: i32,
+    pub lap_time: Option<i32>,
+    pub speed: Vec<f64>,
+    pub throttle: Vec<f64>,
+    pub brake: Vec<f64>,
+    pub delta: Vec<f64>,
+}
+
+// Distances are in meters, the first lap being the reference one
+#[derive(Serialize, Deserialize)]
+pub struct LapComparison {
+    pub distance: Vec<f64>,
+    pub laps: Vec<LapTrace>,
+}
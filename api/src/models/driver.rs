@@ -10,7 +10,7 @@ pub struct Driver {
     first_name: String,
     last_name: String,
     url: String,
-    number: i32,
+    pub number: i32,
     year: i32,
 
     #[sql_names(skip)]
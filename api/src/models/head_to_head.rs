@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{self, FromRow};
+
+use super::Team;
+
+// Result of a driver in a session of the season, best lap time excludes deleted laps
+#[derive(Debug, FromRow)]
+pub struct DriverSessionResult {
+    pub session_key: i32,
+    pub session_name: String,
+    pub driver_number: i32,
+    pub position: Option<i32>,
+    pub retired: bool,
+    pub best_lap_time: Option<i32>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DriverSeason {
+    pub driver_number: i32,
+    pub points: i32,
+    pub races: i32,
+    pub dnfs: i32,
+}
+
+// Counts are given in the same order as 'drivers'
+// Qualifying gap is the average difference in milliseconds of the first driver to the second one
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Duel {
+    pub drivers: [i32; 2],
+    pub qualifying: [i32; 2],
+    pub race: [i32; 2],
+    pub qualifying_gap_avg: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct HeadToHead {
+    pub team: Team,
+    pub drivers: Vec<DriverSeason>,
+    pub duels: Vec<Duel>,
+}
@@ -0,0 +1,40 @@
+use metrics_one_macros::SqlNames;
+use serde::{Deserialize, Serialize};
+use sqlx::{self, FromRow};
+
+// Times are in milliseconds, 'time' being the offset from the start of the session stream
+// Speeds are in km/h
+#[derive(Serialize, Deserialize, FromRow, SqlNames)]
+#[sql_names(table_name = "laps")]
+pub struct Lap {
+    pub session_key: i32,
+    pub driver_number: i32,
+    pub lap_number: i32,
+    pub time: i32,
+    pub lap_time: Option<i32>,
+    pub sector_1: Option<i32>,
+    pub sector_2: Option<i32>,
+    pub sector_3: Option<i32>,
+    pub speed_i1: Option<i32>,
+    pub speed_i2: Option<i32>,
+    pub speed_fl: Option<i32>,
+    pub speed_st: Option<i32>,
+}
+
+// Positions are given by the order of line crossing on each lap
+#[derive(Serialize, Deserialize, FromRow)]
+pub struct LapPosition {
+    driver_number: i32,
+    lap_number: i32,
+    position: i32,
+}
+
+// Gaps are in milliseconds, the leader having no car ahead
+#[derive(Serialize, Deserialize, FromRow)]
+pub struct LapGap {
+    driver_number: i32,
+    lap_number: i32,
+    position: i32,
+    gap_to_leader: i32,
+    interval: Option<i32>,
+}
@@ -2,7 +2,7 @@ use metrics_one_macros::SqlNames;
 use serde::{Deserialize, Serialize};
 use sqlx::{self, FromRow, types::Json};
 
-use super::Session;
+use super::{MeetingsWeather, Session};
 
 #[derive(Serialize, Deserialize, FromRow, SqlNames)]
 #[sql_names(table_name = "meetings")]
@@ -18,4 +18,9 @@ pub struct Meeting {
     #[sqlx(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sessions: Option<Json<Vec<Session>>>,
+
+    #[sql_names(skip)]
+    #[sqlx(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weather: Option<Json<MeetingsWeather>>,
 }
@@ -1,11 +1,39 @@
+pub mod bests;
+pub mod car_data;
 pub mod driver;
+pub mod head_to_head;
 pub mod images;
+pub mod lap;
 pub mod meeting;
+pub mod pit_stop;
+pub mod position;
+pub mod race_control;
+pub mod replay;
 pub mod session;
+pub mod session_result;
+pub mod speed_trap;
 pub mod team;
+pub mod team_radio;
+pub mod track_map;
+pub mod track_status;
+pub mod weather;
 
+pub use bests::*;
+pub use car_data::*;
 pub use driver::*;
+pub use head_to_head::*;
 pub use images::*;
+pub use lap::*;
 pub use meeting::*;
+pub use pit_stop::*;
+pub use position::*;
+pub use race_control::*;
+pub use replay::*;
 pub use session::*;
+pub use session_result::*;
+pub use speed_trap::*;
 pub use team::*;
+pub use team_radio::*;
+pub use track_map::*;
+pub use track_status::*;
+pub use weather::*;
@@ -0,0 +1,16 @@
+use metrics_one_macros::SqlNames;
+use serde::{Deserialize, Serialize};
+use sqlx::{self, FromRow};
+
+// Times are offsets from the start of the session stream and durations are in milliseconds
+#[derive(Serialize, Deserialize, FromRow, SqlNames)]
+#[sql_names(table_name = "pit_stops")]
+pub struct PitStop {
+    session_key: i32,
+    driver_number: i32,
+    lap: i32,
+    in_time: Option<i32>,
+    out_time: Option<i32>,
+    lane_duration: Option<i32>,
+    stationary_duration: Option<i32>,
+}
@@ -0,0 +1,25 @@
+use metrics_one_macros::SqlNames;
+use serde::{Deserialize, Serialize};
+use sqlx::{self, FromRow};
+
+// Positions of a car during the session, coordinates being in decimeters
+// 'time' is the offset in milliseconds from the start of the session stream
+#[derive(Serialize, Deserialize, FromRow, SqlNames)]
+#[sql_names(table_name = "car_positions")]
+pub struct CarPositions {
+    pub session_key: i32,
+    pub driver_number: i32,
+    pub time: Vec<i32>,
+    pub x: Vec<i32>,
+    pub y: Vec<i32>,
+    pub z: Vec<i32>,
+}
+
+// Position of a car at a given time of the replay
+#[derive(Serialize, Deserialize)]
+pub struct ReplayPosition {
+    pub driver_number: i32,
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
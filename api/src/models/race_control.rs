@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use metrics_one_macros::SqlNames;
+use serde::{Deserialize, Serialize};
+use sqlx::{self, FromRow};
+
+#[derive(Serialize, Deserialize, FromRow, SqlNames)]
+#[sql_names(table_name = "race_control_messages")]
+pub struct RaceControlMessage {
+    session_key: i32,
+    date: DateTime<Utc>,
+    pub time: i32,
+    lap: Option<i32>,
+    category: String,
+    flag: Option<String>,
+    scope: Option<String>,
+    sector: Option<i32>,
+    driver_number: Option<i32>,
+    status: Option<String>,
+    mode: Option<String>,
+    message: String,
+    deleted_lap: Option<i32>,
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{self, FromRow};
+
+// A line of the timing tower as it was when the driver crossed the line
+// Times are in milliseconds, 'time' being the offset from the start of the session stream
+#[derive(Serialize, Deserialize, FromRow)]
+pub struct ReplayLap {
+    pub driver_number: i32,
+    pub lap_number: i32,
+    pub time: i32,
+    pub lap_time: Option<i32>,
+    pub position: i32,
+    pub gap_to_leader: i32,
+    pub interval: Option<i32>,
+}
+
+// An event sent to the client, serialized as a Server-Sent Event
+pub struct ReplayEvent {
+    pub time: i32,
+    pub kind: &'static str,
+    pub data: serde_json::Value,
+}
+
+impl ReplayEvent {
+    pub fn to_sse(&self) -> String {
+        format!("event: {}\ndata: {}\n\n", self.kind, self.data)
+    }
+}
@@ -1,16 +1,23 @@
 use chrono::{DateTime, Utc};
 use metrics_one_macros::SqlNames;
 use serde::{Deserialize, Serialize};
-use sqlx::{self, FromRow};
+use sqlx::{self, FromRow, types::Json};
+
+use super::SessionsWeather;
 
 #[derive(Serialize, Deserialize, FromRow, SqlNames)]
 #[sql_names(table_name = "sessions")]
 pub struct Session {
-    key: i32,
-    kind: String,
-    name: String,
-    start_date: DateTime<Utc>,
-    end_date: DateTime<Utc>,
-    path: String,
-    meeting_key: i32,
+    pub key: i32,
+    pub kind: String,
+    pub name: String,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    pub path: String,
+    pub meeting_key: i32,
+
+    #[sql_names(skip)]
+    #[sqlx(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weather: Option<Json<SessionsWeather>>,
 }
@@ -0,0 +1,15 @@
+use metrics_one_macros::SqlNames;
+use serde::{Deserialize, Serialize};
+use sqlx::{self, FromRow};
+
+// Final classification of a driver, as given by timing data at the end of the session
+// 'team_id' is the team the driver raced for in the session, unknown when its name isn't recognised
+#[derive(Serialize, Deserialize, FromRow, SqlNames)]
+#[sql_names(table_name = "session_results")]
+pub struct SessionResult {
+    pub session_key: i32,
+    pub driver_number: i32,
+    pub position: Option<i32>,
+    pub retired: bool,
+    pub team_id: Option<i32>,
+}
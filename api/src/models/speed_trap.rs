@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+// Speeds are in km/h
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TopSpeed {
+    pub driver_number: i32,
+    pub lap_number: i32,
+    pub speed: i32,
+}
+
+// Each leaderboard holds the top speed of every driver, fastest first
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SpeedTrapLeaderboards {
+    pub i1: Vec<TopSpeed>,
+    pub i2: Vec<TopSpeed>,
+    pub fl: Vec<TopSpeed>,
+    pub st: Vec<TopSpeed>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DriverTopSpeeds {
+    pub driver_number: i32,
+    pub i1: Option<i32>,
+    pub i2: Option<i32>,
+    pub fl: Option<i32>,
+    pub st: Option<i32>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SpeedTraps {
+    pub leaderboards: SpeedTrapLeaderboards,
+    pub drivers: Vec<DriverTopSpeeds>,
+}
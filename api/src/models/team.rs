@@ -15,7 +15,7 @@ pub struct Team {
     #[sql_names(skip)]
     #[sqlx(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
-    drivers: Option<Json<Vec<Driver>>>,
+    pub drivers: Option<Json<Vec<Driver>>>,
 
     #[sql_names(skip)]
     #[sqlx(default)]
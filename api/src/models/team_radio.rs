@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use metrics_one_macros::SqlNames;
+use serde::{Deserialize, Serialize};
+use sqlx::{self, FromRow};
+
+// 'path' is relative to the session path, 'url' is resolved when serving the captures
+#[derive(Serialize, Deserialize, FromRow, SqlNames)]
+#[sql_names(table_name = "team_radio")]
+pub struct TeamRadio {
+    pub session_key: i32,
+    pub date: DateTime<Utc>,
+    pub driver_number: i32,
+    pub path: String,
+
+    #[sql_names(skip)]
+    #[sqlx(default)]
+    pub url: String,
+}
@@ -0,0 +1,66 @@
+use metrics_one_macros::SqlNames;
+use serde::{Deserialize, Serialize};
+use sqlx::{self, FromRow};
+
+// Outline normalised between 0 and 1, after a rotation in degrees
+// 'sectors' holds the indexes of the points starting each of the 3 timing sectors
+#[derive(Serialize, Deserialize, FromRow, SqlNames)]
+#[sql_names(table_name = "track_maps")]
+pub struct TrackMap {
+    pub meeting_key: i32,
+    pub session_key: i32,
+    pub rotation: f64,
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    pub sectors: Vec<i32>,
+}
+
+// GeoJSON-like representation of a track map, coordinates being '[x, y]' pairs
+#[derive(Serialize, Deserialize)]
+pub struct TrackMapFeature {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub geometry: TrackMapGeometry,
+    pub properties: TrackMapProperties,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TrackMapGeometry {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub coordinates: Vec<[f64; 2]>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TrackMapProperties {
+    pub meeting_key: i32,
+    pub session_key: i32,
+    pub rotation: f64,
+    pub sectors: Vec<[f64; 2]>,
+}
+
+impl From<TrackMap> for TrackMapFeature {
+    fn from(map: TrackMap) -> Self {
+        let coordinates: Vec<[f64; 2]> = map.x.iter().zip(&map.y).map(|(x, y)| [*x, *y]).collect();
+
+        let sectors = map
+            .sectors
+            .iter()
+            .filter_map(|&i| coordinates.get(i as usize).copied())
+            .collect();
+
+        Self {
+            kind: String::from("Feature"),
+            geometry: TrackMapGeometry {
+                kind: String::from("LineString"),
+                coordinates,
+            },
+            properties: TrackMapProperties {
+                meeting_key: map.meeting_key,
+                session_key: map.session_key,
+                rotation: map.rotation,
+                sectors,
+            },
+        }
+    }
+}
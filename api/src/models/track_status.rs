@@ -0,0 +1,17 @@
+use metrics_one_macros::SqlNames;
+use serde::{Deserialize, Serialize};
+use sqlx::{self, FromRow};
+
+// Times are offsets in milliseconds from the start of the session stream
+// An interval without end is still ongoing at the end of the session
+#[derive(Serialize, Deserialize, FromRow, SqlNames)]
+#[sql_names(table_name = "track_status")]
+pub struct TrackStatus {
+    session_key: i32,
+    status: String,
+    message: String,
+    pub start_time: i32,
+    end_time: Option<i32>,
+    start_lap: Option<i32>,
+    end_lap: Option<i32>,
+}
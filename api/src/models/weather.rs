@@ -0,0 +1,46 @@
+use metrics_one_macros::SqlNames;
+use serde::{Deserialize, Serialize};
+use sqlx::{self, FromRow};
+
+// Time is the offset in milliseconds from the start of the session stream
+#[derive(Serialize, Deserialize, FromRow, SqlNames)]
+#[sql_names(table_name = "weather_samples")]
+pub struct WeatherSample {
+    session_key: i32,
+    time: i32,
+    air_temp: f64,
+    track_temp: f64,
+    humidity: f64,
+    pressure: f64,
+    wind_speed: f64,
+    wind_direction: i32,
+    rainfall: bool,
+}
+
+#[derive(Serialize, Deserialize, FromRow, SqlNames)]
+#[sql_names(table_name = "sessions_weather")]
+pub struct SessionsWeather {
+    air_temp_min: f64,
+    air_temp_max: f64,
+    air_temp_avg: f64,
+    track_temp_min: f64,
+    track_temp_max: f64,
+    track_temp_avg: f64,
+    humidity_avg: f64,
+    wind_speed_avg: f64,
+    rainfall: bool,
+}
+
+#[derive(Serialize, Deserialize, FromRow, SqlNames)]
+#[sql_names(table_name = "meetings_weather")]
+pub struct MeetingsWeather {
+    air_temp_min: f64,
+    air_temp_max: f64,
+    air_temp_avg: f64,
+    track_temp_min: f64,
+    track_temp_max: f64,
+    track_temp_avg: f64,
+    humidity_avg: f64,
+    wind_speed_avg: f64,
+    rainfall: bool,
+}
@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+
+use crate::models::{BestTime, Bests, DriverBests, Lap, SessionBests};
+
+/* /////////////////// */
+/* //// Analytics //// */
+/* /////////////////// */
+
+// Compute fastest laps and best sectors per driver and for the whole session
+// Laps are expected to be valid ones, deleted laps being filtered out beforehand
+pub fn compute(laps: &[Lap]) -> Bests {
+    let mut drivers: BTreeMap<i32, DriverBests> = BTreeMap::new();
+
+    for lap in laps {
+        let driver = drivers
+            .entry(lap.driver_number)
+            .or_insert_with(|| DriverBests {
+                driver_number: lap.driver_number,
+                ..Default::default()
+            });
+
+        keep_best(&mut driver.fastest_lap, lap, lap.lap_time);
+
+        let sectors = [lap.sector_1, lap.sector_2, lap.sector_3];
+        for (best, sector) in driver.best_sectors.iter_mut().zip(sectors) {
+            keep_best(best, lap, sector);
+        }
+    }
+
+    let mut session = SessionBests::default();
+    for driver in drivers.values_mut() {
+        driver.ideal_lap = ideal_lap(&driver.best_sectors);
+
+        session.fastest_lap = faster(session.fastest_lap, driver.fastest_lap);
+        for (best, sector) in session.best_sectors.iter_mut().zip(driver.best_sectors) {
+            *best = faster(*best, sector);
+        }
+    }
+    session.ideal_lap = ideal_lap(&session.best_sectors);
+
+    // Drivers are ranked by fastest lap, the ones without lap time being last
+    let mut drivers: Vec<DriverBests> = drivers.into_values().collect();
+    drivers.sort_by_key(|d| (d.fastest_lap.is_none(), d.fastest_lap.map(|l| l.time)));
+
+    Bests { session, drivers }
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+// On equal times, the first one set is kept
+fn keep_best(best: &mut Option<BestTime>, lap: &Lap, time: Option<i32>) {
+    let Some(time) = time else {
+        return;
+    };
+
+    if best.is_none_or(|b| time < b.time) {
+        *best = Some(BestTime {
+            driver_number: lap.driver_number,
+            lap_number: lap.lap_number,
+            time,
+        });
+    }
+}
+
+fn faster(a: Option<BestTime>, b: Option<BestTime>) -> Option<BestTime> {
+    match (a, b) {
+        (Some(a), Some(b)) if b.time < a.time => Some(b),
+        (Some(a), _) => Some(a),
+        (None, b) => b,
+    }
+}
+
+fn ideal_lap(sectors: &[Option<BestTime>; 3]) -> Option<i32> {
+    sectors.iter().map(|s| s.map(|s| s.time)).sum()
+}
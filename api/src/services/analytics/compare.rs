@@ -0,0 +1,172 @@
+use crate::models::{CarDataLap, LapComparison, LapTrace};
+
+/* /////////////////// */
+/* //// Analytics //// */
+/* /////////////////// */
+
+// Align laps on a common distance axis, sampled every 'step' meters
+// The axis stops at the shortest lap, the first lap being the reference for deltas
+pub fn compute(laps: &[CarDataLap], step: f64) -> LapComparison {
+    let distances: Vec<Vec<f64>> = laps.iter().map(integrate_distance).collect();
+
+    let total = distances
+        .iter()
+        .map(|d| d.last().copied().unwrap_or_default())
+        .fold(f64::INFINITY, f64::min);
+
+    let distance: Vec<f64> = if total.is_finite() && step > 0.0 {
+        (0..=(total / step) as usize)
+            .map(|i| i as f64 * step)
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let times: Vec<Vec<f64>> = laps
+        .iter()
+        .zip(&distances)
+        .map(|(lap, d)| resample(d, &lap.time, &distance))
+        .collect();
+
+    let laps = laps
+        .iter()
+        .zip(&distances)
+        .zip(&times)
+        .map(|((lap, d), time)| LapTrace {
+            driver_number: lap.driver_number,
+            lap_number: lap.lap_number,
+            lap_time: lap.lap_time,
+            speed: resample(d, &lap.speed, &distance),
+            throttle: resample(d, &lap.throttle, &distance),
+            brake: resample(d, &lap.brake, &distance),
+            delta: time.iter().zip(&times[0]).map(|(t, r)| t - r).collect(),
+        })
+        .collect();
+
+    LapComparison { distance, laps }
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+// Distance in meters travelled at each sample, from speeds in km/h and times in milliseconds
+fn integrate_distance(lap: &CarDataLap) -> Vec<f64> {
+    let mut distance = Vec::with_capacity(lap.time.len());
+    let mut total = 0.0;
+
+    for i in 0..lap.time.len().min(lap.speed.len()) {
+        if i > 0 {
+            let speed = (lap.speed[i - 1] + lap.speed[i]) as f64 / 2.0 / 3.6;
+            total += speed * (lap.time[i] - lap.time[i - 1]) as f64 / 1000.0;
+        }
+        distance.push(total);
+    }
+
+    distance
+}
+
+// Linear interpolation of 'values' at each point of 'axis', 'xs' being sorted
+fn resample(xs: &[f64], values: &[i32], axis: &[f64]) -> Vec<f64> {
+    let len = xs.len().min(values.len());
+    if len == 0 {
+        return Vec::new();
+    }
+
+    axis.iter()
+        .map(|&x| {
+            let i = xs[..len].partition_point(|&v| v < x);
+            if i == 0 {
+                return values[0] as f64;
+            }
+            if i >= len {
+                return values[len - 1] as f64;
+            }
+
+            let (x0, x1) = (xs[i - 1], xs[i]);
+            let (y0, y1) = (values[i - 1] as f64, values[i] as f64);
+            if x1 > x0 {
+                y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+            } else {
+                y1
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compute, resample};
+    use crate::models::CarDataLap;
+
+    // Lap driven at a constant speed, sampled every second
+    fn lap(driver_number: i32, speed: i32, seconds: i32) -> CarDataLap {
+        let samples = (seconds + 1) as usize;
+        CarDataLap {
+            session_key: 9472,
+            driver_number,
+            lap_number: 1,
+            time: (0..=seconds).map(|s| s * 1000).collect(),
+            speed: vec![speed; samples],
+            throttle: vec![100; samples],
+            brake: vec![0; samples],
+            rpm: vec![11000; samples],
+            gear: vec![7; samples],
+            drs: vec![0; samples],
+            lap_time: Some(seconds * 1000),
+        }
+    }
+
+    #[test]
+    fn resample_holds_values_past_the_ends() {
+        let xs = [0.0, 10.0, 20.0];
+        let values = [100, 200, 300];
+
+        assert_eq!(
+            resample(&xs, &values, &[-5.0, 0.0, 5.0, 20.0, 25.0]),
+            vec![100.0, 100.0, 150.0, 300.0, 300.0]
+        );
+        assert!(resample(&[], &[], &[0.0]).is_empty());
+    }
+
+    #[test]
+    fn resample_handles_duplicate_and_extra_samples() {
+        // Samples at the same distance don't divide by zero
+        let xs = [0.0, 10.0, 10.0, 20.0];
+        assert_eq!(
+            resample(&xs, &[0, 10, 20, 30], &[10.0, 15.0]),
+            vec![10.0, 25.0]
+        );
+
+        // Distances without a value are ignored
+        assert_eq!(resample(&xs, &[0, 10], &[15.0]), vec![10.0]);
+    }
+
+    #[test]
+    fn laps_are_compared_on_the_shortest_one() {
+        // 100 meters at 10 m/s, then 120 meters at 20 m/s
+        let comparison = compute(&[lap(1, 36, 10), lap(44, 72, 6)], 50.0);
+
+        assert_eq!(comparison.distance, vec![0.0, 50.0, 100.0]);
+
+        let [reference, other] = comparison.laps.as_slice() else {
+            panic!("Expected two laps, got {}", comparison.laps.len());
+        };
+        assert_eq!(reference.delta, vec![0.0, 0.0, 0.0]);
+        assert_eq!(reference.speed, vec![36.0, 36.0, 36.0]);
+
+        // Twice as fast, the second lap gains half of the reference time
+        assert_eq!(other.driver_number, 44);
+        assert_eq!(other.delta, vec![0.0, -2500.0, -5000.0]);
+        assert_eq!(other.speed, vec![72.0, 72.0, 72.0]);
+    }
+
+    #[test]
+    fn invalid_step_gives_no_axis() {
+        let comparison = compute(&[lap(1, 36, 10)], 0.0);
+
+        assert!(comparison.distance.is_empty());
+        assert!(comparison.laps[0].delta.is_empty());
+        assert!(compute(&[], 10.0).distance.is_empty());
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::BTreeMap;
+
+use crate::models::{DriverSeason, DriverSessionResult, Duel};
+
+const RACE_POINTS: [i32; 10] = [25, 18, 15, 12, 10, 8, 6, 4, 2, 1];
+const SPRINT_POINTS: [i32; 8] = [8, 7, 6, 5, 4, 3, 2, 1];
+
+/* /////////////////// */
+/* //// Analytics //// */
+/* /////////////////// */
+
+// Compute season totals of each driver and head-to-head duels of every pair of drivers
+// Only 'Qualifying', 'Sprint' and 'Race' sessions are taken into account
+pub fn compute(
+    driver_numbers: &[i32],
+    results: &[DriverSessionResult],
+) -> (Vec<DriverSeason>, Vec<Duel>) {
+    // Results are grouped by session to compare drivers on the same session
+    let mut sessions: BTreeMap<i32, BTreeMap<i32, &DriverSessionResult>> = BTreeMap::new();
+    for result in results {
+        sessions
+            .entry(result.session_key)
+            .or_default()
+            .insert(result.driver_number, result);
+    }
+
+    let drivers = driver_numbers
+        .iter()
+        .map(|&driver_number| {
+            let mut season = DriverSeason {
+                driver_number,
+                ..Default::default()
+            };
+
+            for result in results.iter().filter(|r| r.driver_number == driver_number) {
+                season.points += points(result);
+
+                if result.session_name == "Race" {
+                    season.races += 1;
+                    season.dnfs += result.retired as i32;
+                }
+            }
+
+            season
+        })
+        .collect();
+
+    let mut duels = Vec::new();
+    for (i, &a) in driver_numbers.iter().enumerate() {
+        for &b in &driver_numbers[i + 1..] {
+            duels.push(duel(a, b, &sessions));
+        }
+    }
+
+    (drivers, duels)
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+// Fastest lap bonus point isn't taken into account
+fn points(result: &DriverSessionResult) -> i32 {
+    let table: &[i32] = match result.session_name.as_str() {
+        "Race" => &RACE_POINTS,
+        "Sprint" => &SPRINT_POINTS,
+        _ => return 0,
+    };
+
+    match result.position {
+        Some(position) if !result.retired && position > 0 => table
+            .get(position as usize - 1)
+            .copied()
+            .unwrap_or_default(),
+        _ => 0,
+    }
+}
+
+fn duel(a: i32, b: i32, sessions: &BTreeMap<i32, BTreeMap<i32, &DriverSessionResult>>) -> Duel {
+    let mut duel = Duel {
+        drivers: [a, b],
+        ..Default::default()
+    };
+    let mut gaps: Vec<i32> = Vec::new();
+
+    for results in sessions.values() {
+        let (Some(ra), Some(rb)) = (results.get(&a), results.get(&b)) else {
+            continue;
+        };
+
+        match ra.session_name.as_str() {
+            "Qualifying" => {
+                if let (Some(pa), Some(pb)) = (ra.position, rb.position) {
+                    duel.qualifying[(pb < pa) as usize] += 1;
+                }
+
+                if let (Some(ta), Some(tb)) = (ra.best_lap_time, rb.best_lap_time) {
+                    gaps.push(ta - tb);
+                }
+            }
+            // A retired driver is beaten by a finisher, duels with both retired are ignored
+            "Race" if !(ra.retired && rb.retired) => {
+                let key = |r: &DriverSessionResult| (r.retired, r.position.unwrap_or(i32::MAX));
+                if key(ra) != key(rb) {
+                    duel.race[(key(rb) < key(ra)) as usize] += 1;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    if !gaps.is_empty() {
+        duel.qualifying_gap_avg =
+            Some(gaps.iter().map(|&g| g as f64).sum::<f64>() / gaps.len() as f64);
+    }
+
+    duel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compute;
+    use crate::models::DriverSessionResult;
+
+    fn result(
+        session_key: i32,
+        session_name: &str,
+        driver_number: i32,
+        position: Option<i32>,
+        retired: bool,
+        best_lap_time: Option<i32>,
+    ) -> DriverSessionResult {
+        DriverSessionResult {
+            session_key,
+            session_name: session_name.to_string(),
+            driver_number,
+            position,
+            retired,
+            best_lap_time,
+        }
+    }
+
+    #[test]
+    fn points_races_and_dnfs_are_summed() {
+        let results = [
+            result(1, "Sprint", 1, Some(1), false, None),
+            result(1, "Sprint", 4, Some(9), false, None),
+            result(2, "Race", 1, Some(2), false, None),
+            result(2, "Race", 4, Some(11), false, None),
+            // Position is kept for retired drivers but doesn't score
+            result(3, "Race", 1, Some(3), true, None),
+            result(3, "Race", 4, Some(10), false, None),
+            result(4, "Practice 1", 1, Some(1), false, None),
+        ];
+
+        let (drivers, _) = compute(&[1, 4], &results);
+
+        assert_eq!(drivers[0].driver_number, 1);
+        assert_eq!(
+            (drivers[0].points, drivers[0].races, drivers[0].dnfs),
+            (8 + 18, 2, 1)
+        );
+        assert_eq!(drivers[1].driver_number, 4);
+        assert_eq!(
+            (drivers[1].points, drivers[1].races, drivers[1].dnfs),
+            (1, 2, 0)
+        );
+    }
+
+    #[test]
+    fn qualifying_duels_count_positions_and_average_gaps() {
+        let results = [
+            result(1, "Qualifying", 1, Some(1), false, Some(80_000)),
+            result(1, "Qualifying", 4, Some(2), false, Some(80_300)),
+            result(2, "Qualifying", 1, Some(5), false, Some(81_000)),
+            result(2, "Qualifying", 4, Some(3), false, Some(80_900)),
+            // Sessions without both drivers are ignored
+            result(3, "Qualifying", 1, Some(1), false, Some(79_000)),
+        ];
+
+        let (_, duels) = compute(&[1, 4], &results);
+
+        assert_eq!(duels.len(), 1);
+        assert_eq!(duels[0].drivers, [1, 4]);
+        assert_eq!(duels[0].qualifying, [1, 1]);
+        assert_eq!(duels[0].qualifying_gap_avg, Some(-100.0));
+    }
+
+    #[test]
+    fn retired_drivers_lose_race_duels() {
+        let results = [
+            result(1, "Race", 1, Some(2), false, None),
+            result(1, "Race", 4, Some(1), false, None),
+            result(2, "Race", 1, Some(1), true, None),
+            result(2, "Race", 4, Some(15), false, None),
+            // Duels with both drivers retired are ignored
+            result(3, "Race", 1, Some(19), true, None),
+            result(3, "Race", 4, Some(20), true, None),
+        ];
+
+        let (_, duels) = compute(&[1, 4, 16], &results);
+
+        assert_eq!(duels.len(), 3);
+        assert_eq!(duels[0].race, [0, 2]);
+        assert_eq!(duels[0].qualifying_gap_avg, None);
+        // Driver without results has empty duels
+        assert_eq!(duels[1].drivers, [1, 16]);
+        assert_eq!(duels[1].race, [0, 0]);
+    }
+}
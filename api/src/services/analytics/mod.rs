@@ -0,0 +1,4 @@
+pub mod bests;
+pub mod compare;
+pub mod head_to_head;
+pub mod speed_traps;
@@ -0,0 +1,79 @@
+use std::collections::BTreeMap;
+
+use crate::models::{DriverTopSpeeds, Lap, SpeedTrapLeaderboards, SpeedTraps, TopSpeed};
+
+/* /////////////////// */
+/* //// Analytics //// */
+/* /////////////////// */
+
+// Compute the top speed of each driver at every speed trap
+pub fn compute(laps: &[Lap]) -> SpeedTraps {
+    let leaderboards = SpeedTrapLeaderboards {
+        i1: leaderboard(laps, |l| l.speed_i1),
+        i2: leaderboard(laps, |l| l.speed_i2),
+        fl: leaderboard(laps, |l| l.speed_fl),
+        st: leaderboard(laps, |l| l.speed_st),
+    };
+
+    let mut drivers: BTreeMap<i32, DriverTopSpeeds> = BTreeMap::new();
+    let traps = [
+        &leaderboards.i1,
+        &leaderboards.i2,
+        &leaderboards.fl,
+        &leaderboards.st,
+    ];
+
+    for (i, trap) in traps.into_iter().enumerate() {
+        for top in trap {
+            let driver = drivers
+                .entry(top.driver_number)
+                .or_insert_with(|| DriverTopSpeeds {
+                    driver_number: top.driver_number,
+                    ..Default::default()
+                });
+
+            let speed = match i {
+                0 => &mut driver.i1,
+                1 => &mut driver.i2,
+                2 => &mut driver.fl,
+                _ => &mut driver.st,
+            };
+            *speed = Some(top.speed);
+        }
+    }
+
+    SpeedTraps {
+        leaderboards,
+        drivers: drivers.into_values().collect(),
+    }
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+// On equal speeds, the first lap is kept
+fn leaderboard(laps: &[Lap], speed_of: impl Fn(&Lap) -> Option<i32>) -> Vec<TopSpeed> {
+    let mut tops: BTreeMap<i32, TopSpeed> = BTreeMap::new();
+
+    for lap in laps {
+        let Some(speed) = speed_of(lap) else {
+            continue;
+        };
+
+        let top = tops.entry(lap.driver_number).or_insert(TopSpeed {
+            driver_number: lap.driver_number,
+            lap_number: lap.lap_number,
+            speed,
+        });
+
+        if speed > top.speed {
+            top.lap_number = lap.lap_number;
+            top.speed = speed;
+        }
+    }
+
+    let mut tops: Vec<TopSpeed> = tops.into_values().collect();
+    tops.sort_by_key(|t| std::cmp::Reverse(t.speed));
+    tops
+}
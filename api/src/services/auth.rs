@@ -0,0 +1,267 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::header,
+    middleware::Next,
+};
+use once_cell::sync::Lazy;
+use opentelemetry::{KeyValue, global, metrics::Counter};
+use sha2::{Digest, Sha256};
+use sqlx::{Execute, Pool, Postgres};
+use tracing::{debug, error, info, warn};
+
+use crate::settings::ENV;
+
+const API_KEYS_QUERY: &str = "\
+    SELECT name, hash, scopes \
+    FROM api_keys \
+    WHERE revoked_at IS NULL";
+
+// Keys created or revoked are taken into account after this interval at most
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scope {
+    Read,
+    Admin,
+    Ingest,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 3] = [Scope::Read, Scope::Admin, Scope::Ingest];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::Admin => "admin",
+            Scope::Ingest => "ingest",
+        }
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct ApiKey {
+    pub name: String,
+    pub hash: String,
+    pub scopes: Vec<String>,
+}
+
+impl ApiKey {
+    // Admin keys are allowed to read too
+    pub fn allows(&self, scope: Scope) -> bool {
+        self.scopes
+            .iter()
+            .any(|s| s == scope.as_str() || (scope == Scope::Read && s == Scope::Admin.as_str()))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum AuthError {
+    Missing,
+    Invalid,
+    Forbidden,
+}
+
+impl AuthError {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AuthError::Missing => "missing",
+            AuthError::Invalid => "invalid",
+            AuthError::Forbidden => "forbidden",
+        }
+    }
+}
+
+/* /////////////////////// */
+/* //// API Key Store //// */
+/* /////////////////////// */
+
+// Keys are kept in memory, as the gRPC interceptor can't query the database
+// They are indexed by hash, the key itself never being stored
+#[derive(Default)]
+pub struct ApiKeyStore {
+    keys: RwLock<HashMap<String, Arc<ApiKey>>>,
+}
+
+pub static API_KEYS: Lazy<ApiKeyStore> = Lazy::new(ApiKeyStore::default);
+
+// Requests authenticated per key, with the scope asked and the outcome
+static REQUESTS_COUNTER: Lazy<Counter<u64>> = Lazy::new(|| {
+    global::meter("metrics-one-api")
+        .u64_counter("m1.api_key.requests")
+        .build()
+});
+
+impl ApiKeyStore {
+    pub async fn load(&self, db: &Pool<Postgres>) -> Result<usize, sqlx::Error> {
+        let query = sqlx::query_as::<_, ApiKey>(API_KEYS_QUERY);
+        debug!("SQL query - {}", query.sql());
+
+        let keys: HashMap<String, Arc<ApiKey>> = query
+            .fetch_all(db)
+            .await?
+            .into_iter()
+            .map(|key| (key.hash.clone(), Arc::new(key)))
+            .collect();
+        let nb_keys = keys.len();
+
+        // A poisoned lock only means a writer panicked, the map itself is still valid
+        *self.keys.write().unwrap_or_else(|err| err.into_inner()) = keys;
+
+        Ok(nb_keys)
+    }
+
+    pub fn find(&self, key: &str) -> Option<Arc<ApiKey>> {
+        self.keys
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(&hash_key(key))
+            .cloned()
+    }
+
+    // Check the key is known and allowed the scope, counting the request for the key
+    pub fn authenticate(
+        &self,
+        key: Option<&str>,
+        scope: Scope,
+        transport: &'static str,
+    ) -> Result<Arc<ApiKey>, AuthError> {
+        let result = match key {
+            None => Err(AuthError::Missing),
+            Some(key) => self.find(key).ok_or(AuthError::Invalid).and_then(|key| {
+                if key.allows(scope) {
+                    Ok(key)
+                } else {
+                    Err(AuthError::Forbidden)
+                }
+            }),
+        };
+
+        let name = result
+            .as_ref()
+            .map_or("anonymous".to_string(), |k| k.name.clone());
+        let outcome = result
+            .as_ref()
+            .err()
+            .map_or("authorized", |err| err.as_str());
+        REQUESTS_COUNTER.add(
+            1,
+            &[
+                KeyValue::new("api_key.name", name),
+                KeyValue::new("auth.scope", scope.as_str()),
+                KeyValue::new("auth.result", outcome),
+                KeyValue::new("transport", transport),
+            ],
+        );
+
+        result
+    }
+}
+
+// Loaded once before serving requests, so they aren't refused while the keys are loading
+pub async fn init_api_keys(db: &Pool<Postgres>) -> Result<(), sqlx::Error> {
+    let nb_keys = API_KEYS.load(db).await?;
+    info!("{} API keys loaded", nb_keys);
+
+    Ok(())
+}
+
+// Reload the keys periodically for as long as the server runs
+pub async fn refresh_api_keys(db: Arc<Pool<Postgres>>) {
+    loop {
+        tokio::time::sleep(REFRESH_INTERVAL).await;
+
+        match API_KEYS.load(db.as_ref()).await {
+            Ok(nb_keys) => debug!("{} API keys loaded", nb_keys),
+            Err(err) => error!(error = ?err, "Failed to load API keys"),
+        }
+    }
+}
+
+// Keys are random, so a single round of SHA-256 is enough to not store them in clear
+pub fn hash_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+/* ///////////////////////// */
+/* //// HTTP Middleware //// */
+/* ///////////////////////// */
+
+// Requests need a key with the 'read' scope, only when required by the settings
+pub async fn require_read(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    if ENV.require_read_key {
+        authorize(&req, Scope::Read)?;
+    }
+
+    next.call(req).await
+}
+
+pub async fn require_admin(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    authorize(&req, Scope::Admin)?;
+
+    next.call(req).await
+}
+
+// Keys are sent as 'Authorization: Bearer {key}'
+pub fn bearer_token(req: &ServiceRequest) -> Option<&str> {
+    req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+}
+
+fn authorize(req: &ServiceRequest, scope: Scope) -> Result<(), actix_web::Error> {
+    match API_KEYS.authenticate(bearer_token(req), scope, "http") {
+        Ok(key) => {
+            debug!(api_key = key.name, "Request authorized");
+            Ok(())
+        }
+        Err(AuthError::Forbidden) => {
+            warn!(
+                path = req.path(),
+                scope = scope.as_str(),
+                "Forbidden request"
+            );
+            Err(actix_web::error::ErrorForbidden("Forbidden"))
+        }
+        Err(_) => {
+            warn!(path = req.path(), "Unauthorized request");
+            Err(actix_web::error::ErrorUnauthorized("Unauthorized"))
+        }
+    }
+}
+
+/* ////////////////////////// */
+/* //// gRPC Interceptor //// */
+/* ////////////////////////// */
+
+// Writes need a key with the 'ingest' scope, the trace context being extracted beforehand
+#[allow(clippy::result_large_err)] // Signature of tonic interceptors
+pub fn require_ingest(request: tonic::Request<()>) -> Result<tonic::Request<()>, tonic::Status> {
+    let request = metrics_one_grpc::interceptor::tracing::tracing_extractor(request)?;
+    let key = metrics_one_grpc::interceptor::auth::bearer_token(request.metadata());
+
+    match API_KEYS.authenticate(key, Scope::Ingest, "grpc") {
+        Ok(_) => Ok(request),
+        Err(AuthError::Forbidden) => {
+            warn!("Forbidden gRPC request");
+            Err(tonic::Status::permission_denied("Forbidden"))
+        }
+        Err(_) => {
+            warn!("Unauthenticated gRPC request");
+            Err(tonic::Status::unauthenticated("Unauthenticated"))
+        }
+    }
+}
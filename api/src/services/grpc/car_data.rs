@@ -0,0 +1,91 @@
+use metrics_one_grpc::proto;
+use opentelemetry::global;
+use sqlx::Execute;
+use tracing::{Span, debug, error, info, instrument, trace};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::models::CarDataLap;
+use crate::services::query_preparer::{SqlType, insert::InsertQuery};
+
+use super::InsertServiceHandler;
+
+/* /////////////////////// */
+/* //// gRPC Handlers //// */
+/* /////////////////////// */
+
+#[instrument(name = "gRPC car_data.insert", skip_all)]
+pub async fn insert(
+    handler: &InsertServiceHandler,
+    request: tonic::Request<proto::InsertCarDataRequest>,
+) -> Result<tonic::Response<proto::InsertCarDataResponse>, tonic::Status> {
+    // Get Trace context from request metadata
+    let parent_cx = global::get_text_map_propagator(|propagator| {
+        propagator.extract(
+            &metrics_one_grpc::interceptor::tracing::MetadataMapExtractor(request.metadata()),
+        )
+    });
+    Span::current().set_parent(parent_cx);
+
+    let session_key = request.get_ref().session_key;
+    let driver_number = request.get_ref().driver_number;
+    let laps = request.into_inner().laps;
+
+    debug!("Request received with {} insertions", laps.len());
+    let time = std::time::Instant::now();
+
+    let response = proto::InsertCarDataResponse {};
+
+    // If no laps, we do nothing and return an 'ok' response
+    if laps.is_empty() {
+        return Ok(tonic::Response::new(response));
+    }
+
+    let nb_laps = laps.len();
+
+    // Prepare query
+    let mut query = InsertQuery::new(CarDataLap::SQL_TABLE, Vec::from(CarDataLap::SQL_FIELDS));
+    query.ignore_conflicts();
+
+    for l in laps.into_iter() {
+        // Order should be the same as 'SQL_FIELDS'
+        let values = vec![
+            SqlType::Int(session_key),
+            SqlType::Int(driver_number),
+            SqlType::Int(l.lap_number),
+            SqlType::IntArray(l.time),
+            SqlType::IntArray(l.speed),
+            SqlType::IntArray(l.throttle),
+            SqlType::IntArray(l.brake),
+            SqlType::IntArray(l.rpm),
+            SqlType::IntArray(l.gear),
+            SqlType::IntArray(l.drs),
+        ];
+
+        if let Err(err) = query.add_values(values) {
+            let message = "Failed to prepare 'car_data_laps' query";
+            error!(error = ?err, message);
+            return Err(tonic::Status::internal(message));
+        }
+    }
+
+    let query = query.build();
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    if let Err(err) = query.execute(handler.db.as_ref()).await {
+        let message = "Failed to process the SQL request";
+        error!(error = ?err, message);
+        return Err(tonic::Status::internal(message));
+    }
+
+    info!(
+        "Inserted {} laps of car data of driver {} for session {} successfully in {:?}",
+        nb_laps,
+        driver_number,
+        session_key,
+        time.elapsed()
+    );
+
+    Ok(tonic::Response::new(response))
+}
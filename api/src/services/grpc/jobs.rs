@@ -0,0 +1,85 @@
+use metrics_one_grpc::proto;
+use opentelemetry::global;
+use tracing::{Span, debug, error, info, instrument};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::services::jobs;
+
+use super::InsertServiceHandler;
+
+/* /////////////////////// */
+/* //// gRPC Handlers //// */
+/* /////////////////////// */
+
+#[instrument(name = "gRPC jobs.update", skip_all)]
+pub async fn update(
+    handler: &InsertServiceHandler,
+    request: tonic::Request<proto::UpdateJobRequest>,
+) -> Result<tonic::Response<proto::UpdateJobResponse>, tonic::Status> {
+    // Get Trace context from request metadata
+    let parent_cx = global::get_text_map_propagator(|propagator| {
+        propagator.extract(
+            &metrics_one_grpc::interceptor::tracing::MetadataMapExtractor(request.metadata()),
+        )
+    });
+    Span::current().set_parent(parent_cx);
+
+    let job = request.into_inner();
+
+    debug!("Request received for job {}", job.id);
+
+    match jobs::update_job(handler.db.as_ref(), job.id, job.done, job.error).await {
+        Ok(true) => {
+            info!("Job {} updated successfully", job.id);
+            Ok(tonic::Response::new(proto::UpdateJobResponse {}))
+        }
+        Ok(false) => {
+            let message = "Unknown job";
+            error!(job = job.id, message);
+            Err(tonic::Status::not_found(message))
+        }
+        Err(err) => {
+            let message = "Failed to process the SQL request";
+            error!(error = ?err, message);
+            Err(tonic::Status::internal(message))
+        }
+    }
+}
+
+// Jobs produced by the worker are registered like the API ones, to be coalesced with them
+#[instrument(name = "gRPC jobs.register", skip_all)]
+pub async fn register(
+    handler: &InsertServiceHandler,
+    request: tonic::Request<proto::RegisterJobRequest>,
+) -> Result<tonic::Response<proto::RegisterJobResponse>, tonic::Status> {
+    // Get Trace context from request metadata
+    let parent_cx = global::get_text_map_propagator(|propagator| {
+        propagator.extract(
+            &metrics_one_grpc::interceptor::tracing::MetadataMapExtractor(request.metadata()),
+        )
+    });
+    Span::current().set_parent(parent_cx);
+
+    let request = request.into_inner();
+
+    debug!("Request received for job '{}'", request.key);
+
+    match jobs::register_job(handler.db.as_ref(), &request.key, request.force).await {
+        Ok(job) => {
+            info!(
+                "Job '{}' registered with id {} ({})",
+                request.key, job.id, job.status
+            );
+            Ok(tonic::Response::new(proto::RegisterJobResponse {
+                id: job.id,
+                status: job.status,
+                is_new: job.is_new,
+            }))
+        }
+        Err(err) => {
+            let message = "Failed to process the SQL request";
+            error!(error = ?err, message);
+            Err(tonic::Status::internal(message))
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use metrics_one_grpc::proto;
+use opentelemetry::global;
+use sqlx::Execute;
+use tracing::{Span, debug, error, info, instrument, trace};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::models::Lap;
+use crate::services::query_preparer::{SqlType, insert::InsertQuery};
+
+use super::InsertServiceHandler;
+
+/* /////////////////////// */
+/* //// gRPC Handlers //// */
+/* /////////////////////// */
+
+#[instrument(name = "gRPC laps.insert", skip_all)]
+pub async fn insert(
+    handler: &InsertServiceHandler,
+    request: tonic::Request<proto::InsertLapsRequest>,
+) -> Result<tonic::Response<proto::InsertLapsResponse>, tonic::Status> {
+    // Get Trace context from request metadata
+    let parent_cx = global::get_text_map_propagator(|propagator| {
+        propagator.extract(
+            &metrics_one_grpc::interceptor::tracing::MetadataMapExtractor(request.metadata()),
+        )
+    });
+    Span::current().set_parent(parent_cx);
+
+    let session_key = request.get_ref().session_key;
+    let laps = request.into_inner().laps;
+
+    debug!("Request received with {} insertions", laps.len());
+    let time = std::time::Instant::now();
+
+    let response = proto::InsertLapsResponse {};
+
+    // If no laps, we do nothing and return an 'ok' response
+    if laps.is_empty() {
+        return Ok(tonic::Response::new(response));
+    }
+
+    let nb_laps = laps.len();
+
+    // Prepare query
+    let mut query = InsertQuery::new(Lap::SQL_TABLE, Vec::from(Lap::SQL_FIELDS));
+    query.ignore_conflicts();
+
+    for l in laps.into_iter() {
+        // Order should be the same as 'SQL_FIELDS'
+        let values = vec![
+            SqlType::Int(session_key),
+            SqlType::Int(l.driver_number),
+            SqlType::Int(l.lap_number),
+            SqlType::Int(l.time),
+            SqlType::OptInt(l.lap_time),
+            SqlType::OptInt(l.sector_1),
+            SqlType::OptInt(l.sector_2),
+            SqlType::OptInt(l.sector_3),
+            SqlType::OptInt(l.speed_i1),
+            SqlType::OptInt(l.speed_i2),
+            SqlType::OptInt(l.speed_fl),
+            SqlType::OptInt(l.speed_st),
+        ];
+
+        if let Err(err) = query.add_values(values) {
+            let message = "Failed to prepare 'laps' query";
+            error!(error = ?err, message);
+            return Err(tonic::Status::internal(message));
+        }
+    }
+
+    let query = query.build();
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    if let Err(err) = query.execute(handler.db.as_ref()).await {
+        let message = "Failed to process the SQL request";
+        error!(error = ?err, message);
+        return Err(tonic::Status::internal(message));
+    }
+
+    info!(
+        "Inserted {} laps for session {} successfully in {:?}",
+        nb_laps,
+        session_key,
+        time.elapsed()
+    );
+
+    Ok(tonic::Response::new(response))
+}
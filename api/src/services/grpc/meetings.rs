@@ -1,8 +1,6 @@
-use chrono::{DateTime, Utc};
-use metrics_one_grpc::{proto, utils::timestamp_to_datetime};
+use metrics_one_grpc::proto;
 use opentelemetry::global;
 use opentelemetry::propagation::Extractor;
-use prost_types::Timestamp;
 use sqlx::Execute;
 use tracing::{Span, debug, error, info, instrument, trace};
 use tracing_opentelemetry::OpenTelemetrySpanExt;
@@ -10,16 +8,7 @@ use tracing_opentelemetry::OpenTelemetrySpanExt;
 use crate::models::{Meeting, Session};
 use crate::services::query_preparer::{SqlType, insert::InsertQuery};
 
-use super::InsertServiceHandler;
-
-/* ///////////////////// */
-/* //// gRPC Helper //// */
-/* ///////////////////// */
-
-fn process_date(s: &Option<Timestamp>) -> Result<DateTime<Utc>, Box<dyn std::error::Error>> {
-    let ts = &s.ok_or("Missing timestamp")?;
-    timestamp_to_datetime(ts)
-}
+use super::{InsertServiceHandler, process_date};
 
 /* /////////////////////// */
 /* //// gRPC Handlers //// */
@@ -59,6 +48,10 @@ pub async fn insert(
     let mut meetings_query = InsertQuery::new(Meeting::SQL_TABLE, Vec::from(Meeting::SQL_FIELDS));
     let mut sessions_query = InsertQuery::new(Session::SQL_TABLE, Vec::from(Session::SQL_FIELDS));
 
+    // The whole calendar is sent on refresh, only new meetings and sessions are inserted
+    meetings_query.ignore_conflicts();
+    sessions_query.ignore_conflicts();
+
     for m in meetings.into_iter() {
         let mut meetings_values = Vec::new();
 
@@ -1,8 +1,24 @@
+mod car_data;
+mod jobs;
+mod laps;
 mod meetings;
+mod pit_stops;
+mod positions;
+mod race_control;
+mod session_results;
+mod team_radio;
+mod track_maps;
+mod track_status;
+mod weather;
 
 use std::sync::Arc;
 
-use metrics_one_grpc::proto::{self, insert_service_server::InsertService};
+use chrono::{DateTime, Utc};
+use metrics_one_grpc::{
+    proto::{self, insert_service_server::InsertService},
+    utils::timestamp_to_datetime,
+};
+use prost_types::Timestamp;
 use sqlx::{Pool, Postgres};
 
 //TODO: Move to dedicated file
@@ -19,4 +35,97 @@ impl InsertService for InsertServiceHandler {
     ) -> Result<tonic::Response<proto::InsertMeetingsResponse>, tonic::Status> {
         meetings::insert(&self, request).await
     }
+
+    async fn insert_car_data(
+        &self,
+        request: tonic::Request<proto::InsertCarDataRequest>,
+    ) -> Result<tonic::Response<proto::InsertCarDataResponse>, tonic::Status> {
+        car_data::insert(self, request).await
+    }
+
+    async fn insert_laps(
+        &self,
+        request: tonic::Request<proto::InsertLapsRequest>,
+    ) -> Result<tonic::Response<proto::InsertLapsResponse>, tonic::Status> {
+        laps::insert(self, request).await
+    }
+
+    async fn insert_positions(
+        &self,
+        request: tonic::Request<proto::InsertPositionsRequest>,
+    ) -> Result<tonic::Response<proto::InsertPositionsResponse>, tonic::Status> {
+        positions::insert(self, request).await
+    }
+
+    async fn insert_pit_stops(
+        &self,
+        request: tonic::Request<proto::InsertPitStopsRequest>,
+    ) -> Result<tonic::Response<proto::InsertPitStopsResponse>, tonic::Status> {
+        pit_stops::insert(self, request).await
+    }
+
+    async fn insert_race_control(
+        &self,
+        request: tonic::Request<proto::InsertRaceControlRequest>,
+    ) -> Result<tonic::Response<proto::InsertRaceControlResponse>, tonic::Status> {
+        race_control::insert(self, request).await
+    }
+
+    async fn insert_session_results(
+        &self,
+        request: tonic::Request<proto::InsertSessionResultsRequest>,
+    ) -> Result<tonic::Response<proto::InsertSessionResultsResponse>, tonic::Status> {
+        session_results::insert(self, request).await
+    }
+
+    async fn insert_team_radio(
+        &self,
+        request: tonic::Request<proto::InsertTeamRadioRequest>,
+    ) -> Result<tonic::Response<proto::InsertTeamRadioResponse>, tonic::Status> {
+        team_radio::insert(self, request).await
+    }
+
+    async fn insert_track_map(
+        &self,
+        request: tonic::Request<proto::InsertTrackMapRequest>,
+    ) -> Result<tonic::Response<proto::InsertTrackMapResponse>, tonic::Status> {
+        track_maps::insert(self, request).await
+    }
+
+    async fn insert_track_status(
+        &self,
+        request: tonic::Request<proto::InsertTrackStatusRequest>,
+    ) -> Result<tonic::Response<proto::InsertTrackStatusResponse>, tonic::Status> {
+        track_status::insert(self, request).await
+    }
+
+    async fn insert_weather(
+        &self,
+        request: tonic::Request<proto::InsertWeatherRequest>,
+    ) -> Result<tonic::Response<proto::InsertWeatherResponse>, tonic::Status> {
+        weather::insert(self, request).await
+    }
+
+    async fn update_job(
+        &self,
+        request: tonic::Request<proto::UpdateJobRequest>,
+    ) -> Result<tonic::Response<proto::UpdateJobResponse>, tonic::Status> {
+        jobs::update(self, request).await
+    }
+
+    async fn register_job(
+        &self,
+        request: tonic::Request<proto::RegisterJobRequest>,
+    ) -> Result<tonic::Response<proto::RegisterJobResponse>, tonic::Status> {
+        jobs::register(self, request).await
+    }
+}
+
+/* ///////////////////// */
+/* //// gRPC Helper //// */
+/* ///////////////////// */
+
+fn process_date(s: &Option<Timestamp>) -> Result<DateTime<Utc>, Box<dyn std::error::Error>> {
+    let ts = &s.ok_or("Missing timestamp")?;
+    timestamp_to_datetime(ts)
 }
@@ -0,0 +1,86 @@
+use metrics_one_grpc::proto;
+use opentelemetry::global;
+use sqlx::Execute;
+use tracing::{Span, debug, error, info, instrument, trace};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::models::PitStop;
+use crate::services::query_preparer::{SqlType, insert::InsertQuery};
+
+use super::InsertServiceHandler;
+
+/* /////////////////////// */
+/* //// gRPC Handlers //// */
+/* /////////////////////// */
+
+#[instrument(name = "gRPC pit_stops.insert", skip_all)]
+pub async fn insert(
+    handler: &InsertServiceHandler,
+    request: tonic::Request<proto::InsertPitStopsRequest>,
+) -> Result<tonic::Response<proto::InsertPitStopsResponse>, tonic::Status> {
+    // Get Trace context from request metadata
+    let parent_cx = global::get_text_map_propagator(|propagator| {
+        propagator.extract(
+            &metrics_one_grpc::interceptor::tracing::MetadataMapExtractor(request.metadata()),
+        )
+    });
+    Span::current().set_parent(parent_cx);
+
+    let session_key = request.get_ref().session_key;
+    let pit_stops = request.into_inner().pit_stops;
+
+    debug!("Request received with {} insertions", pit_stops.len());
+    let time = std::time::Instant::now();
+
+    let response = proto::InsertPitStopsResponse {};
+
+    // If no pit stops, we do nothing and return an 'ok' response
+    if pit_stops.is_empty() {
+        return Ok(tonic::Response::new(response));
+    }
+
+    let nb_pit_stops = pit_stops.len();
+
+    // Prepare query
+    let mut query = InsertQuery::new(PitStop::SQL_TABLE, Vec::from(PitStop::SQL_FIELDS));
+    query.ignore_conflicts();
+
+    for p in pit_stops.into_iter() {
+        // Order should be the same as 'SQL_FIELDS'
+        let values = vec![
+            SqlType::Int(session_key),
+            SqlType::Int(p.driver_number),
+            SqlType::Int(p.lap),
+            SqlType::OptInt(p.in_time),
+            SqlType::OptInt(p.out_time),
+            SqlType::OptInt(p.lane_duration),
+            SqlType::OptInt(p.stationary_duration),
+        ];
+
+        if let Err(err) = query.add_values(values) {
+            let message = "Failed to prepare 'pit_stops' query";
+            error!(error = ?err, message);
+            return Err(tonic::Status::internal(message));
+        }
+    }
+
+    let query = query.build();
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    if let Err(err) = query.execute(handler.db.as_ref()).await {
+        let message = "Failed to process the SQL request";
+        error!(error = ?err, message);
+        return Err(tonic::Status::internal(message));
+    }
+
+    info!(
+        "Inserted {} pit stops for session {} successfully in {:?}",
+        nb_pit_stops,
+        session_key,
+        time.elapsed()
+    );
+
+    Ok(tonic::Response::new(response))
+}
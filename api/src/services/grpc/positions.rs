@@ -0,0 +1,84 @@
+use metrics_one_grpc::proto;
+use opentelemetry::global;
+use sqlx::Execute;
+use tracing::{Span, debug, error, info, instrument, trace};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::models::CarPositions;
+use crate::services::query_preparer::{SqlType, insert::InsertQuery};
+
+use super::InsertServiceHandler;
+
+/* /////////////////////// */
+/* //// gRPC Handlers //// */
+/* /////////////////////// */
+
+#[instrument(name = "gRPC positions.insert", skip_all)]
+pub async fn insert(
+    handler: &InsertServiceHandler,
+    request: tonic::Request<proto::InsertPositionsRequest>,
+) -> Result<tonic::Response<proto::InsertPositionsResponse>, tonic::Status> {
+    // Get Trace context from request metadata
+    let parent_cx = global::get_text_map_propagator(|propagator| {
+        propagator.extract(
+            &metrics_one_grpc::interceptor::tracing::MetadataMapExtractor(request.metadata()),
+        )
+    });
+    Span::current().set_parent(parent_cx);
+
+    let positions = request.into_inner();
+
+    debug!("Request received with {} insertions", positions.time.len());
+    let time = std::time::Instant::now();
+
+    let response = proto::InsertPositionsResponse {};
+
+    // If no positions, we do nothing and return an 'ok' response
+    if positions.time.is_empty() {
+        return Ok(tonic::Response::new(response));
+    }
+
+    let (session_key, driver_number) = (positions.session_key, positions.driver_number);
+    let nb_positions = positions.time.len();
+
+    // Prepare query
+    let mut query = InsertQuery::new(CarPositions::SQL_TABLE, Vec::from(CarPositions::SQL_FIELDS));
+    query.ignore_conflicts();
+
+    // Order should be the same as 'SQL_FIELDS'
+    let values = vec![
+        SqlType::Int(session_key),
+        SqlType::Int(driver_number),
+        SqlType::IntArray(positions.time),
+        SqlType::IntArray(positions.x),
+        SqlType::IntArray(positions.y),
+        SqlType::IntArray(positions.z),
+    ];
+
+    if let Err(err) = query.add_values(values) {
+        let message = "Failed to prepare 'car_positions' query";
+        error!(error = ?err, message);
+        return Err(tonic::Status::internal(message));
+    }
+
+    let query = query.build();
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    if let Err(err) = query.execute(handler.db.as_ref()).await {
+        let message = "Failed to process the SQL request";
+        error!(error = ?err, message);
+        return Err(tonic::Status::internal(message));
+    }
+
+    info!(
+        "Inserted {} positions of driver {} for session {} successfully in {:?}",
+        nb_positions,
+        driver_number,
+        session_key,
+        time.elapsed()
+    );
+
+    Ok(tonic::Response::new(response))
+}
@@ -0,0 +1,104 @@
+use metrics_one_grpc::proto;
+use opentelemetry::global;
+use sqlx::Execute;
+use tracing::{Span, debug, error, info, instrument, trace};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::models::RaceControlMessage;
+use crate::services::query_preparer::{SqlType, insert::InsertQuery};
+
+use super::{InsertServiceHandler, process_date};
+
+/* /////////////////////// */
+/* //// gRPC Handlers //// */
+/* /////////////////////// */
+
+#[instrument(name = "gRPC race_control.insert", skip_all)]
+pub async fn insert(
+    handler: &InsertServiceHandler,
+    request: tonic::Request<proto::InsertRaceControlRequest>,
+) -> Result<tonic::Response<proto::InsertRaceControlResponse>, tonic::Status> {
+    // Get Trace context from request metadata
+    let parent_cx = global::get_text_map_propagator(|propagator| {
+        propagator.extract(
+            &metrics_one_grpc::interceptor::tracing::MetadataMapExtractor(request.metadata()),
+        )
+    });
+    Span::current().set_parent(parent_cx);
+
+    let session_key = request.get_ref().session_key;
+    let messages = request.into_inner().messages;
+
+    debug!("Request received with {} insertions", messages.len());
+    let time = std::time::Instant::now();
+
+    let response = proto::InsertRaceControlResponse {};
+
+    // If no messages, we do nothing and return an 'ok' response
+    if messages.is_empty() {
+        return Ok(tonic::Response::new(response));
+    }
+
+    let nb_messages = messages.len();
+
+    // Prepare query
+    let mut query = InsertQuery::new(
+        RaceControlMessage::SQL_TABLE,
+        Vec::from(RaceControlMessage::SQL_FIELDS),
+    );
+    query.ignore_conflicts();
+
+    for m in messages.into_iter() {
+        let date = match process_date(&m.date) {
+            Ok(res) => res,
+            Err(err) => {
+                let message = "Failed to parse timestamp";
+                error!(error = ?err, message);
+                return Err(tonic::Status::internal(message));
+            }
+        };
+
+        // Order should be the same as 'SQL_FIELDS'
+        let values = vec![
+            SqlType::Int(session_key),
+            SqlType::Timestamp(date),
+            SqlType::Int(m.time),
+            SqlType::OptInt(m.lap),
+            SqlType::Text(m.category),
+            SqlType::OptText(m.flag),
+            SqlType::OptText(m.scope),
+            SqlType::OptInt(m.sector),
+            SqlType::OptInt(m.driver_number),
+            SqlType::OptText(m.status),
+            SqlType::OptText(m.mode),
+            SqlType::Text(m.message),
+            SqlType::OptInt(m.deleted_lap),
+        ];
+
+        if let Err(err) = query.add_values(values) {
+            let message = "Failed to prepare 'race_control_messages' query";
+            error!(error = ?err, message);
+            return Err(tonic::Status::internal(message));
+        }
+    }
+
+    let query = query.build();
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    if let Err(err) = query.execute(handler.db.as_ref()).await {
+        let message = "Failed to process the SQL request";
+        error!(error = ?err, message);
+        return Err(tonic::Status::internal(message));
+    }
+
+    info!(
+        "Inserted {} race control messages for session {} successfully in {:?}",
+        nb_messages,
+        session_key,
+        time.elapsed()
+    );
+
+    Ok(tonic::Response::new(response))
+}
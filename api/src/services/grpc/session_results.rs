@@ -0,0 +1,145 @@
+use metrics_one_grpc::proto;
+use opentelemetry::global;
+use sqlx::Execute;
+use tracing::{Span, debug, error, info, instrument, trace};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::models::SessionResult;
+use crate::services::query_preparer::{SqlType, insert::InsertQuery};
+
+use super::InsertServiceHandler;
+
+// Teams of the season of the session, to resolve the team names of the driver list
+const TEAMS_QUERY: &str = "\
+    SELECT teams.id, teams.reference \
+    FROM teams \
+    JOIN meetings ON meetings.year = teams.year \
+    JOIN sessions ON sessions.meeting_key = meetings.key \
+    WHERE sessions.key = $1";
+
+/* /////////////////////// */
+/* //// gRPC Handlers //// */
+/* /////////////////////// */
+
+#[instrument(name = "gRPC session_results.insert", skip_all)]
+pub async fn insert(
+    handler: &InsertServiceHandler,
+    request: tonic::Request<proto::InsertSessionResultsRequest>,
+) -> Result<tonic::Response<proto::InsertSessionResultsResponse>, tonic::Status> {
+    // Get Trace context from request metadata
+    let parent_cx = global::get_text_map_propagator(|propagator| {
+        propagator.extract(
+            &metrics_one_grpc::interceptor::tracing::MetadataMapExtractor(request.metadata()),
+        )
+    });
+    Span::current().set_parent(parent_cx);
+
+    let session_key = request.get_ref().session_key;
+    let results = request.into_inner().results;
+
+    debug!("Request received with {} insertions", results.len());
+    let time = std::time::Instant::now();
+
+    let response = proto::InsertSessionResultsResponse {};
+
+    // If no results, we do nothing and return an 'ok' response
+    if results.is_empty() {
+        return Ok(tonic::Response::new(response));
+    }
+
+    let nb_results = results.len();
+
+    let query = sqlx::query_as::<_, (i32, String)>(TEAMS_QUERY).bind(session_key);
+    debug!("SQL query - {}", query.sql());
+
+    let teams = match query.fetch_all(handler.db.as_ref()).await {
+        Ok(teams) => teams,
+        Err(err) => {
+            let message = "Failed to process the SQL request";
+            error!(error = ?err, message);
+            return Err(tonic::Status::internal(message));
+        }
+    };
+
+    // Prepare query
+    let mut query = InsertQuery::new(
+        SessionResult::SQL_TABLE,
+        Vec::from(SessionResult::SQL_FIELDS),
+    );
+    query.ignore_conflicts();
+
+    for r in results.into_iter() {
+        // Order should be the same as 'SQL_FIELDS'
+        let values = vec![
+            SqlType::Int(session_key),
+            SqlType::Int(r.driver_number),
+            SqlType::OptInt(r.position),
+            SqlType::Bool(r.retired),
+            SqlType::OptInt(
+                r.team_name
+                    .as_deref()
+                    .and_then(|name| find_team(&teams, name)),
+            ),
+        ];
+
+        if let Err(err) = query.add_values(values) {
+            let message = "Failed to prepare 'session_results' query";
+            error!(error = ?err, message);
+            return Err(tonic::Status::internal(message));
+        }
+    }
+
+    let query = query.build();
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    if let Err(err) = query.execute(handler.db.as_ref()).await {
+        let message = "Failed to process the SQL request";
+        error!(error = ?err, message);
+        return Err(tonic::Status::internal(message));
+    }
+
+    info!(
+        "Inserted {} results for session {} successfully in {:?}",
+        nb_results,
+        session_key,
+        time.elapsed()
+    );
+
+    Ok(tonic::Response::new(response))
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+// Team names of the driver list start with the reference of the team, e.g. 'Haas F1 Team' for 'haas'
+// The longest reference is kept, so a reference being the start of another doesn't match it
+fn find_team(teams: &[(i32, String)], name: &str) -> Option<i32> {
+    let name = name.to_lowercase();
+
+    teams
+        .iter()
+        .filter(|(_, reference)| name.starts_with(reference.as_str()))
+        .max_by_key(|(_, reference)| reference.len())
+        .map(|(id, _)| *id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_team;
+
+    #[test]
+    fn longest_team_reference_is_matched() {
+        let teams = [
+            (1, "red bull".to_string()),
+            (2, "red bull racing".to_string()),
+            (3, "haas".to_string()),
+        ];
+
+        assert_eq!(find_team(&teams, "Red Bull Racing Honda RBPT"), Some(2));
+        assert_eq!(find_team(&teams, "Haas F1 Team"), Some(3));
+        assert_eq!(find_team(&teams, "Williams"), None);
+    }
+}
@@ -0,0 +1,92 @@
+use metrics_one_grpc::proto;
+use opentelemetry::global;
+use sqlx::Execute;
+use tracing::{Span, debug, error, info, instrument, trace};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::models::TeamRadio;
+use crate::services::query_preparer::{SqlType, insert::InsertQuery};
+
+use super::{InsertServiceHandler, process_date};
+
+/* /////////////////////// */
+/* //// gRPC Handlers //// */
+/* /////////////////////// */
+
+#[instrument(name = "gRPC team_radio.insert", skip_all)]
+pub async fn insert(
+    handler: &InsertServiceHandler,
+    request: tonic::Request<proto::InsertTeamRadioRequest>,
+) -> Result<tonic::Response<proto::InsertTeamRadioResponse>, tonic::Status> {
+    // Get Trace context from request metadata
+    let parent_cx = global::get_text_map_propagator(|propagator| {
+        propagator.extract(
+            &metrics_one_grpc::interceptor::tracing::MetadataMapExtractor(request.metadata()),
+        )
+    });
+    Span::current().set_parent(parent_cx);
+
+    let session_key = request.get_ref().session_key;
+    let captures = request.into_inner().captures;
+
+    debug!("Request received with {} insertions", captures.len());
+    let time = std::time::Instant::now();
+
+    let response = proto::InsertTeamRadioResponse {};
+
+    // If no captures, we do nothing and return an 'ok' response
+    if captures.is_empty() {
+        return Ok(tonic::Response::new(response));
+    }
+
+    let nb_captures = captures.len();
+
+    // Prepare query
+    let mut query = InsertQuery::new(TeamRadio::SQL_TABLE, Vec::from(TeamRadio::SQL_FIELDS));
+    query.ignore_conflicts();
+
+    for c in captures.into_iter() {
+        let date = match process_date(&c.date) {
+            Ok(res) => res,
+            Err(err) => {
+                let message = "Failed to parse timestamp";
+                error!(error = ?err, message);
+                return Err(tonic::Status::internal(message));
+            }
+        };
+
+        // Order should be the same as 'SQL_FIELDS'
+        let values = vec![
+            SqlType::Int(session_key),
+            SqlType::Timestamp(date),
+            SqlType::Int(c.driver_number),
+            SqlType::Text(c.path),
+        ];
+
+        if let Err(err) = query.add_values(values) {
+            let message = "Failed to prepare 'team_radio' query";
+            error!(error = ?err, message);
+            return Err(tonic::Status::internal(message));
+        }
+    }
+
+    let query = query.build();
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    if let Err(err) = query.execute(handler.db.as_ref()).await {
+        let message = "Failed to process the SQL request";
+        error!(error = ?err, message);
+        return Err(tonic::Status::internal(message));
+    }
+
+    info!(
+        "Inserted {} team radio captures for session {} successfully in {:?}",
+        nb_captures,
+        session_key,
+        time.elapsed()
+    );
+
+    Ok(tonic::Response::new(response))
+}
@@ -0,0 +1,92 @@
+use metrics_one_grpc::proto;
+use opentelemetry::global;
+use sqlx::Execute;
+use tracing::{Span, debug, error, info, instrument, trace};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::models::TrackMap;
+use crate::services::{
+    jobs,
+    query_preparer::{SqlType, insert::InsertQuery},
+};
+
+use super::InsertServiceHandler;
+
+/* /////////////////////// */
+/* //// gRPC Handlers //// */
+/* /////////////////////// */
+
+#[instrument(name = "gRPC track_maps.insert", skip_all)]
+pub async fn insert(
+    handler: &InsertServiceHandler,
+    request: tonic::Request<proto::InsertTrackMapRequest>,
+) -> Result<tonic::Response<proto::InsertTrackMapResponse>, tonic::Status> {
+    // Get Trace context from request metadata
+    let parent_cx = global::get_text_map_propagator(|propagator| {
+        propagator.extract(
+            &metrics_one_grpc::interceptor::tracing::MetadataMapExtractor(request.metadata()),
+        )
+    });
+    Span::current().set_parent(parent_cx);
+
+    let map = request.into_inner();
+
+    debug!("Request received with {} points", map.x.len());
+    let time = std::time::Instant::now();
+
+    let response = proto::InsertTrackMapResponse {};
+
+    // Track maps are stored per meeting
+    let session = match jobs::find_session(handler.db.as_ref(), map.session_key).await {
+        Ok(Some(session)) => session,
+        Ok(None) => {
+            let message = "Unknown session for track map";
+            error!(session = map.session_key, message);
+            return Err(tonic::Status::not_found(message));
+        }
+        Err(err) => {
+            let message = "Failed to process the SQL request";
+            error!(error = ?err, message);
+            return Err(tonic::Status::internal(message));
+        }
+    };
+
+    // Prepare query
+    let mut query = InsertQuery::new(TrackMap::SQL_TABLE, Vec::from(TrackMap::SQL_FIELDS));
+    query.ignore_conflicts();
+
+    // Order should be the same as 'SQL_FIELDS'
+    let values = vec![
+        SqlType::Int(session.meeting_key),
+        SqlType::Int(map.session_key),
+        SqlType::Float(map.rotation),
+        SqlType::FloatArray(map.x),
+        SqlType::FloatArray(map.y),
+        SqlType::IntArray(map.sectors),
+    ];
+
+    if let Err(err) = query.add_values(values) {
+        let message = "Failed to prepare 'track_maps' query";
+        error!(error = ?err, message);
+        return Err(tonic::Status::internal(message));
+    }
+
+    let query = query.build();
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    if let Err(err) = query.execute(handler.db.as_ref()).await {
+        let message = "Failed to process the SQL request";
+        error!(error = ?err, message);
+        return Err(tonic::Status::internal(message));
+    }
+
+    info!(
+        "Inserted track map of meeting {} successfully in {:?}",
+        session.meeting_key,
+        time.elapsed()
+    );
+
+    Ok(tonic::Response::new(response))
+}
@@ -0,0 +1,86 @@
+use metrics_one_grpc::proto;
+use opentelemetry::global;
+use sqlx::Execute;
+use tracing::{Span, debug, error, info, instrument, trace};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::models::TrackStatus;
+use crate::services::query_preparer::{SqlType, insert::InsertQuery};
+
+use super::InsertServiceHandler;
+
+/* /////////////////////// */
+/* //// gRPC Handlers //// */
+/* /////////////////////// */
+
+#[instrument(name = "gRPC track_status.insert", skip_all)]
+pub async fn insert(
+    handler: &InsertServiceHandler,
+    request: tonic::Request<proto::InsertTrackStatusRequest>,
+) -> Result<tonic::Response<proto::InsertTrackStatusResponse>, tonic::Status> {
+    // Get Trace context from request metadata
+    let parent_cx = global::get_text_map_propagator(|propagator| {
+        propagator.extract(
+            &metrics_one_grpc::interceptor::tracing::MetadataMapExtractor(request.metadata()),
+        )
+    });
+    Span::current().set_parent(parent_cx);
+
+    let session_key = request.get_ref().session_key;
+    let statuses = request.into_inner().statuses;
+
+    debug!("Request received with {} insertions", statuses.len());
+    let time = std::time::Instant::now();
+
+    let response = proto::InsertTrackStatusResponse {};
+
+    // If no statuses, we do nothing and return an 'ok' response
+    if statuses.is_empty() {
+        return Ok(tonic::Response::new(response));
+    }
+
+    let nb_statuses = statuses.len();
+
+    // Prepare query
+    let mut query = InsertQuery::new(TrackStatus::SQL_TABLE, Vec::from(TrackStatus::SQL_FIELDS));
+    query.ignore_conflicts();
+
+    for s in statuses.into_iter() {
+        // Order should be the same as 'SQL_FIELDS'
+        let values = vec![
+            SqlType::Int(session_key),
+            SqlType::Text(s.status),
+            SqlType::Text(s.message),
+            SqlType::Int(s.start_time),
+            SqlType::OptInt(s.end_time),
+            SqlType::OptInt(s.start_lap),
+            SqlType::OptInt(s.end_lap),
+        ];
+
+        if let Err(err) = query.add_values(values) {
+            let message = "Failed to prepare 'track_status' query";
+            error!(error = ?err, message);
+            return Err(tonic::Status::internal(message));
+        }
+    }
+
+    let query = query.build();
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    if let Err(err) = query.execute(handler.db.as_ref()).await {
+        let message = "Failed to process the SQL request";
+        error!(error = ?err, message);
+        return Err(tonic::Status::internal(message));
+    }
+
+    info!(
+        "Inserted {} track statuses for session {} successfully in {:?}",
+        nb_statuses,
+        session_key,
+        time.elapsed()
+    );
+
+    Ok(tonic::Response::new(response))
+}
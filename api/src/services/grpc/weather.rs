@@ -0,0 +1,91 @@
+use metrics_one_grpc::proto;
+use opentelemetry::global;
+use sqlx::Execute;
+use tracing::{Span, debug, error, info, instrument, trace};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::models::WeatherSample;
+use crate::services::query_preparer::{SqlType, insert::InsertQuery};
+
+use super::InsertServiceHandler;
+
+/* /////////////////////// */
+/* //// gRPC Handlers //// */
+/* /////////////////////// */
+
+#[instrument(name = "gRPC weather.insert", skip_all)]
+pub async fn insert(
+    handler: &InsertServiceHandler,
+    request: tonic::Request<proto::InsertWeatherRequest>,
+) -> Result<tonic::Response<proto::InsertWeatherResponse>, tonic::Status> {
+    // Get Trace context from request metadata
+    let parent_cx = global::get_text_map_propagator(|propagator| {
+        propagator.extract(
+            &metrics_one_grpc::interceptor::tracing::MetadataMapExtractor(request.metadata()),
+        )
+    });
+    Span::current().set_parent(parent_cx);
+
+    let session_key = request.get_ref().session_key;
+    let samples = request.into_inner().samples;
+
+    debug!("Request received with {} insertions", samples.len());
+    let time = std::time::Instant::now();
+
+    let response = proto::InsertWeatherResponse {};
+
+    // If no samples, we do nothing and return an 'ok' response
+    if samples.is_empty() {
+        return Ok(tonic::Response::new(response));
+    }
+
+    let nb_samples = samples.len();
+
+    // Prepare query
+    let mut query = InsertQuery::new(
+        WeatherSample::SQL_TABLE,
+        Vec::from(WeatherSample::SQL_FIELDS),
+    );
+    query.ignore_conflicts();
+
+    for s in samples.into_iter() {
+        // Order should be the same as 'SQL_FIELDS'
+        let values = vec![
+            SqlType::Int(session_key),
+            SqlType::Int(s.time),
+            SqlType::Float(s.air_temp),
+            SqlType::Float(s.track_temp),
+            SqlType::Float(s.humidity),
+            SqlType::Float(s.pressure),
+            SqlType::Float(s.wind_speed),
+            SqlType::Int(s.wind_direction),
+            SqlType::Bool(s.rainfall),
+        ];
+
+        if let Err(err) = query.add_values(values) {
+            let message = "Failed to prepare 'weather_samples' query";
+            error!(error = ?err, message);
+            return Err(tonic::Status::internal(message));
+        }
+    }
+
+    let query = query.build();
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    if let Err(err) = query.execute(handler.db.as_ref()).await {
+        let message = "Failed to process the SQL request";
+        error!(error = ?err, message);
+        return Err(tonic::Status::internal(message));
+    }
+
+    info!(
+        "Inserted {} weather samples for session {} successfully in {:?}",
+        nb_samples,
+        session_key,
+        time.elapsed()
+    );
+
+    Ok(tonic::Response::new(response))
+}
@@ -0,0 +1,182 @@
+use actix_web::{
+    HttpResponse, Responder, get, post,
+    web::{self, Data},
+};
+use metrics_one_queue::models::SESSION_FEEDS;
+use serde::Deserialize;
+use tracing::{debug, error, info};
+
+use crate::{AppState, services::jobs};
+
+// Queues consumed by the worker, which can be inspected and requeued
+const QUEUES: [&str; 3] = [
+    metrics_one_queue::MEETINGS_QUEUE,
+    metrics_one_queue::SESSIONS_QUEUE,
+    metrics_one_queue::TRACK_MAPS_QUEUE,
+];
+
+/* ///////////////////////// */
+/* //// HTTP Parameters //// */
+/* ///////////////////////// */
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AdminFetchParams {
+    pub feeds: Option<String>,
+}
+
+impl AdminFetchParams {
+    // Feeds are sorted so the same selection is coalesced with the job in flight
+    // 'Err' holds the first unknown feed
+    pub fn get_feeds(&self) -> Result<Option<Vec<String>>, String> {
+        let Some(feeds) = &self.feeds else {
+            return Ok(None);
+        };
+
+        let mut feeds: Vec<String> = feeds.split(',').map(|f| f.trim().to_string()).collect();
+        if let Some(feed) = feeds.iter().find(|f| !SESSION_FEEDS.contains(&f.as_str())) {
+            return Err(feed.clone());
+        }
+
+        feeds.sort();
+        feeds.dedup();
+
+        Ok(Some(feeds))
+    }
+}
+
+/* /////////////////////// */
+/* //// HTTP Handlers //// */
+/* /////////////////////// */
+
+#[post("/meetings/{year}/fetch")]
+pub async fn admin_fetch_meetings(state: Data<AppState>, path: web::Path<i32>) -> impl Responder {
+    let year = path.into_inner();
+    debug!(year, "Request received with");
+
+    match jobs::request_meetings_fetch(&state, year, true).await {
+        Ok(Some(job)) => accepted(job),
+        Ok(None) => HttpResponse::Ok().body("Meetings already stored"),
+        Err(err) => {
+            error!(error = ?err, "Failed to request meetings fetch");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[post("/meetings/{key}/track/fetch")]
+pub async fn admin_fetch_track_map(state: Data<AppState>, path: web::Path<i32>) -> impl Responder {
+    let meeting_key = path.into_inner();
+    debug!(meeting_key, "Request received with");
+
+    match jobs::request_track_map_fetch(&state, meeting_key, true).await {
+        Ok(Some(job)) => accepted(job),
+        Ok(None) => HttpResponse::NotFound().body("No session over for this meeting"),
+        Err(err) => {
+            error!(error = ?err, "Failed to request track map fetch");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[post("/sessions/{key}/fetch")]
+pub async fn admin_fetch_session(
+    state: Data<AppState>,
+    info: web::Query<AdminFetchParams>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let session_key = path.into_inner();
+    let params = info.into_inner();
+    debug!(session_key, parameters = ?params, "Request received with");
+
+    let feeds = match params.get_feeds() {
+        Ok(feeds) => feeds,
+        Err(feed) => return HttpResponse::BadRequest().body(format!("Unknown feed '{}'", feed)),
+    };
+
+    match jobs::request_session_feeds_fetch(&state, session_key, feeds, true).await {
+        Ok(Some(job)) => accepted(job),
+        Ok(None) => HttpResponse::NotFound().body("Session unknown or not over"),
+        Err(err) => {
+            error!(error = ?err, "Failed to request session fetch");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[post("/sessions/{key}/reingest")]
+pub async fn admin_reingest_session(state: Data<AppState>, path: web::Path<i32>) -> impl Responder {
+    let session_key = path.into_inner();
+    debug!(session_key, "Request received with");
+
+    match jobs::request_session_reingest(&state, session_key).await {
+        Ok(Some(job)) => accepted(job),
+        Ok(None) => HttpResponse::NotFound().body("Session unknown or not over"),
+        Err(err) => {
+            error!(error = ?err, "Failed to request session reingestion");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/queues")]
+pub async fn admin_fetch_queues(state: Data<AppState>) -> impl Responder {
+    let time = std::time::Instant::now();
+
+    let mut queues = Vec::new();
+    for queue in QUEUES {
+        let dead_letter_queue = metrics_one_queue::dead_letter_queue(queue);
+
+        let depths = tokio::try_join!(
+            metrics_one_queue::queue_depth(state.rabbitmq.as_ref(), queue),
+            metrics_one_queue::queue_depth(state.rabbitmq.as_ref(), &dead_letter_queue),
+        );
+
+        match depths {
+            Ok(((messages, consumers), (dead_letters, _))) => queues.push(serde_json::json!({
+                "name": queue,
+                "messages": messages,
+                "consumers": consumers,
+                "dead_letters": dead_letters,
+            })),
+            Err(err) => {
+                error!(error = ?err, "Failed to inspect queue '{}'", queue);
+                return HttpResponse::InternalServerError().finish();
+            }
+        }
+    }
+
+    info!("Inspected {} queues in {:?}", queues.len(), time.elapsed());
+
+    HttpResponse::Ok().json(queues)
+}
+
+#[post("/queues/{queue}/requeue")]
+pub async fn admin_requeue_dead_letters(
+    state: Data<AppState>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let queue = path.into_inner();
+    debug!(queue, "Request received with");
+
+    if !QUEUES.contains(&queue.as_str()) {
+        return HttpResponse::NotFound().body("Unknown queue");
+    }
+
+    match metrics_one_queue::requeue_dead_letters(state.rabbitmq.as_ref(), &queue).await {
+        Ok(requeued) => HttpResponse::Ok().json(serde_json::json!({ "requeued": requeued })),
+        Err(err) => {
+            error!(error = ?err, "Failed to requeue dead letters of '{}'", queue);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+fn accepted(job: i32) -> HttpResponse {
+    HttpResponse::Accepted()
+        .insert_header((jobs::JOB_ID_HEADER, job))
+        .json(serde_json::json!({ "job": job }))
+}
@@ -0,0 +1,89 @@
+use actix_web::{
+    HttpResponse, Responder, get,
+    web::{self, Data},
+};
+use sqlx::{Execute, Postgres, postgres::PgArguments, query::QueryAs};
+use tracing::{debug, error, info, trace};
+
+use crate::{
+    AppState,
+    models::Lap,
+    services::{analytics, jobs},
+};
+
+// Laps deleted by race control (e.g. track limits) are excluded
+const VALID_LAPS_QUERY: &str = "\
+    SELECT \
+        session_key, driver_number, lap_number, time, lap_time, sector_1, sector_2, sector_3, \
+        speed_i1, speed_i2, speed_fl, speed_st \
+    FROM laps \
+    WHERE session_key = $1 \
+    AND NOT EXISTS ( \
+        SELECT 1 FROM race_control_messages \
+        WHERE race_control_messages.session_key = laps.session_key \
+        AND race_control_messages.driver_number = laps.driver_number \
+        AND race_control_messages.deleted_lap = laps.lap_number \
+    ) \
+    ORDER BY lap_number ASC";
+
+/* /////////////////////// */
+/* //// HTTP Handlers //// */
+/* /////////////////////// */
+
+#[get("/sessions/{key}/bests")]
+pub async fn fetch_bests(state: Data<AppState>, path: web::Path<i32>) -> impl Responder {
+    let session_key = path.into_inner();
+
+    debug!(session = session_key, "Request received with");
+    let time = std::time::Instant::now();
+
+    // Prepare the query
+    let query = prepare_query(session_key);
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    // Execute the query
+    let laps = match query.fetch_all(state.db.as_ref()).await {
+        Ok(laps) => {
+            trace!("Fetched {} valid laps in {:?}", laps.len(), time.elapsed());
+            laps
+        }
+        Err(err) => {
+            error!(error = ?err, "Failed to execute SQL request");
+            return HttpResponse::Ok().json(serde_json::json!({}));
+        }
+    };
+
+    let bests = analytics::bests::compute(&laps);
+    info!(
+        "Computed bests of {} drivers successfully in {:?}",
+        bests.drivers.len(),
+        time.elapsed()
+    );
+
+    if !laps.is_empty() {
+        return HttpResponse::Ok().json(bests);
+    }
+
+    // No laps found, session data might not have been ingested yet
+    match jobs::request_session_fetch(&state, session_key).await {
+        // Respond with "Accepted" status to indicate the request is being process
+        Ok(Some(job)) => HttpResponse::Accepted()
+            .insert_header((jobs::JOB_ID_HEADER, job))
+            .json(bests),
+        Ok(None) => HttpResponse::Ok().json(bests),
+        Err(err) => {
+            error!(error = ?err, "Failed to request session fetch");
+            HttpResponse::Ok().json(bests)
+        }
+    }
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+fn prepare_query(session_key: i32) -> QueryAs<'static, Postgres, Lap, PgArguments> {
+    sqlx::query_as::<_, Lap>(VALID_LAPS_QUERY).bind(session_key)
+}
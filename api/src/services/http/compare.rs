@@ -0,0 +1,158 @@
+use actix_web::{
+    HttpResponse, Responder, get,
+    web::{self, Data},
+};
+use serde::Deserialize;
+use tracing::{debug, error, info, trace};
+
+use crate::{
+    AppState,
+    models::CarDataLap,
+    services::{analytics, jobs},
+};
+
+// Default distance between two points of the comparison, in meters
+const DEFAULT_STEP: f64 = 10.0;
+
+// Without lap number, the fastest lap not deleted by race control is used
+const LAP_QUERY: &str = "\
+    SELECT \
+        car_data_laps.session_key, \
+        car_data_laps.driver_number, \
+        car_data_laps.lap_number, \
+        car_data_laps.time, \
+        car_data_laps.speed, \
+        car_data_laps.throttle, \
+        car_data_laps.brake, \
+        car_data_laps.rpm, \
+        car_data_laps.gear, \
+        car_data_laps.drs, \
+        laps.lap_time \
+    FROM car_data_laps \
+    JOIN laps ON laps.session_key = car_data_laps.session_key \
+        AND laps.driver_number = car_data_laps.driver_number \
+        AND laps.lap_number = car_data_laps.lap_number \
+    WHERE car_data_laps.session_key = $1 \
+    AND car_data_laps.driver_number = $2 \
+    AND ($3::integer IS NULL OR car_data_laps.lap_number = $3) \
+    AND ($3::integer IS NOT NULL OR ( \
+        laps.lap_time IS NOT NULL \
+        AND NOT EXISTS ( \
+            SELECT 1 FROM race_control_messages \
+            WHERE race_control_messages.session_key = laps.session_key \
+            AND race_control_messages.driver_number = laps.driver_number \
+            AND race_control_messages.deleted_lap = laps.lap_number \
+        ) \
+    )) \
+    ORDER BY laps.lap_time ASC NULLS LAST \
+    LIMIT 1";
+
+/* ///////////////////////// */
+/* //// HTTP Parameters //// */
+/* ///////////////////////// */
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CompareParams {
+    pub session: Option<i32>,
+    pub drivers: Option<String>,
+    pub lap: Option<String>,
+    pub step: Option<f64>,
+}
+
+impl CompareParams {
+    pub fn get_drivers(&self) -> Vec<i32> {
+        if let Some(drivers) = &self.drivers {
+            return drivers
+                .split(",")
+                .filter_map(|d| d.trim().parse().ok())
+                .collect();
+        }
+
+        // Dafault to an empty vector
+        Vec::new()
+    }
+
+    // 'None' stands for the fastest lap
+    pub fn get_lap(&self) -> Result<Option<i32>, std::num::ParseIntError> {
+        match self.lap.as_deref() {
+            None | Some("fastest") => Ok(None),
+            Some(lap) => lap.parse().map(Some),
+        }
+    }
+}
+
+/* /////////////////////// */
+/* //// HTTP Handlers //// */
+/* /////////////////////// */
+
+#[get("/sessions/{key}/compare")]
+pub async fn fetch_compare(
+    state: Data<AppState>,
+    info: web::Query<CompareParams>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let params = CompareParams {
+        session: Some(path.into_inner()),
+        ..info.into_inner()
+    };
+
+    debug!(parameters = ?params, "Request received with");
+    let time = std::time::Instant::now();
+
+    let session_key = params.session.unwrap_or_default();
+    let drivers = params.get_drivers();
+    let Ok(lap) = params.get_lap() else {
+        debug!("Invalid lap parameter");
+        return HttpResponse::BadRequest().json(serde_json::json!({}));
+    };
+
+    if drivers.len() < 2 {
+        debug!("At least two drivers are needed for a comparison");
+        return HttpResponse::BadRequest().json(serde_json::json!({}));
+    }
+
+    // Execute the queries, one lap per driver
+    let mut laps: Vec<CarDataLap> = Vec::with_capacity(drivers.len());
+    for driver_number in drivers {
+        let query = sqlx::query_as::<_, CarDataLap>(LAP_QUERY)
+            .bind(session_key)
+            .bind(driver_number)
+            .bind(lap);
+
+        match query.fetch_optional(state.db.as_ref()).await {
+            Ok(Some(lap)) => laps.push(lap),
+            Ok(None) => debug!("No car data found for driver {}", driver_number),
+            Err(err) => {
+                error!(error = ?err, "Failed to execute SQL request");
+                return HttpResponse::Ok().json(serde_json::json!({}));
+            }
+        }
+    }
+    trace!("Fetched {} laps in {:?}", laps.len(), time.elapsed());
+
+    if laps.len() < 2 {
+        // No car data found, session data might not have been ingested yet
+        return match jobs::request_session_fetch(&state, session_key).await {
+            // Respond with "Accepted" status to indicate the request is being process
+            Ok(Some(job)) => HttpResponse::Accepted()
+                .insert_header((jobs::JOB_ID_HEADER, job))
+                .json(serde_json::json!({})),
+            Ok(None) => HttpResponse::Ok().json(serde_json::json!({})),
+            Err(err) => {
+                error!(error = ?err, "Failed to request session fetch");
+                HttpResponse::Ok().json(serde_json::json!({}))
+            }
+        };
+    }
+
+    let comparison =
+        analytics::compare::compute(&laps, params.step.unwrap_or(DEFAULT_STEP).max(1.0));
+    info!(
+        "Compared {} laps on {} points successfully in {:?}",
+        comparison.laps.len(),
+        comparison.distance.len(),
+        time.elapsed()
+    );
+
+    HttpResponse::Ok().json(comparison)
+}
@@ -0,0 +1,176 @@
+use actix_web::{
+    HttpResponse, Responder, get,
+    web::{self, Data},
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{Execute, Postgres, postgres::PgArguments, query::QueryAs};
+use tracing::{debug, error, info, trace};
+
+use crate::{
+    AppState,
+    models::{LapGap, LapPosition},
+    services::jobs,
+};
+
+// Positions are ranked by line crossing time, so lapped cars are ranked behind on each lap
+// Windows are computed over the whole session before filtering on a driver
+const LAP_CHART_QUERY: &str = "\
+    SELECT driver_number, lap_number, position \
+    FROM ( \
+        SELECT \
+            driver_number, \
+            lap_number, \
+            RANK() OVER (PARTITION BY lap_number ORDER BY time)::integer AS position \
+        FROM laps \
+        WHERE session_key = $1 \
+    ) AS positions \
+    WHERE $2::integer IS NULL OR driver_number = $2 \
+    ORDER BY lap_number ASC, position ASC";
+
+const GAPS_QUERY: &str = "\
+    SELECT driver_number, lap_number, position, gap_to_leader, interval \
+    FROM ( \
+        SELECT \
+            driver_number, \
+            lap_number, \
+            RANK() OVER (PARTITION BY lap_number ORDER BY time)::integer AS position, \
+            time - MIN(time) OVER (PARTITION BY lap_number) AS gap_to_leader, \
+            time - LAG(time) OVER (PARTITION BY lap_number ORDER BY time) AS interval \
+        FROM laps \
+        WHERE session_key = $1 \
+    ) AS gaps \
+    WHERE $2::integer IS NULL OR driver_number = $2 \
+    ORDER BY lap_number ASC, position ASC";
+
+/* ///////////////////////// */
+/* //// HTTP Parameters //// */
+/* ///////////////////////// */
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LapsParams {
+    pub session: Option<i32>,
+    pub driver: Option<i32>,
+}
+
+/* /////////////////////// */
+/* //// HTTP Handlers //// */
+/* /////////////////////// */
+
+#[get("/sessions/{key}/lap-chart")]
+pub async fn fetch_lap_chart(
+    state: Data<AppState>,
+    info: web::Query<LapsParams>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let params = LapsParams {
+        session: Some(path.into_inner()),
+        ..info.into_inner()
+    };
+
+    debug!(parameters = ?params, "Request received with");
+    let time = std::time::Instant::now();
+
+    // Prepare the query
+    let query = prepare_query::<LapPosition>(LAP_CHART_QUERY, &params);
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    // Execute the query
+    let positions = match query.fetch_all(state.db.as_ref()).await {
+        Ok(positions) => {
+            info!(
+                "Fetched {} lap positions successfully in {:?}",
+                positions.len(),
+                time.elapsed()
+            );
+            positions
+        }
+        Err(err) => {
+            error!(error = ?err, "Failed to execute SQL request");
+            return HttpResponse::Ok().json(serde_json::json!([]));
+        }
+    };
+
+    respond(&state, &params, positions).await
+}
+
+#[get("/sessions/{key}/gaps")]
+pub async fn fetch_gaps(
+    state: Data<AppState>,
+    info: web::Query<LapsParams>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let params = LapsParams {
+        session: Some(path.into_inner()),
+        ..info.into_inner()
+    };
+
+    debug!(parameters = ?params, "Request received with");
+    let time = std::time::Instant::now();
+
+    // Prepare the query
+    let query = prepare_query::<LapGap>(GAPS_QUERY, &params);
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    // Execute the query
+    let gaps = match query.fetch_all(state.db.as_ref()).await {
+        Ok(gaps) => {
+            info!(
+                "Fetched {} lap gaps successfully in {:?}",
+                gaps.len(),
+                time.elapsed()
+            );
+            gaps
+        }
+        Err(err) => {
+            error!(error = ?err, "Failed to execute SQL request");
+            return HttpResponse::Ok().json(serde_json::json!([]));
+        }
+    };
+
+    respond(&state, &params, gaps).await
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+fn prepare_query<T>(
+    sql: &'static str,
+    params: &LapsParams,
+) -> QueryAs<'static, Postgres, T, PgArguments>
+where
+    T: for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>,
+{
+    sqlx::query_as::<_, T>(sql)
+        .bind(params.session.unwrap_or_default())
+        .bind(params.driver)
+}
+
+async fn respond<T: Serialize>(
+    state: &AppState,
+    params: &LapsParams,
+    rows: Vec<T>,
+) -> HttpResponse {
+    // If there are filters parameters, it might just be a bad filter
+    // So, it doesn't trigger a fetch job even if there are no laps
+    if !rows.is_empty() || params.driver.is_some() {
+        return HttpResponse::Ok().json(rows);
+    }
+
+    // No laps found, session data might not have been ingested yet
+    match jobs::request_session_fetch(state, params.session.unwrap_or_default()).await {
+        // Respond with "Accepted" status to indicate the request is being process
+        Ok(Some(job)) => HttpResponse::Accepted()
+            .insert_header((jobs::JOB_ID_HEADER, job))
+            .json(serde_json::json!([])),
+        Ok(None) => HttpResponse::Ok().json(rows),
+        Err(err) => {
+            error!(error = ?err, "Failed to request session fetch");
+            HttpResponse::Ok().json(rows)
+        }
+    }
+}
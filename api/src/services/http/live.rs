@@ -0,0 +1,105 @@
+use std::{convert::Infallible, sync::Arc, time::Duration};
+
+use actix_web::{
+    HttpResponse, Responder, get,
+    web::{self, Bytes, Data},
+};
+use metrics_one_queue::models::LiveUpdate;
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, error, warn};
+
+use crate::{AppState, services::http::replay};
+
+// Comments are sent regularly so proxies don't close idle connections
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/* /////////////////////// */
+/* //// HTTP Handlers //// */
+/* /////////////////////// */
+
+// Push the updates of a live session as Server-Sent Events, as they are ingested
+// Events are named by kind ('timing', 'race_control', 'track_status'), data only holding the changes
+// The timing tower and track status stored so far are sent first, as 'lap' and 'track_status' replay events
+#[get("/live/{key}")]
+pub async fn fetch_live(state: Data<AppState>, path: web::Path<i32>) -> impl Responder {
+    let session_key = path.into_inner();
+    debug!(session_key, "Live subscription received for");
+
+    // Subscribed before reading the stored state, so no update is missed in between
+    let updates = state.live.subscribe();
+
+    let (tx, rx) = mpsc::channel::<Result<Bytes, Infallible>>(16);
+    tokio::spawn(async move {
+        if push_snapshot(&tx, &state, session_key).await {
+            push_updates(tx, updates, session_key).await;
+        }
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(ReceiverStream::new(rx))
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+// Send the state of the session as stored so far, returning whether the client is still connected
+async fn push_snapshot(
+    tx: &mpsc::Sender<Result<Bytes, Infallible>>,
+    state: &AppState,
+    session_key: i32,
+) -> bool {
+    let events = match replay::fetch_timing_events(state, session_key).await {
+        Ok(events) => events,
+        Err(err) => {
+            // Updates are still pushed, the client only missing the state so far
+            error!(error = ?err, "Failed to execute SQL request");
+            return true;
+        }
+    };
+
+    for event in replay::build_state(events) {
+        if tx.send(Ok(Bytes::from(event.to_sse()))).await.is_err() {
+            debug!("Live client disconnected");
+            return false;
+        }
+    }
+
+    true
+}
+
+// Forward the updates of the session until the client disconnects
+async fn push_updates(
+    tx: mpsc::Sender<Result<Bytes, Infallible>>,
+    mut updates: broadcast::Receiver<Arc<LiveUpdate>>,
+    session_key: i32,
+) {
+    let mut keep_alive = tokio::time::interval(KEEP_ALIVE_INTERVAL);
+
+    loop {
+        let event = tokio::select! {
+            update = updates.recv() => match update {
+                Ok(update) if update.session_key == session_key => {
+                    let data = serde_json::json!({ "time": update.time, "data": update.data });
+                    format!("event: {}\ndata: {}\n\n", update.kind, data)
+                }
+                Ok(_) => continue,
+                // Missed updates can't be recovered, the client gets the next ones
+                Err(broadcast::error::RecvError::Lagged(nb_updates)) => {
+                    warn!("Live subscriber lagging, {} updates skipped", nb_updates);
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = keep_alive.tick() => ": keep-alive\n\n".to_string(),
+        };
+
+        if tx.send(Ok(Bytes::from(event))).await.is_err() {
+            debug!("Live client disconnected");
+            break;
+        }
+    }
+}
@@ -1,9 +1,12 @@
 use crate::{
     AppState,
-    models::Session,
-    services::query_preparer::{
-        SqlOperator, SqlType,
-        select::{JoinRow, JoinType, RowType, SelectQuery},
+    models::{MeetingsWeather, Session},
+    services::{
+        jobs,
+        query_preparer::{
+            SqlOperator, SqlType,
+            select::{JoinRow, JoinType, RowType, SelectQuery},
+        },
     },
 };
 use actix_web::{
@@ -11,15 +14,9 @@ use actix_web::{
     web::{self, Data},
 };
 use chrono::Datelike;
-use lapin::{
-    BasicProperties,
-    types::{AMQPValue, FieldTable},
-};
-use opentelemetry::{global, propagation::Injector};
 use serde::Deserialize;
 use sqlx::Execute;
-use tracing::{Span, debug, error, info, trace};
-use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing::{debug, error, info, trace};
 
 use crate::models::Meeting;
 
@@ -57,18 +54,6 @@ impl MeetingsParams {
     }
 }
 
-// TODO: Move to the common crate
-struct AmqpHeaderInjector<'a> {
-    headers: &'a mut FieldTable,
-}
-
-impl<'a> Injector for AmqpHeaderInjector<'a> {
-    fn set(&mut self, key: &str, value: String) {
-        self.headers
-            .insert(key.into(), AMQPValue::LongString(value.into()));
-    }
-}
-
 /* /////////////////////// */
 /* //// HTTP Handlers //// */
 /* /////////////////////// */
@@ -82,7 +67,6 @@ async fn fetch_meetings(
         year: Some(path.into_inner()),
         ..info.into_inner()
     };
-    let rabbitmq = state.rabbitmq.clone();
 
     debug!(parameters = ?params, "Request received with");
     let time = std::time::Instant::now();
@@ -111,9 +95,8 @@ async fn fetch_meetings(
     };
 
     // If meetings are found in the database, send fetched data
-    // But if it's current year, new data might be availaible
-    // So proceed to send a request to fetch new data
-    if meetings.len() > 0 && params.get_year() != chrono::Utc::now().year() {
+    // The calendar of the current season is kept up to date by the worker scheduler
+    if meetings.len() > 0 {
         return HttpResponse::Ok().json(meetings);
     }
 
@@ -123,82 +106,15 @@ async fn fetch_meetings(
         return HttpResponse::Ok().json(meetings);
     }
 
-    // Prepare RabbitMQ payload
-    let meetings_keys = meetings.iter().map(|m| m.key).collect();
-
-    let rabbitmq_payload = metrics_one_queue::models::Meetings {
-        year: params.get_year(),
-        keys: meetings_keys,
-    };
-
-    // Encode payload into JSON
-    let rabbitmq_body = match serde_json::to_vec(&rabbitmq_payload) {
-        Ok(body) => {
-            trace!("Serialized queue payload in {:?}", time.elapsed());
-            body
-        }
-        Err(err) => {
-            error!(error = ?err, "Failed to serialize queue payload");
-            return HttpResponse::Ok().json(serde_json::json!([]));
-        }
-    };
-
-    let mut rabbitmq_headers = FieldTable::default();
-
-    // Inject trace context into RabbitMQ headers
-    let context = Span::current().context();
-    global::get_text_map_propagator(|propagator| {
-        propagator.inject_context(
-            &context,
-            &mut AmqpHeaderInjector {
-                headers: &mut rabbitmq_headers,
-            },
-        )
-    });
-
-    let rabbitmq_request_properties = BasicProperties::default()
-        .with_headers(rabbitmq_headers)
-        .with_content_type("application/json".into());
-
-    // Prepare RabbitMQ request
-    let rabbitmq_request = rabbitmq.basic_publish(
-        "",
-        "fetch.meetings",
-        lapin::options::BasicPublishOptions::default(),
-        &rabbitmq_body,
-        rabbitmq_request_properties,
-    );
-
-    // Send fetch request to the queue
-    let rabbitmq_publish = match rabbitmq_request.await {
-        Ok(publish) => {
-            trace!(
-                "Published meetings fetch request to the queue in {:?}",
-                time.elapsed()
-            );
-            publish
-        }
-        Err(err) => {
-            error!(error = ?err, "Failed to publish meetings fetch request to the queue");
-            return HttpResponse::Ok().json(serde_json::json!([]));
-        }
-    };
-
-    // Check if acknowledgement received
-    // TODO: Check if producer acknowledgement is necessary ?
-    match rabbitmq_publish.await {
-        Ok(_) => {
-            trace!("Acknowledgement received in {:?}", time.elapsed());
-            // If we fetched meetings earlier, send data as a response
-            if meetings.len() > 0 {
-                return HttpResponse::Ok().json(meetings);
-            }
-
-            // Respond with "Accepted" status to indicate the request is being process
-            HttpResponse::Accepted().json(serde_json::json!([]))
-        }
+    // Send fetch request to the queue, coalesced with the one in flight if any
+    match jobs::request_meetings_fetch(&state, params.get_year(), false).await {
+        // Respond with "Accepted" status to indicate the request is being process
+        Ok(Some(job)) => HttpResponse::Accepted()
+            .insert_header((jobs::JOB_ID_HEADER, job))
+            .json(serde_json::json!([])),
+        Ok(None) => HttpResponse::Ok().json(meetings),
         Err(err) => {
-            error!(error = ?err, "Failed to receive queue confirmation");
+            error!(error = ?err, "Failed to request meetings fetch");
             HttpResponse::Ok().json(meetings)
         }
     }
@@ -228,6 +144,17 @@ fn prepare_query(params: &MeetingsParams) -> SelectQuery<Meeting> {
                 (Meeting::SQL_TABLE, "key"),
                 (Session::SQL_TABLE, "meeting_key"),
             ),
+            "weather" => query_builder.add_join(
+                JoinType::LeftJoin,
+                JoinRow::new(
+                    RowType::Single,
+                    MeetingsWeather::SQL_TABLE,
+                    Vec::from(MeetingsWeather::SQL_FIELDS),
+                    "weather",
+                ),
+                (Meeting::SQL_TABLE, "key"),
+                (MeetingsWeather::SQL_TABLE, "meeting_key"),
+            ),
             _ => (),
         }
     }
@@ -1,9 +1,35 @@
+pub mod admin;
+pub mod bests;
+pub mod compare;
 pub mod drivers;
+pub mod laps;
+pub mod live;
 pub mod meetings;
+pub mod pit_stops;
+pub mod race_control;
+pub mod replay;
 pub mod sessions;
+pub mod speed_traps;
+pub mod team_radio;
 pub mod teams;
+pub mod track_maps;
+pub mod track_status;
+pub mod weather;
 
+pub use admin::*;
+pub use bests::*;
+pub use compare::*;
 pub use drivers::*;
+pub use laps::*;
+pub use live::*;
 pub use meetings::*;
+pub use pit_stops::*;
+pub use race_control::*;
+pub use replay::*;
 pub use sessions::*;
+pub use speed_traps::*;
+pub use team_radio::*;
 pub use teams::*;
+pub use track_maps::*;
+pub use track_status::*;
+pub use weather::*;
@@ -0,0 +1,141 @@
+use actix_web::{
+    HttpResponse, Responder, get,
+    web::{self, Data},
+};
+use serde::Deserialize;
+use sqlx::Execute;
+use tracing::{debug, error, info, trace};
+
+use crate::{
+    AppState,
+    models::PitStop,
+    services::{
+        jobs,
+        query_preparer::{SqlOperator, SqlOrder, SqlType, select::SelectQuery},
+    },
+};
+
+/* ///////////////////////// */
+/* //// HTTP Parameters //// */
+/* ///////////////////////// */
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PitStopsParams {
+    pub session: Option<i32>,
+    pub driver: Option<i32>,
+    pub order: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/* /////////////////////// */
+/* //// HTTP Handlers //// */
+/* /////////////////////// */
+
+#[get("/sessions/{key}/pit-stops")]
+pub async fn fetch_pit_stops(
+    state: Data<AppState>,
+    info: web::Query<PitStopsParams>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let params = PitStopsParams {
+        session: Some(path.into_inner()),
+        ..info.into_inner()
+    };
+
+    debug!(parameters = ?params, "Request received with");
+    let time = std::time::Instant::now();
+
+    if params.limit.is_some_and(|limit| limit < 1) {
+        debug!("Invalid limit parameter");
+        return HttpResponse::BadRequest().json(serde_json::json!([]));
+    }
+
+    // Prepare the query
+    let mut query_builder = prepare_query(&params);
+    let query = query_builder.build();
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    // Execute the query
+    let pit_stops = match query.fetch_all(state.db.as_ref()).await {
+        Ok(pit_stops) => {
+            info!(
+                "Fetched {} pit stops successfully in {:?}",
+                pit_stops.len(),
+                time.elapsed()
+            );
+            pit_stops
+        }
+        Err(err) => {
+            error!(error = ?err, "Failed to execute SQL request");
+            return HttpResponse::Ok().json(serde_json::json!([]));
+        }
+    };
+
+    // If there are filters parameters, it might just be a bad filter
+    // So, it doesn't trigger a fetch job even if there are no pit stops
+    if !pit_stops.is_empty() || params.driver.is_some() {
+        return HttpResponse::Ok().json(pit_stops);
+    }
+
+    // No pit stops found, session data might not have been ingested yet
+    match jobs::request_session_fetch(&state, params.session.unwrap_or_default()).await {
+        // Respond with "Accepted" status to indicate the request is being process
+        Ok(Some(job)) => HttpResponse::Accepted()
+            .insert_header((jobs::JOB_ID_HEADER, job))
+            .json(serde_json::json!([])),
+        Ok(None) => HttpResponse::Ok().json(pit_stops),
+        Err(err) => {
+            error!(error = ?err, "Failed to request session fetch");
+            HttpResponse::Ok().json(pit_stops)
+        }
+    }
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+fn prepare_query(params: &PitStopsParams) -> SelectQuery<'_, '_, PitStop> {
+    // Start to prepare the query
+    let mut query_builder =
+        SelectQuery::<PitStop>::new(PitStop::SQL_TABLE, Vec::from(PitStop::SQL_FIELDS));
+
+    // Add 'filters' to the query
+    if let Some(session_key) = params.session {
+        query_builder.add_filter(
+            (PitStop::SQL_TABLE, "session_key"),
+            SqlOperator::Eq,
+            SqlType::Int(session_key),
+        );
+    }
+
+    if let Some(driver_number) = params.driver {
+        query_builder.add_filter(
+            (PitStop::SQL_TABLE, "driver_number"),
+            SqlOperator::Eq,
+            SqlType::Int(driver_number),
+        );
+    }
+
+    // Add 'order' to the query, pit stops without timing are sent last
+    match params.order.as_deref() {
+        Some("duration") => {
+            query_builder.add_sort((PitStop::SQL_TABLE, "lane_duration"), SqlOrder::Asc)
+        }
+        Some("stationary") => {
+            query_builder.add_sort((PitStop::SQL_TABLE, "stationary_duration"), SqlOrder::Asc)
+        }
+        _ => (),
+    }
+
+    query_builder.add_sort((PitStop::SQL_TABLE, "lap"), SqlOrder::Asc);
+    query_builder.add_sort((PitStop::SQL_TABLE, "driver_number"), SqlOrder::Asc);
+
+    if let Some(limit) = params.limit {
+        query_builder.set_limit(limit);
+    }
+
+    query_builder
+}
@@ -0,0 +1,157 @@
+use actix_web::{
+    HttpResponse, Responder, get,
+    web::{self, Data},
+};
+use serde::Deserialize;
+use sqlx::Execute;
+use tracing::{debug, error, info, trace};
+
+use crate::{
+    AppState,
+    models::RaceControlMessage,
+    services::{
+        jobs,
+        query_preparer::{SqlOperator, SqlOrder, SqlType, select::SelectQuery},
+    },
+};
+
+/* ///////////////////////// */
+/* //// HTTP Parameters //// */
+/* ///////////////////////// */
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RaceControlParams {
+    pub session: Option<i32>,
+    pub category: Option<String>,
+    pub flag: Option<String>,
+    pub driver: Option<i32>,
+    pub lap: Option<i32>,
+}
+
+impl RaceControlParams {
+    fn has_filters(&self) -> bool {
+        self.category.is_some()
+            || self.flag.is_some()
+            || self.driver.is_some()
+            || self.lap.is_some()
+    }
+}
+
+/* /////////////////////// */
+/* //// HTTP Handlers //// */
+/* /////////////////////// */
+
+#[get("/sessions/{key}/race-control")]
+pub async fn fetch_race_control(
+    state: Data<AppState>,
+    info: web::Query<RaceControlParams>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let params = RaceControlParams {
+        session: Some(path.into_inner()),
+        ..info.into_inner()
+    };
+
+    debug!(parameters = ?params, "Request received with");
+    let time = std::time::Instant::now();
+
+    // Prepare the query
+    let mut query_builder = prepare_query(&params);
+    let query = query_builder.build();
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    // Execute the query
+    let messages = match query.fetch_all(state.db.as_ref()).await {
+        Ok(messages) => {
+            info!(
+                "Fetched {} race control messages successfully in {:?}",
+                messages.len(),
+                time.elapsed()
+            );
+            messages
+        }
+        Err(err) => {
+            error!(error = ?err, "Failed to execute SQL request");
+            return HttpResponse::Ok().json(serde_json::json!([]));
+        }
+    };
+
+    // If there are filters parameters, it might just be a bad filter
+    // So, it doesn't trigger a fetch job even if there are no messages
+    if !messages.is_empty() || params.has_filters() {
+        return HttpResponse::Ok().json(messages);
+    }
+
+    // No messages found, session data might not have been ingested yet
+    match jobs::request_session_fetch(&state, params.session.unwrap_or_default()).await {
+        // Respond with "Accepted" status to indicate the request is being process
+        Ok(Some(job)) => HttpResponse::Accepted()
+            .insert_header((jobs::JOB_ID_HEADER, job))
+            .json(serde_json::json!([])),
+        Ok(None) => HttpResponse::Ok().json(messages),
+        Err(err) => {
+            error!(error = ?err, "Failed to request session fetch");
+            HttpResponse::Ok().json(messages)
+        }
+    }
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+fn prepare_query(params: &RaceControlParams) -> SelectQuery<'_, '_, RaceControlMessage> {
+    // Start to prepare the query
+    let mut query_builder = SelectQuery::<RaceControlMessage>::new(
+        RaceControlMessage::SQL_TABLE,
+        Vec::from(RaceControlMessage::SQL_FIELDS),
+    );
+
+    // Add 'filters' to the query
+    if let Some(session_key) = params.session {
+        query_builder.add_filter(
+            (RaceControlMessage::SQL_TABLE, "session_key"),
+            SqlOperator::Eq,
+            SqlType::Int(session_key),
+        );
+    }
+
+    if let Some(category) = &params.category {
+        query_builder.add_filter(
+            (RaceControlMessage::SQL_TABLE, "category"),
+            SqlOperator::ILike,
+            SqlType::Text(category.clone()),
+        );
+    }
+
+    if let Some(flag) = &params.flag {
+        query_builder.add_filter(
+            (RaceControlMessage::SQL_TABLE, "flag"),
+            SqlOperator::ILike,
+            SqlType::Text(flag.clone()),
+        );
+    }
+
+    if let Some(driver_number) = params.driver {
+        query_builder.add_filter(
+            (RaceControlMessage::SQL_TABLE, "driver_number"),
+            SqlOperator::Eq,
+            SqlType::Int(driver_number),
+        );
+    }
+
+    if let Some(lap) = params.lap {
+        query_builder.add_filter(
+            (RaceControlMessage::SQL_TABLE, "lap"),
+            SqlOperator::Eq,
+            SqlType::Int(lap),
+        );
+    }
+
+    // Add 'order' to the query
+    query_builder.add_sort((RaceControlMessage::SQL_TABLE, "date"), SqlOrder::Asc);
+
+    query_builder
+}
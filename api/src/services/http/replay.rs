@@ -0,0 +1,401 @@
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use actix_web::{
+    HttpResponse, Responder, get,
+    web::{self, Bytes, Data},
+};
+use serde::Deserialize;
+use sqlx::{Execute, Pool, Postgres};
+use tokio::{sync::mpsc, time::Instant};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, error, info, trace};
+
+use crate::{
+    AppState,
+    models::{RaceControlMessage, ReplayEvent, ReplayLap, ReplayPosition, TrackStatus},
+    services::jobs,
+};
+
+// Fastest replay speed allowed, so a client can't flood the connection
+const MAX_SPEED: f64 = 64.0;
+
+// Positions are read by pages of this many milliseconds of the session, as they are replayed
+const POSITIONS_PAGE: i32 = 60_000;
+
+// Same computation as the gaps, ordered by line crossing time to be replayed
+const LAPS_QUERY: &str = "\
+    SELECT driver_number, lap_number, time, lap_time, position, gap_to_leader, interval \
+    FROM ( \
+        SELECT \
+            driver_number, \
+            lap_number, \
+            time, \
+            lap_time, \
+            RANK() OVER (PARTITION BY lap_number ORDER BY time)::integer AS position, \
+            time - MIN(time) OVER (PARTITION BY lap_number) AS gap_to_leader, \
+            time - LAG(time) OVER (PARTITION BY lap_number ORDER BY time) AS interval \
+        FROM laps \
+        WHERE session_key = $1 \
+    ) AS tower \
+    ORDER BY time ASC";
+
+const RACE_CONTROL_QUERY: &str = "\
+    SELECT \
+        session_key, date, time, lap, category, flag, scope, sector, \
+        driver_number, status, mode, message, deleted_lap \
+    FROM race_control_messages \
+    WHERE session_key = $1 \
+    ORDER BY time ASC";
+
+const TRACK_STATUS_QUERY: &str = "\
+    SELECT session_key, status, message, start_time, end_time, start_lap, end_lap \
+    FROM track_status \
+    WHERE session_key = $1 \
+    ORDER BY start_time ASC";
+
+// Replay starts from the last sample before 'from', for the state to show the cars
+const POSITIONS_RANGE_QUERY: &str = "\
+    SELECT \
+        COALESCE(MAX(samples.time) FILTER (WHERE samples.time < $2), $2), \
+        MAX(samples.time) \
+    FROM car_positions, unnest(car_positions.time) AS samples(time) \
+    WHERE car_positions.session_key = $1";
+
+const POSITIONS_PAGE_QUERY: &str = "\
+    SELECT car_positions.driver_number, samples.time, samples.x, samples.y, samples.z \
+    FROM \
+        car_positions, \
+        unnest(car_positions.time, car_positions.x, car_positions.y, car_positions.z) \
+            AS samples(time, x, y, z) \
+    WHERE car_positions.session_key = $1 \
+    AND samples.time >= $2 \
+    AND samples.time < $3 \
+    ORDER BY samples.time ASC, car_positions.driver_number ASC";
+
+/* ///////////////////////// */
+/* //// HTTP Parameters //// */
+/* ///////////////////////// */
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReplayParams {
+    pub speed: Option<f64>,
+    pub from: Option<i32>,
+}
+
+/* /////////////////////// */
+/* //// HTTP Handlers //// */
+/* /////////////////////// */
+
+// Replays the stored session as Server-Sent Events, 'from' being an offset in milliseconds
+// Positions of every car are sent as one event per sample time
+// The timing tower, track status and car positions at 'from' are sent first, so a seek shows a complete state
+#[get("/sessions/{key}/replay")]
+pub async fn fetch_replay(
+    state: Data<AppState>,
+    info: web::Query<ReplayParams>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let session_key = path.into_inner();
+    let params = info.into_inner();
+
+    debug!(parameters = ?params, session_key, "Request received with");
+    let time = std::time::Instant::now();
+
+    let speed = params.speed.unwrap_or(1.0);
+    if !(speed > 0.0 && speed <= MAX_SPEED) {
+        debug!("Invalid speed parameter");
+        return HttpResponse::BadRequest().json(serde_json::json!({}));
+    }
+    let from = params.from.unwrap_or_default().max(0);
+
+    // Execute the queries, positions being read as they are replayed
+    let fetched = tokio::try_join!(
+        fetch_timing_events(&state, session_key),
+        PositionPages::new(state.db.clone(), session_key, from),
+    );
+    let (events, positions) = match fetched {
+        Ok(fetched) => {
+            info!(
+                "Fetched {} replay events successfully in {:?}",
+                fetched.0.len(),
+                time.elapsed()
+            );
+            fetched
+        }
+        Err(err) => {
+            error!(error = ?err, "Failed to execute SQL request");
+            return HttpResponse::Ok().json(serde_json::json!([]));
+        }
+    };
+
+    if events.is_empty() && positions.is_empty() {
+        // No events found, session data might not have been ingested yet
+        return match jobs::request_session_fetch(&state, session_key).await {
+            // Respond with "Accepted" status to indicate the request is being process
+            Ok(Some(job)) => HttpResponse::Accepted()
+                .insert_header((jobs::JOB_ID_HEADER, job))
+                .json(serde_json::json!([])),
+            Ok(None) => HttpResponse::Ok().json(serde_json::json!([])),
+            Err(err) => {
+                error!(error = ?err, "Failed to request session fetch");
+                HttpResponse::Ok().json(serde_json::json!([]))
+            }
+        };
+    }
+
+    let (tx, rx) = mpsc::channel::<Result<Bytes, Infallible>>(16);
+    tokio::spawn(replay(tx, events, positions, from, speed));
+    trace!("Replay started for session {}", session_key);
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(ReceiverStream::new(rx))
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+// Laps, race control messages and track statuses of the session, ordered on the stream timeline
+pub(crate) async fn fetch_timing_events(
+    state: &AppState,
+    session_key: i32,
+) -> Result<Vec<ReplayEvent>, sqlx::Error> {
+    let db = state.db.as_ref();
+
+    let laps = sqlx::query_as::<_, ReplayLap>(LAPS_QUERY)
+        .bind(session_key)
+        .fetch_all(db)
+        .await?;
+    let messages = sqlx::query_as::<_, RaceControlMessage>(RACE_CONTROL_QUERY)
+        .bind(session_key)
+        .fetch_all(db)
+        .await?;
+    let statuses = sqlx::query_as::<_, TrackStatus>(TRACK_STATUS_QUERY)
+        .bind(session_key)
+        .fetch_all(db)
+        .await?;
+
+    let mut events: Vec<ReplayEvent> = Vec::new();
+    events.extend(laps.iter().map(|l| ReplayEvent {
+        time: l.time,
+        kind: "lap",
+        data: serde_json::json!(l),
+    }));
+    events.extend(messages.iter().map(|m| ReplayEvent {
+        time: m.time,
+        kind: "race_control",
+        data: serde_json::json!(m),
+    }));
+    events.extend(statuses.iter().map(|s| ReplayEvent {
+        time: s.start_time,
+        kind: "track_status",
+        data: serde_json::json!(s),
+    }));
+
+    events.sort_by_key(|e| e.time);
+
+    Ok(events)
+}
+
+// Send the events at their time scaled by the speed, until the client disconnects
+// Positions are merged with the other events as their pages are read
+async fn replay(
+    tx: mpsc::Sender<Result<Bytes, Infallible>>,
+    events: Vec<ReplayEvent>,
+    mut positions: PositionPages,
+    from: i32,
+    speed: f64,
+) {
+    let (mut past, upcoming): (Vec<ReplayEvent>, Vec<ReplayEvent>) =
+        events.into_iter().partition(|e| e.time < from);
+
+    let mut position = positions.next_logged().await;
+    if position.as_ref().is_some_and(|p| p.time < from) {
+        past.extend(position.take());
+        position = positions.next_logged().await;
+    }
+
+    for event in build_state(past) {
+        if tx.send(Ok(Bytes::from(event.to_sse()))).await.is_err() {
+            return;
+        }
+    }
+
+    let start = Instant::now();
+    let mut upcoming = upcoming.into_iter().peekable();
+    loop {
+        // Events at the same time as positions are sent first
+        let is_position = match (upcoming.peek(), position.as_ref()) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some(e), Some(p)) => p.time < e.time,
+        };
+        let event = if is_position {
+            let next = positions.next_logged().await;
+            std::mem::replace(&mut position, next)
+        } else {
+            upcoming.next()
+        };
+        let Some(event) = event else {
+            break;
+        };
+
+        let delay = f64::from(event.time - from) / speed;
+        let deadline = start + std::time::Duration::from_millis(delay as u64);
+
+        // The client might disconnect while waiting for a distant event
+        tokio::select! {
+            _ = tx.closed() => {
+                debug!("Replay client disconnected");
+                return;
+            }
+            _ = tokio::time::sleep_until(deadline) => {}
+        }
+
+        if tx.send(Ok(Bytes::from(event.to_sse()))).await.is_err() {
+            debug!("Replay client disconnected");
+            return;
+        }
+    }
+
+    let end = ReplayEvent {
+        time: 0,
+        kind: "end",
+        data: serde_json::json!({}),
+    };
+    let _ = tx.send(Ok(Bytes::from(end.to_sse()))).await;
+}
+
+// Only the last line of each driver, the last track status and the last positions describe
+// the state once the events happened
+pub(crate) fn build_state(events: Vec<ReplayEvent>) -> Vec<ReplayEvent> {
+    let mut tower: HashMap<i32, ReplayEvent> = HashMap::new();
+    let mut track_status: Option<ReplayEvent> = None;
+    let mut position: Option<ReplayEvent> = None;
+    for event in events {
+        match event.kind {
+            "lap" => {
+                let driver_number = event.data["driver_number"].as_i64().unwrap_or_default();
+                tower.insert(driver_number as i32, event);
+            }
+            "track_status" => track_status = Some(event),
+            "position" => position = Some(event),
+            _ => {}
+        }
+    }
+
+    let mut state: Vec<ReplayEvent> = tower
+        .into_values()
+        .chain(track_status)
+        .chain(position)
+        .collect();
+    state.sort_by_key(|e| e.time);
+
+    state
+}
+
+/* //////////////////////// */
+/* //// Position Pages //// */
+/* //////////////////////// */
+
+// Samples of the cars from the last one before 'from', grouped by time as one event each
+// A page is only read once the previous one is replayed, so memory doesn't grow with the session
+struct PositionPages {
+    db: Arc<Pool<Postgres>>,
+    session_key: i32,
+    next: i32,
+    end: Option<i32>,
+    buffer: VecDeque<ReplayEvent>,
+}
+
+impl PositionPages {
+    async fn new(
+        db: Arc<Pool<Postgres>>,
+        session_key: i32,
+        from: i32,
+    ) -> Result<Self, sqlx::Error> {
+        let query = sqlx::query_as::<_, (i32, Option<i32>)>(POSITIONS_RANGE_QUERY)
+            .bind(session_key)
+            .bind(from);
+        debug!("SQL query - {}", query.sql());
+        let (start, end) = query.fetch_one(db.as_ref()).await?;
+
+        Ok(PositionPages {
+            db,
+            session_key,
+            next: start,
+            end,
+            buffer: VecDeque::new(),
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.end.is_none()
+    }
+
+    async fn next(&mut self) -> Result<Option<ReplayEvent>, sqlx::Error> {
+        while self.buffer.is_empty() {
+            let Some(end) = self.end.filter(|end| self.next <= *end) else {
+                return Ok(None);
+            };
+            let page_end = self
+                .next
+                .saturating_add(POSITIONS_PAGE)
+                .min(end.saturating_add(1));
+
+            let query = sqlx::query_as::<_, (i32, i32, i32, i32, i32)>(POSITIONS_PAGE_QUERY)
+                .bind(self.session_key)
+                .bind(self.next)
+                .bind(page_end);
+            debug!("SQL query - {}", query.sql());
+            let samples = query.fetch_all(self.db.as_ref()).await?;
+            self.next = page_end;
+
+            // Cars are sampled at the same times, so their positions are grouped by time
+            let mut group: Option<(i32, Vec<ReplayPosition>)> = None;
+            for (driver_number, time, x, y, z) in samples {
+                if group.as_ref().is_some_and(|(t, _)| *t != time) {
+                    self.buffer.extend(group.take().map(to_event));
+                }
+
+                let position = ReplayPosition {
+                    driver_number,
+                    x,
+                    y,
+                    z,
+                };
+                group
+                    .get_or_insert_with(|| (time, Vec::new()))
+                    .1
+                    .push(position);
+            }
+            self.buffer.extend(group.map(to_event));
+        }
+
+        Ok(self.buffer.pop_front())
+    }
+
+    // Positions are left out of the replay once one page fails to be read
+    async fn next_logged(&mut self) -> Option<ReplayEvent> {
+        match self.next().await {
+            Ok(event) => event,
+            Err(err) => {
+                error!(error = ?err, "Failed to read replay positions");
+                self.end = None;
+                None
+            }
+        }
+    }
+}
+
+fn to_event((time, positions): (i32, Vec<ReplayPosition>)) -> ReplayEvent {
+    ReplayEvent {
+        time,
+        kind: "position",
+        data: serde_json::json!(positions),
+    }
+}
@@ -1,6 +1,10 @@
 use crate::{
     AppState,
-    services::query_preparer::{SqlOperator, SqlType, select::SelectQuery},
+    models::SessionsWeather,
+    services::query_preparer::{
+        SqlOperator, SqlType,
+        select::{JoinRow, JoinType, RowType, SelectQuery},
+    },
 };
 use actix_web::{
     HttpResponse, Responder, get,
@@ -20,6 +24,18 @@ use crate::models::Session;
 struct SessionsParams {
     pub key: Option<i32>,
     pub meeting: Option<i32>,
+    pub expand: Option<String>,
+}
+
+impl SessionsParams {
+    pub fn get_expands(&self) -> Vec<&str> {
+        if let Some(expands) = &self.expand {
+            return expands.split(",").collect();
+        }
+
+        // Dafault to an empty vector
+        Vec::new()
+    }
 }
 
 /* /////////////////////// */
@@ -66,6 +82,24 @@ fn prepare_query(params: &SessionsParams) -> SelectQuery<Session> {
     let mut query_builder =
         SelectQuery::<Session>::new(Session::SQL_TABLE, Vec::from(Session::SQL_FIELDS));
 
+    // Add 'expands' to the query
+    let expands = params.get_expands();
+    for exp in expands {
+        if exp == "weather" {
+            query_builder.add_join(
+                JoinType::LeftJoin,
+                JoinRow::new(
+                    RowType::Single,
+                    SessionsWeather::SQL_TABLE,
+                    Vec::from(SessionsWeather::SQL_FIELDS),
+                    "weather",
+                ),
+                (Session::SQL_TABLE, "key"),
+                (SessionsWeather::SQL_TABLE, "session_key"),
+            );
+        }
+    }
+
     // Add 'filters' to the query
     if let Some(key) = params.key {
         query_builder.add_filter(
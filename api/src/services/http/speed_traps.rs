@@ -0,0 +1,108 @@
+use actix_web::{
+    HttpResponse, Responder, get,
+    web::{self, Data},
+};
+use serde::Deserialize;
+use sqlx::{Execute, Postgres, postgres::PgArguments, query::QueryAs};
+use tracing::{debug, error, info, trace};
+
+use crate::{
+    AppState,
+    models::Lap,
+    services::{analytics, jobs},
+};
+
+const LAPS_QUERY: &str = "\
+    SELECT \
+        session_key, driver_number, lap_number, time, lap_time, sector_1, sector_2, sector_3, \
+        speed_i1, speed_i2, speed_fl, speed_st \
+    FROM laps \
+    WHERE session_key = $1 \
+    AND ($2::integer IS NULL OR lap_number >= $2) \
+    AND ($3::integer IS NULL OR lap_number <= $3) \
+    ORDER BY lap_number ASC";
+
+/* ///////////////////////// */
+/* //// HTTP Parameters //// */
+/* ///////////////////////// */
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpeedTrapsParams {
+    pub session: Option<i32>,
+    pub from_lap: Option<i32>,
+    pub to_lap: Option<i32>,
+}
+
+/* /////////////////////// */
+/* //// HTTP Handlers //// */
+/* /////////////////////// */
+
+#[get("/sessions/{key}/speed-traps")]
+pub async fn fetch_speed_traps(
+    state: Data<AppState>,
+    info: web::Query<SpeedTrapsParams>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let params = SpeedTrapsParams {
+        session: Some(path.into_inner()),
+        ..info.into_inner()
+    };
+
+    debug!(parameters = ?params, "Request received with");
+    let time = std::time::Instant::now();
+
+    // Prepare the query
+    let query = prepare_query(&params);
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    // Execute the query
+    let laps = match query.fetch_all(state.db.as_ref()).await {
+        Ok(laps) => {
+            trace!("Fetched {} laps in {:?}", laps.len(), time.elapsed());
+            laps
+        }
+        Err(err) => {
+            error!(error = ?err, "Failed to execute SQL request");
+            return HttpResponse::Ok().json(serde_json::json!({}));
+        }
+    };
+
+    let speed_traps = analytics::speed_traps::compute(&laps);
+    info!(
+        "Computed speed traps of {} drivers successfully in {:?}",
+        speed_traps.drivers.len(),
+        time.elapsed()
+    );
+
+    // If there are filters parameters, it might just be a bad filter
+    // So, it doesn't trigger a fetch job even if there are no laps
+    if !laps.is_empty() || params.from_lap.is_some() || params.to_lap.is_some() {
+        return HttpResponse::Ok().json(speed_traps);
+    }
+
+    // No laps found, session data might not have been ingested yet
+    match jobs::request_session_fetch(&state, params.session.unwrap_or_default()).await {
+        // Respond with "Accepted" status to indicate the request is being process
+        Ok(Some(job)) => HttpResponse::Accepted()
+            .insert_header((jobs::JOB_ID_HEADER, job))
+            .json(speed_traps),
+        Ok(None) => HttpResponse::Ok().json(speed_traps),
+        Err(err) => {
+            error!(error = ?err, "Failed to request session fetch");
+            HttpResponse::Ok().json(speed_traps)
+        }
+    }
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+fn prepare_query(params: &SpeedTrapsParams) -> QueryAs<'static, Postgres, Lap, PgArguments> {
+    sqlx::query_as::<_, Lap>(LAPS_QUERY)
+        .bind(params.session.unwrap_or_default())
+        .bind(params.from_lap)
+        .bind(params.to_lap)
+}
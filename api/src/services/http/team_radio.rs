@@ -0,0 +1,163 @@
+use actix_web::{
+    HttpResponse, Responder, get,
+    web::{self, Data},
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sqlx::Execute;
+use tracing::{debug, error, info, trace};
+
+use crate::{
+    AppState,
+    models::TeamRadio,
+    services::{
+        jobs,
+        query_preparer::{SqlOperator, SqlOrder, SqlType, select::SelectQuery},
+    },
+    settings::ENV,
+};
+
+/* ///////////////////////// */
+/* //// HTTP Parameters //// */
+/* ///////////////////////// */
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TeamRadioParams {
+    pub session: Option<i32>,
+    pub driver: Option<i32>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/* /////////////////////// */
+/* //// HTTP Handlers //// */
+/* /////////////////////// */
+
+#[get("/sessions/{key}/team-radio")]
+pub async fn fetch_team_radio(
+    state: Data<AppState>,
+    info: web::Query<TeamRadioParams>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let session_key = path.into_inner();
+    let params = TeamRadioParams {
+        session: Some(session_key),
+        ..info.into_inner()
+    };
+
+    debug!(parameters = ?params, "Request received with");
+    let time = std::time::Instant::now();
+
+    // The session path is needed to build the audio URLs
+    let session = match jobs::find_session(state.db.as_ref(), session_key).await {
+        Ok(Some(session)) => session,
+        Ok(None) => {
+            debug!("Session {} not found", session_key);
+            return HttpResponse::Ok().json(serde_json::json!([]));
+        }
+        Err(err) => {
+            error!(error = ?err, "Failed to execute SQL request");
+            return HttpResponse::Ok().json(serde_json::json!([]));
+        }
+    };
+    trace!("Session fetched in {:?}", time.elapsed());
+
+    // Prepare the query
+    let mut query_builder = prepare_query(&params);
+    let query = query_builder.build();
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    // Execute the query
+    let mut captures = match query.fetch_all(state.db.as_ref()).await {
+        Ok(captures) => {
+            info!(
+                "Fetched {} team radio captures successfully in {:?}",
+                captures.len(),
+                time.elapsed()
+            );
+            captures
+        }
+        Err(err) => {
+            error!(error = ?err, "Failed to execute SQL request");
+            return HttpResponse::Ok().json(serde_json::json!([]));
+        }
+    };
+
+    // Resolve absolute URLs, same layout as the files fetched by the worker
+    for c in captures.iter_mut() {
+        c.url = format!("{}/{}{}", ENV.livetiming_url, session.path, c.path);
+    }
+
+    // If there are filters parameters, it might just be a bad filter
+    // So, it doesn't trigger a fetch job even if there are no captures
+    if !captures.is_empty()
+        || params.driver.is_some()
+        || params.from.is_some()
+        || params.to.is_some()
+    {
+        return HttpResponse::Ok().json(captures);
+    }
+
+    // No captures found, session data might not have been ingested yet
+    match jobs::request_session_fetch(&state, session_key).await {
+        // Respond with "Accepted" status to indicate the request is being process
+        Ok(Some(job)) => HttpResponse::Accepted()
+            .insert_header((jobs::JOB_ID_HEADER, job))
+            .json(serde_json::json!([])),
+        Ok(None) => HttpResponse::Ok().json(captures),
+        Err(err) => {
+            error!(error = ?err, "Failed to request session fetch");
+            HttpResponse::Ok().json(captures)
+        }
+    }
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+fn prepare_query(params: &TeamRadioParams) -> SelectQuery<'_, '_, TeamRadio> {
+    // Start to prepare the query
+    let mut query_builder =
+        SelectQuery::<TeamRadio>::new(TeamRadio::SQL_TABLE, Vec::from(TeamRadio::SQL_FIELDS));
+
+    // Add 'filters' to the query
+    if let Some(session_key) = params.session {
+        query_builder.add_filter(
+            (TeamRadio::SQL_TABLE, "session_key"),
+            SqlOperator::Eq,
+            SqlType::Int(session_key),
+        );
+    }
+
+    if let Some(driver_number) = params.driver {
+        query_builder.add_filter(
+            (TeamRadio::SQL_TABLE, "driver_number"),
+            SqlOperator::Eq,
+            SqlType::Int(driver_number),
+        );
+    }
+
+    if let Some(from) = params.from {
+        query_builder.add_filter(
+            (TeamRadio::SQL_TABLE, "date"),
+            SqlOperator::Sup,
+            SqlType::Timestamp(from),
+        );
+    }
+
+    if let Some(to) = params.to {
+        query_builder.add_filter(
+            (TeamRadio::SQL_TABLE, "date"),
+            SqlOperator::Inf,
+            SqlType::Timestamp(to),
+        );
+    }
+
+    // Add 'order' to the query
+    query_builder.add_sort((TeamRadio::SQL_TABLE, "date"), SqlOrder::Asc);
+
+    query_builder
+}
@@ -9,13 +9,58 @@ use tracing::{debug, error, info, trace};
 
 use crate::{
     AppState,
-    models::{Driver, Team, TeamsImages},
-    services::query_preparer::{
-        SqlOperator, SqlType,
-        select::{JoinRow, JoinType, RowType, SelectQuery},
+    models::{Driver, DriverSessionResult, HeadToHead, Team, TeamsImages},
+    services::{
+        analytics, jobs,
+        query_preparer::{
+            SqlOperator, SqlType,
+            select::{JoinRow, JoinType, RowType, SelectQuery},
+        },
     },
 };
 
+// Results of the season's main sessions raced for the team, best lap times exclude laps deleted by race control
+const RESULTS_QUERY: &str = "\
+    SELECT \
+        session_results.session_key, \
+        sessions.name AS session_name, \
+        session_results.driver_number, \
+        session_results.position, \
+        session_results.retired, \
+        ( \
+            SELECT MIN(laps.lap_time) FROM laps \
+            WHERE laps.session_key = session_results.session_key \
+            AND laps.driver_number = session_results.driver_number \
+            AND NOT EXISTS ( \
+                SELECT 1 FROM race_control_messages \
+                WHERE race_control_messages.session_key = laps.session_key \
+                AND race_control_messages.driver_number = laps.driver_number \
+                AND race_control_messages.deleted_lap = laps.lap_number \
+            ) \
+        ) AS best_lap_time \
+    FROM session_results \
+    JOIN sessions ON sessions.key = session_results.session_key \
+    JOIN meetings ON meetings.key = sessions.meeting_key \
+    JOIN teams ON teams.id = session_results.team_id \
+    WHERE meetings.year = $1 \
+    AND sessions.name IN ('Qualifying', 'Sprint', 'Race') \
+    AND teams.url = $2 \
+    ORDER BY sessions.start_date ASC";
+
+// Main sessions of the season over without results, possibly not ingested yet
+const MISSING_RESULTS_QUERY: &str = "\
+    SELECT sessions.key \
+    FROM sessions \
+    JOIN meetings ON meetings.key = sessions.meeting_key \
+    WHERE meetings.year = $1 \
+    AND sessions.name IN ('Qualifying', 'Sprint', 'Race') \
+    AND sessions.end_date < NOW() \
+    AND NOT EXISTS ( \
+        SELECT 1 FROM session_results \
+        WHERE session_results.session_key = sessions.key \
+    ) \
+    ORDER BY sessions.start_date ASC";
+
 /* ///////////////////////// */
 /* //// HTTP Parameters //// */
 /* ///////////////////////// */
@@ -120,10 +165,120 @@ pub async fn fetch_team_by_name(
     HttpResponse::Ok().json(serde_json::json!({}))
 }
 
+#[get("/{year}/teams/{name}/head-to-head")]
+pub async fn fetch_team_head_to_head(
+    state: Data<AppState>,
+    path: web::Path<(i32, String)>,
+) -> impl Responder {
+    let path = path.into_inner();
+    let params = TeamsParams {
+        year: Some(path.0),
+        name: Some(path.1),
+        expand: Some(String::from("drivers")),
+    };
+
+    debug!(paramters = ?params, "Request received with");
+    let time = std::time::Instant::now();
+
+    // Prepare the query, drivers are needed to compare them
+    let mut query_builder = prepare_query(&params);
+    let query = query_builder.build();
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    // Execute the query
+    let team = match query.fetch_optional(state.db.as_ref()).await {
+        Ok(Some(team)) => team,
+        Ok(None) => {
+            info!("Fetched 0 team successfully in {:?}", time.elapsed());
+            return HttpResponse::Ok().json(serde_json::json!({}));
+        }
+        Err(err) => {
+            error!(error = ?err, "Failed to execute SQL request");
+            return HttpResponse::Ok().json(serde_json::json!({}));
+        }
+    };
+    trace!("Team fetched in {:?}", time.elapsed());
+
+    let query = sqlx::query_as::<_, DriverSessionResult>(RESULTS_QUERY)
+        .bind(utils::get_year(params.year))
+        .bind(params.name.as_deref().unwrap_or_default());
+
+    debug!("SQL query - {}", query.sql());
+
+    let results = match query.fetch_all(state.db.as_ref()).await {
+        Ok(results) => {
+            trace!("Fetched {} results in {:?}", results.len(), time.elapsed());
+            results
+        }
+        Err(err) => {
+            error!(error = ?err, "Failed to execute SQL request");
+            return HttpResponse::Ok().json(serde_json::json!({}));
+        }
+    };
+
+    if results.is_empty() {
+        // No results found, sessions of the season might not have been ingested yet
+        return match request_results_fetch(&state, utils::get_year(params.year)).await {
+            // Respond with "Accepted" status to indicate the request is being process
+            Ok(Some(job)) => HttpResponse::Accepted()
+                .insert_header((jobs::JOB_ID_HEADER, job))
+                .json(serde_json::json!({})),
+            Ok(None) => HttpResponse::Ok().json(serde_json::json!({})),
+            Err(err) => {
+                error!(error = ?err, "Failed to request sessions fetch");
+                HttpResponse::Ok().json(serde_json::json!({}))
+            }
+        };
+    }
+
+    // Drivers of the team are the ones of its lineup and the ones who raced for it during the season
+    let mut driver_numbers: Vec<i32> = team
+        .drivers
+        .as_ref()
+        .map(|drivers| drivers.iter().map(|d| d.number).collect())
+        .unwrap_or_default();
+    driver_numbers.extend(results.iter().map(|r| r.driver_number));
+    driver_numbers.sort();
+    driver_numbers.dedup();
+
+    let (drivers, duels) = analytics::head_to_head::compute(&driver_numbers, &results);
+    info!(
+        "Computed head-to-head of {} drivers successfully in {:?}",
+        drivers.len(),
+        time.elapsed()
+    );
+
+    HttpResponse::Ok().json(HeadToHead {
+        team,
+        drivers,
+        duels,
+    })
+}
+
 /* ///////////////// */
 /* //// Helpers //// */
 /* ///////////////// */
 
+// Request the fetch of every main session of the season without results
+// The first job is returned, sessions already ingested being skipped
+async fn request_results_fetch(
+    state: &AppState,
+    year: i32,
+) -> Result<Option<i32>, Box<dyn std::error::Error>> {
+    let query = sqlx::query_scalar::<_, i32>(MISSING_RESULTS_QUERY).bind(year);
+    debug!("SQL query - {}", query.sql());
+
+    let mut first_job = None;
+    for session_key in query.fetch_all(state.db.as_ref()).await? {
+        let job = jobs::request_session_fetch(state, session_key).await?;
+        first_job = first_job.or(job);
+    }
+
+    Ok(first_job)
+}
+
 fn prepare_query(params: &TeamsParams) -> SelectQuery<Team> {
     // Start to prepare the query
     let mut query_builder = SelectQuery::<Team>::new(Team::SQL_TABLE, Vec::from(Team::SQL_FIELDS));
@@ -0,0 +1,98 @@
+use actix_web::{
+    HttpResponse, Responder, get,
+    web::{self, Data},
+};
+use serde::Deserialize;
+use sqlx::{Execute, Postgres, postgres::PgArguments, query::QueryAs};
+use tracing::{debug, error, info, trace};
+
+use crate::{
+    AppState,
+    models::{TrackMap, TrackMapFeature},
+    services::jobs,
+};
+
+const TRACK_MAP_QUERY: &str = "\
+    SELECT \
+        track_maps.meeting_key, \
+        track_maps.session_key, \
+        track_maps.rotation, \
+        track_maps.x, \
+        track_maps.y, \
+        track_maps.sectors \
+    FROM track_maps \
+    JOIN meetings ON meetings.key = track_maps.meeting_key \
+    WHERE meetings.year = $1 \
+    AND track_maps.meeting_key = $2";
+
+/* ///////////////////////// */
+/* //// HTTP Parameters //// */
+/* ///////////////////////// */
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TrackMapParams {
+    pub year: i32,
+    pub meeting: i32,
+}
+
+/* /////////////////////// */
+/* //// HTTP Handlers //// */
+/* /////////////////////// */
+
+// Outline of the circuit with the start of its 3 timing sectors only
+// Marshal sectors and corners are not part of the live timing feeds, so they are not provided
+#[get("/{year}/meetings/{key}/track")]
+pub async fn fetch_track_map(state: Data<AppState>, path: web::Path<(i32, i32)>) -> impl Responder {
+    let path = path.into_inner();
+    let params = TrackMapParams {
+        year: path.0,
+        meeting: path.1,
+    };
+
+    debug!(parameters = ?params, "Request received with");
+    let time = std::time::Instant::now();
+
+    // Prepare the query
+    let query = prepare_query(&params);
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    // Execute the query, track maps being computed once per meeting
+    match query.fetch_optional(state.db.as_ref()).await {
+        Ok(Some(map)) => {
+            info!("Fetched 1 track map successfully in {:?}", time.elapsed());
+            return HttpResponse::Ok().json(TrackMapFeature::from(map));
+        }
+        Ok(None) => {
+            info!("Fetched 0 track map successfully in {:?}", time.elapsed());
+        }
+        Err(err) => {
+            error!(error = ?err, "Failed to execute SQL request");
+            return HttpResponse::Ok().json(serde_json::json!({}));
+        }
+    };
+
+    // No track map found, it might not have been drawn yet
+    match jobs::request_track_map_fetch(&state, params.meeting, false).await {
+        // Respond with "Accepted" status to indicate the request is being process
+        Ok(Some(job)) => HttpResponse::Accepted()
+            .insert_header((jobs::JOB_ID_HEADER, job))
+            .json(serde_json::json!({})),
+        Ok(None) => HttpResponse::Ok().json(serde_json::json!({})),
+        Err(err) => {
+            error!(error = ?err, "Failed to request track map fetch");
+            HttpResponse::Ok().json(serde_json::json!({}))
+        }
+    }
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+fn prepare_query(params: &TrackMapParams) -> QueryAs<'static, Postgres, TrackMap, PgArguments> {
+    sqlx::query_as::<_, TrackMap>(TRACK_MAP_QUERY)
+        .bind(params.year)
+        .bind(params.meeting)
+}
@@ -0,0 +1,119 @@
+use actix_web::{
+    HttpResponse, Responder, get,
+    web::{self, Data},
+};
+use serde::Deserialize;
+use sqlx::Execute;
+use tracing::{debug, error, info, trace};
+
+use crate::{
+    AppState,
+    models::TrackStatus,
+    services::{
+        jobs,
+        query_preparer::{SqlOperator, SqlOrder, SqlType, select::SelectQuery},
+    },
+};
+
+/* ///////////////////////// */
+/* //// HTTP Parameters //// */
+/* ///////////////////////// */
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TrackStatusParams {
+    pub session: Option<i32>,
+    pub status: Option<String>,
+}
+
+/* /////////////////////// */
+/* //// HTTP Handlers //// */
+/* /////////////////////// */
+
+#[get("/sessions/{key}/track-status")]
+pub async fn fetch_track_status(
+    state: Data<AppState>,
+    info: web::Query<TrackStatusParams>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let params = TrackStatusParams {
+        session: Some(path.into_inner()),
+        ..info.into_inner()
+    };
+
+    debug!(parameters = ?params, "Request received with");
+    let time = std::time::Instant::now();
+
+    // Prepare the query
+    let mut query_builder = prepare_query(&params);
+    let query = query_builder.build();
+    trace!("Query prepared in {:?}", time.elapsed());
+
+    debug!("SQL query - {}", query.sql());
+
+    // Execute the query
+    let statuses = match query.fetch_all(state.db.as_ref()).await {
+        Ok(statuses) => {
+            info!(
+                "Fetched {} track statuses successfully in {:?}",
+                statuses.len(),
+                time.elapsed()
+            );
+            statuses
+        }
+        Err(err) => {
+            error!(error = ?err, "Failed to execute SQL request");
+            return HttpResponse::Ok().json(serde_json::json!([]));
+        }
+    };
+
+    // If there are filters parameters, it might just be a bad filter
+    // So, it doesn't trigger a fetch job even if there are no statuses
+    if !statuses.is_empty() || params.status.is_some() {
+        return HttpResponse::Ok().json(statuses);
+    }
+
+    // No statuses found, session data might not have been ingested yet
+    match jobs::request_session_fetch(&state, params.session.unwrap_or_default()).await {
+        // Respond with "Accepted" status to indicate the request is being process
+        Ok(Some(job)) => HttpResponse::Accepted()
+            .insert_header((jobs::JOB_ID_HEADER, job))
+            .json(serde_json::json!([])),
+        Ok(None) => HttpResponse::Ok().json(statuses),
+        Err(err) => {
+            error!(error = ?err, "Failed to request session fetch");
+            HttpResponse::Ok().json(statuses)
+        }
+    }
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+fn prepare_query(params: &TrackStatusParams) -> SelectQuery<'_, '_, TrackStatus> {
+    // Start to prepare the query
+    let mut query_builder =
+        SelectQuery::<TrackStatus>::new(TrackStatus::SQL_TABLE, Vec::from(TrackStatus::SQL_FIELDS));
+
+    // Add 'filters' to the query
+    if let Some(session_key) = params.session {
+        query_builder.add_filter(
+            (TrackStatus::SQL_TABLE, "session_key"),
+            SqlOperator::Eq,
+            SqlType::Int(session_key),
+        );
+    }
+
+    if let Some(status) = &params.status {
+        query_builder.add_filter(
+            (TrackStatus::SQL_TABLE, "status"),
+            SqlOperator::Eq,
+            SqlType::Text(status.clone()),
+        );
+    }
+
+    // Add 'order' to the query
+    query_builder.add_sort((TrackStatus::SQL_TABLE, "start_time"), SqlOrder::Asc);
+
+    query_builder
+}
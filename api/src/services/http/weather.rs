@@ -0,0 +1,158 @@
+use actix_web::{
+    HttpResponse, Responder, get,
+    web::{self, Data},
+};
+use serde::Deserialize;
+use sqlx::{Execute, Postgres, postgres::PgArguments, query::QueryAs};
+use tracing::{debug, error, info, trace};
+
+use crate::{
+    AppState,
+    models::WeatherSample,
+    services::{
+        jobs,
+        query_preparer::{SqlOperator, SqlOrder, SqlType, select::SelectQuery},
+    },
+};
+
+// Samples are averaged per bucket, wind direction being averaged as an angle
+const BUCKET_QUERY: &str = "\
+    SELECT \
+        session_key, \
+        (time / $2) * $2 AS time, \
+        AVG(air_temp) AS air_temp, \
+        AVG(track_temp) AS track_temp, \
+        AVG(humidity) AS humidity, \
+        AVG(pressure) AS pressure, \
+        AVG(wind_speed) AS wind_speed, \
+        ((DEGREES(ATAN2(AVG(SIN(RADIANS(wind_direction))), AVG(COS(RADIANS(wind_direction))))) \
+            + 360)::integer % 360) AS wind_direction, \
+        BOOL_OR(rainfall) AS rainfall \
+    FROM weather_samples \
+    WHERE session_key = $1 \
+    GROUP BY session_key, (time / $2) \
+    ORDER BY time ASC";
+
+/* ///////////////////////// */
+/* //// HTTP Parameters //// */
+/* ///////////////////////// */
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WeatherParams {
+    pub session: Option<i32>,
+    pub bucket: Option<i32>,
+}
+
+impl WeatherParams {
+    // Bucket size in milliseconds, from a size given in seconds
+    fn get_bucket(&self) -> Option<i32> {
+        self.bucket
+            .filter(|b| *b > 0)
+            .and_then(|b| b.checked_mul(1000))
+    }
+}
+
+/* /////////////////////// */
+/* //// HTTP Handlers //// */
+/* /////////////////////// */
+
+#[get("/sessions/{key}/weather")]
+pub async fn fetch_weather(
+    state: Data<AppState>,
+    info: web::Query<WeatherParams>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let params = WeatherParams {
+        session: Some(path.into_inner()),
+        ..info.into_inner()
+    };
+
+    debug!(parameters = ?params, "Request received with");
+    let time = std::time::Instant::now();
+
+    // Execute the query, samples are aggregated if a bucket is given
+    let result = match params.get_bucket() {
+        Some(bucket) => {
+            let query = prepare_bucket_query(params.session.unwrap_or_default(), bucket);
+            trace!("Query prepared in {:?}", time.elapsed());
+
+            debug!("SQL query - {}", query.sql());
+            query.fetch_all(state.db.as_ref()).await
+        }
+        None => {
+            let mut query_builder = prepare_query(&params);
+            let query = query_builder.build();
+            trace!("Query prepared in {:?}", time.elapsed());
+
+            debug!("SQL query - {}", query.sql());
+            query.fetch_all(state.db.as_ref()).await
+        }
+    };
+
+    let samples = match result {
+        Ok(samples) => {
+            info!(
+                "Fetched {} weather samples successfully in {:?}",
+                samples.len(),
+                time.elapsed()
+            );
+            samples
+        }
+        Err(err) => {
+            error!(error = ?err, "Failed to execute SQL request");
+            return HttpResponse::Ok().json(serde_json::json!([]));
+        }
+    };
+
+    if !samples.is_empty() {
+        return HttpResponse::Ok().json(samples);
+    }
+
+    // No samples found, session data might not have been ingested yet
+    match jobs::request_session_fetch(&state, params.session.unwrap_or_default()).await {
+        // Respond with "Accepted" status to indicate the request is being process
+        Ok(Some(job)) => HttpResponse::Accepted()
+            .insert_header((jobs::JOB_ID_HEADER, job))
+            .json(serde_json::json!([])),
+        Ok(None) => HttpResponse::Ok().json(samples),
+        Err(err) => {
+            error!(error = ?err, "Failed to request session fetch");
+            HttpResponse::Ok().json(samples)
+        }
+    }
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+fn prepare_query(params: &WeatherParams) -> SelectQuery<'_, '_, WeatherSample> {
+    // Start to prepare the query
+    let mut query_builder = SelectQuery::<WeatherSample>::new(
+        WeatherSample::SQL_TABLE,
+        Vec::from(WeatherSample::SQL_FIELDS),
+    );
+
+    // Add 'filters' to the query
+    if let Some(session_key) = params.session {
+        query_builder.add_filter(
+            (WeatherSample::SQL_TABLE, "session_key"),
+            SqlOperator::Eq,
+            SqlType::Int(session_key),
+        );
+    }
+
+    // Add 'order' to the query
+    query_builder.add_sort((WeatherSample::SQL_TABLE, "time"), SqlOrder::Asc);
+
+    query_builder
+}
+
+fn prepare_bucket_query(
+    session_key: i32,
+    bucket: i32,
+) -> QueryAs<'static, Postgres, WeatherSample, PgArguments> {
+    sqlx::query_as::<_, WeatherSample>(BUCKET_QUERY)
+        .bind(session_key)
+        .bind(bucket)
+}
@@ -0,0 +1,436 @@
+use serde::Serialize;
+use sqlx::{Execute, Pool, Postgres};
+use tracing::{debug, error, info, instrument, trace};
+
+use crate::{
+    AppState,
+    models::Session,
+    services::query_preparer::{SqlOperator, SqlType, select::SelectQuery},
+};
+
+// Sessions are ranked by their relevance to draw a track map, the most recent first
+const TRACK_MAP_SESSION_QUERY: &str = "\
+    SELECT key, kind, name, start_date, end_date, path, meeting_key \
+    FROM sessions \
+    WHERE meeting_key = $1 \
+    AND end_date < NOW() \
+    ORDER BY \
+        CASE name WHEN 'Qualifying' THEN 0 WHEN 'Race' THEN 1 ELSE 2 END ASC, \
+        start_date DESC \
+    LIMIT 1";
+
+// A job in flight is coalesced with, until it failed or stayed queued for 'JOB_TTL' seconds
+// Jobs done are final, unless registered again on purpose ('$3')
+// The row is only updated when registered again, so nothing is returned otherwise
+const REGISTER_JOB_QUERY: &str = "\
+    INSERT INTO jobs (key) VALUES ($1) \
+    ON CONFLICT (key) DO UPDATE \
+    SET status = 'queued', error = NULL, updated_at = NOW() \
+    WHERE jobs.status = 'failed' \
+    OR (jobs.status = 'queued' AND jobs.updated_at < NOW() - make_interval(secs => $2)) \
+    OR ($3 AND jobs.status = 'done') \
+    RETURNING id, status, true AS is_new";
+
+const FIND_JOB_QUERY: &str = "SELECT id, status, false AS is_new FROM jobs WHERE key = $1";
+
+// Sessions are ingested once the job fetching all their feeds is done
+const SESSION_INGESTED_QUERY: &str = "\
+    SELECT EXISTS (SELECT 1 FROM jobs WHERE key = $1 AND status = 'done')";
+
+const JOB_STATUS_QUERY: &str = "SELECT status FROM jobs WHERE id = $1";
+
+const UPDATE_JOB_QUERY: &str = "\
+    UPDATE jobs \
+    SET status = $2, error = $3, updated_at = NOW() \
+    WHERE id = $1";
+
+// Tables holding the data ingested for a session
+const SESSION_TABLES: [&str; 10] = [
+    "laps",
+    "pit_stops",
+    "race_control_messages",
+    "track_status",
+    "weather_samples",
+    "team_radio",
+    "session_results",
+    "car_data_laps",
+    "car_positions",
+    "track_maps",
+];
+
+// Jobs lost by the worker are sent again once expired
+pub const JOB_TTL: f64 = 600.0;
+
+// Header of 'Accepted' responses, holding the id of the job fetching the data
+pub const JOB_ID_HEADER: &str = "Job-Id";
+
+// Job once registered, 'is_new' when it has to be sent to the queue
+#[derive(Debug, sqlx::FromRow)]
+pub struct Job {
+    pub id: i32,
+    pub status: String,
+    pub is_new: bool,
+}
+
+impl Job {
+    pub fn is_done(&self) -> bool {
+        self.status == "done"
+    }
+}
+
+/* ///////////////////// */
+/* //// Jobs Helper //// */
+/* ///////////////////// */
+
+pub async fn find_session(
+    db: &Pool<Postgres>,
+    session_key: i32,
+) -> Result<Option<Session>, sqlx::Error> {
+    let mut query_builder =
+        SelectQuery::<Session>::new(Session::SQL_TABLE, Vec::from(Session::SQL_FIELDS));
+    query_builder.add_filter(
+        (Session::SQL_TABLE, "key"),
+        SqlOperator::Eq,
+        SqlType::Int(session_key),
+    );
+
+    let query = query_builder.build();
+    debug!("SQL query - {}", query.sql());
+
+    query.fetch_optional(db).await
+}
+
+// Register a job identified by 'key', coalescing it with the same job in flight if any
+// Explicit requests ('force') run again a job already done instead of returning it
+pub async fn register_job(db: &Pool<Postgres>, key: &str, force: bool) -> Result<Job, sqlx::Error> {
+    let query = sqlx::query_as::<_, Job>(REGISTER_JOB_QUERY)
+        .bind(key)
+        .bind(JOB_TTL)
+        .bind(force);
+    debug!("SQL query - {}", query.sql());
+
+    if let Some(job) = query.fetch_optional(db).await? {
+        return Ok(job);
+    }
+
+    let query = sqlx::query_as::<_, Job>(FIND_JOB_QUERY).bind(key);
+    debug!("SQL query - {}", query.sql());
+
+    query.fetch_one(db).await
+}
+
+// A session without data for a feed is told apart from a session never ingested
+pub async fn session_ingested(db: &Pool<Postgres>, session_key: i32) -> Result<bool, sqlx::Error> {
+    let key = metrics_one_queue::models::Session::job_key(session_key, None);
+
+    let query = sqlx::query_scalar::<_, bool>(SESSION_INGESTED_QUERY).bind(key);
+    debug!("SQL query - {}", query.sql());
+
+    query.fetch_one(db).await
+}
+
+pub async fn job_status(db: &Pool<Postgres>, id: i32) -> Result<Option<String>, sqlx::Error> {
+    let query = sqlx::query_scalar::<_, String>(JOB_STATUS_QUERY).bind(id);
+    debug!("SQL query - {}", query.sql());
+
+    query.fetch_optional(db).await
+}
+
+// Returns 'false' when the job is unknown
+pub async fn update_job(
+    db: &Pool<Postgres>,
+    id: i32,
+    done: bool,
+    error: Option<String>,
+) -> Result<bool, sqlx::Error> {
+    let status = if done { "done" } else { "failed" };
+
+    let query = sqlx::query(UPDATE_JOB_QUERY)
+        .bind(id)
+        .bind(status)
+        .bind(error);
+    debug!("SQL query - {}", query.sql());
+
+    Ok(query.execute(db).await?.rows_affected() > 0)
+}
+
+// Send a job to the queue unless the same one is in flight, returning its id
+// Returns 'None' when the job is already done, its data being stored
+// 'payload' is built with the id of the job, for the worker to report its status
+async fn send_job<T, P>(
+    state: &AppState,
+    key: &str,
+    queue: &str,
+    force: bool,
+    payload: P,
+) -> Result<Option<i32>, Box<dyn std::error::Error>>
+where
+    T: Serialize,
+    P: FnOnce(i32) -> T,
+{
+    let job = register_job(state.db.as_ref(), key, force).await?;
+    if job.is_done() {
+        debug!("Job '{}' already done with id {}", key, job.id);
+        return Ok(None);
+    }
+    if !job.is_new {
+        debug!("Job '{}' already in flight with id {}", key, job.id);
+        return Ok(Some(job.id));
+    }
+
+    publish_job(state, job.id, queue, &payload(job.id)).await?;
+
+    Ok(Some(job.id))
+}
+
+async fn publish_job<T>(
+    state: &AppState,
+    id: i32,
+    queue: &str,
+    payload: &T,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    T: Serialize,
+{
+    // A job that never reached the queue isn't coalesced with
+    if let Err(err) = metrics_one_queue::publish(state.rabbitmq.as_ref(), queue, payload).await {
+        if let Err(err) = update_job(state.db.as_ref(), id, false, Some(err.to_string())).await {
+            error!(error = ?err, "Failed to mark job {} as failed", id);
+        }
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+// Delete every data ingested for a session, its track map included
+pub async fn purge_session(db: &Pool<Postgres>, session_key: i32) -> Result<u64, sqlx::Error> {
+    let mut transaction = db.begin().await?;
+
+    let mut nb_rows = 0;
+    for table in SESSION_TABLES {
+        let query = format!("DELETE FROM {} WHERE session_key = $1", table);
+        debug!("SQL query - {}", query);
+
+        nb_rows += sqlx::query(&query)
+            .bind(session_key)
+            .execute(&mut *transaction)
+            .await?
+            .rows_affected();
+    }
+
+    transaction.commit().await?;
+
+    Ok(nb_rows)
+}
+
+// Request the calendar of a season to be stored, returning the id of the job
+// Returns 'None' when already stored, unless requested explicitly ('force')
+#[instrument(name = "Request meetings fetch", skip(state))]
+pub async fn request_meetings_fetch(
+    state: &AppState,
+    year: i32,
+    force: bool,
+) -> Result<Option<i32>, Box<dyn std::error::Error>> {
+    let time = std::time::Instant::now();
+
+    // Meetings already stored are ignored on insertion
+    let job = send_job(
+        state,
+        &metrics_one_queue::models::Meetings::job_key(year),
+        metrics_one_queue::MEETINGS_QUEUE,
+        force,
+        |id| metrics_one_queue::models::Meetings {
+            keys: Vec::new(),
+            year,
+            job: Some(id),
+        },
+    )
+    .await?;
+
+    if let Some(job) = job {
+        info!(
+            "Meetings fetch request of {} sent as job {} in {:?}",
+            year,
+            job,
+            time.elapsed()
+        );
+    }
+
+    Ok(job)
+}
+
+// Request the ingestion of a session's feeds if the session is over and not ingested yet
+// Returns the id of the job fetching them, if any
+pub async fn request_session_fetch(
+    state: &AppState,
+    session_key: i32,
+) -> Result<Option<i32>, Box<dyn std::error::Error>> {
+    // Feeds empty for an ingested session are legitimately so, e.g. a race without pit stops
+    if session_ingested(state.db.as_ref(), session_key).await? {
+        debug!(
+            "Session {} already ingested, skipping fetch request",
+            session_key
+        );
+        return Ok(None);
+    }
+
+    request_session_feeds_fetch(state, session_key, None, false).await
+}
+
+// Only the given feeds are fetched when set, all of them otherwise
+// Sessions already ingested are fetched again only when requested explicitly ('force')
+#[instrument(name = "Request session fetch", skip(state))]
+pub async fn request_session_feeds_fetch(
+    state: &AppState,
+    session_key: i32,
+    feeds: Option<Vec<String>>,
+    force: bool,
+) -> Result<Option<i32>, Box<dyn std::error::Error>> {
+    let time = std::time::Instant::now();
+
+    let Some(session) = find_session_over(state.db.as_ref(), session_key).await? else {
+        return Ok(None);
+    };
+    trace!("Session fetched in {:?}", time.elapsed());
+
+    let key = metrics_one_queue::models::Session::job_key(session.key, feeds.as_deref());
+
+    let job = send_job(
+        state,
+        &key,
+        metrics_one_queue::SESSIONS_QUEUE,
+        force,
+        |id| metrics_one_queue::models::Session {
+            key: session.key,
+            path: session.path,
+            feeds,
+            job: Some(id),
+        },
+    )
+    .await?;
+
+    if let Some(job) = job {
+        info!(
+            "Session {} fetch request sent as job {} in {:?}",
+            session_key,
+            job,
+            time.elapsed()
+        );
+    }
+
+    Ok(job)
+}
+
+// Purge a session then request its ingestion again, if the session is over
+// Returns the id of the job fetching it, if any
+#[instrument(name = "Request session reingestion", skip(state))]
+pub async fn request_session_reingest(
+    state: &AppState,
+    session_key: i32,
+) -> Result<Option<i32>, Box<dyn std::error::Error>> {
+    let time = std::time::Instant::now();
+
+    let Some(session) = find_session_over(state.db.as_ref(), session_key).await? else {
+        return Ok(None);
+    };
+
+    // Registered as the session ingestion, so data being ingested aren't purged underneath it
+    let key = metrics_one_queue::models::Session::job_key(session.key, None);
+    let job = register_job(state.db.as_ref(), &key, true).await?;
+    if !job.is_new {
+        debug!("Job '{}' already in flight with id {}", key, job.id);
+        return Ok(Some(job.id));
+    }
+
+    let nb_rows = purge_session(state.db.as_ref(), session.key).await?;
+    debug!("{} rows of session {} purged", nb_rows, session.key);
+
+    let payload = metrics_one_queue::models::Session {
+        key: session.key,
+        path: session.path,
+        feeds: None,
+        job: Some(job.id),
+    };
+    publish_job(state, job.id, metrics_one_queue::SESSIONS_QUEUE, &payload).await?;
+
+    info!(
+        "Session {} reingestion request sent as job {} in {:?}",
+        session_key,
+        job.id,
+        time.elapsed()
+    );
+
+    Ok(Some(job.id))
+}
+
+// Unknown sessions can't be fetched as the path to its data is missing
+// And data are only complete once the session is over
+async fn find_session_over(
+    db: &Pool<Postgres>,
+    session_key: i32,
+) -> Result<Option<Session>, sqlx::Error> {
+    let Some(session) = find_session(db, session_key).await? else {
+        debug!("Session {} not found, skipping fetch request", session_key);
+        return Ok(None);
+    };
+
+    if session.end_date > chrono::Utc::now() {
+        debug!(
+            "Session {} not over yet, skipping fetch request",
+            session_key
+        );
+        return Ok(None);
+    }
+
+    Ok(Some(session))
+}
+
+// Request the drawing of a meeting's track map from one of its sessions
+// Returns the id of the job drawing it, if any and not drawn yet unless requested explicitly ('force')
+#[instrument(name = "Request track map fetch", skip(state))]
+pub async fn request_track_map_fetch(
+    state: &AppState,
+    meeting_key: i32,
+    force: bool,
+) -> Result<Option<i32>, Box<dyn std::error::Error>> {
+    let time = std::time::Instant::now();
+
+    let query = sqlx::query_as::<_, Session>(TRACK_MAP_SESSION_QUERY).bind(meeting_key);
+    debug!("SQL query - {}", query.sql());
+
+    // Position data are only available once a session is over
+    let Some(session) = query.fetch_optional(state.db.as_ref()).await? else {
+        debug!(
+            "No session over for meeting {}, skipping fetch request",
+            meeting_key
+        );
+        return Ok(None);
+    };
+    trace!("Session fetched in {:?}", time.elapsed());
+
+    let job = send_job(
+        state,
+        &format!("track_map:{}", meeting_key),
+        metrics_one_queue::TRACK_MAPS_QUEUE,
+        force,
+        |id| metrics_one_queue::models::Session {
+            key: session.key,
+            path: session.path,
+            feeds: None,
+            job: Some(id),
+        },
+    )
+    .await?;
+
+    if let Some(job) = job {
+        info!(
+            "Track map fetch request of meeting {} sent as job {} in {:?}",
+            meeting_key,
+            job,
+            time.elapsed()
+        );
+    }
+
+    Ok(job)
+}
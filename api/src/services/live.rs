@@ -0,0 +1,101 @@
+use std::{sync::Arc, time::Duration};
+
+use metrics_one_queue::models::LiveUpdate;
+use tokio::sync::broadcast;
+use tokio_stream::StreamExt;
+use tracing::{error, info, instrument, trace, warn};
+
+use crate::settings::ENV;
+
+// Number of updates kept for subscribers lagging behind, older ones being dropped
+pub const LIVE_CAPACITY: usize = 1024;
+
+// Delays between reconnections to RabbitMQ, doubled after each failure
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+
+/* /////////////////////// */
+/* //// Live Consumer //// */
+/* /////////////////////// */
+
+// Consume the live updates sent by the worker and broadcast them to the HTTP subscribers
+// The consumer is set up again when its connection is lost, for as long as the server runs
+pub async fn forward_updates(sender: broadcast::Sender<Arc<LiveUpdate>>) {
+    let mut delay = RECONNECT_DELAY;
+
+    loop {
+        match consume_updates().await {
+            Ok(consumer) => {
+                delay = RECONNECT_DELAY;
+                broadcast_updates(consumer, &sender).await;
+            }
+            Err(err) => error!(error = ?err, "Failed to set up live updates consumer"),
+        }
+
+        warn!("Live updates consumer stopped, reconnecting in {:?}", delay);
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+    }
+}
+
+// Each replica consumes every live update from its own queue, on its own connection
+#[instrument(name = "Live consumer setup", skip_all)]
+async fn consume_updates() -> Result<lapin::Consumer, lapin::Error> {
+    let addr = format!("{}:{}", ENV.rabbitmq.host, ENV.rabbitmq.port);
+    let channel = metrics_one_queue::get_rabbitmq_channel(
+        &addr,
+        &ENV.rabbitmq.user,
+        &ENV.rabbitmq.password,
+        &ENV.rabbitmq.queue,
+    )
+    .await?;
+
+    metrics_one_queue::declare_fanout_exchange(&channel, metrics_one_queue::LIVE_EXCHANGE).await?;
+    let live_queue =
+        metrics_one_queue::bind_exclusive_queue(&channel, metrics_one_queue::LIVE_EXCHANGE).await?;
+
+    channel
+        .basic_consume(
+            &live_queue,
+            "api.live",
+            lapin::options::BasicConsumeOptions {
+                no_ack: true,
+                ..Default::default()
+            },
+            lapin::types::FieldTable::default(),
+        )
+        .await
+        .inspect_err(|err| {
+            error!(error = ?err, "Failed to consume live updates");
+        })
+}
+
+async fn broadcast_updates(
+    mut consumer: lapin::Consumer,
+    sender: &broadcast::Sender<Arc<LiveUpdate>>,
+) {
+    info!("Live updates consumer started");
+
+    while let Some(delivery) = consumer.next().await {
+        let delivery = match delivery {
+            Ok(delivery) => delivery,
+            Err(err) => {
+                error!(error = ?err, "Error in live updates consumer, stopping consumption");
+                return;
+            }
+        };
+
+        let update: LiveUpdate = match serde_json::from_slice(&delivery.data) {
+            Ok(update) => update,
+            Err(err) => {
+                warn!(error = ?err, "Failed to deserialize live update, discarding message");
+                continue;
+            }
+        };
+
+        // Sending fails when no one is subscribed, which is fine
+        if sender.send(Arc::new(update)).is_err() {
+            trace!("No live subscriber, update dropped");
+        }
+    }
+}
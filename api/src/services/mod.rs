@@ -1,4 +1,9 @@
+pub mod analytics;
+pub mod auth;
 pub mod grpc;
 pub mod http;
+pub mod jobs;
+pub mod live;
+pub mod rate_limit;
 
 mod query_preparer;
@@ -6,6 +6,7 @@ pub struct InsertQuery<'q> {
     query_builder: QueryBuilder<'q, Postgres>,
     nb_fields: usize,
     values: Vec<Vec<SqlType>>,
+    ignore_conflicts: bool,
 }
 
 impl<'q> InsertQuery<'q> {
@@ -18,6 +19,7 @@ impl<'q> InsertQuery<'q> {
             )),
             nb_fields: fields.len(),
             values: Vec::new(),
+            ignore_conflicts: false,
         }
     }
 
@@ -31,18 +33,33 @@ impl<'q> InsertQuery<'q> {
         Ok(())
     }
 
+    // Skip rows violating a unique constraint instead of failing the whole query
+    pub fn ignore_conflicts(&mut self) {
+        self.ignore_conflicts = true;
+    }
+
     pub fn build(&'q mut self) -> Query<'q, Postgres, PgArguments> {
         self.query_builder
             .push_values(self.values.iter(), |mut query, values| {
                 for v in values {
                     match v {
                         SqlType::Int(v) => query.push_bind(v),
+                        SqlType::OptInt(v) => query.push_bind(v),
+                        SqlType::Float(v) => query.push_bind(v),
+                        SqlType::Bool(v) => query.push_bind(v),
                         SqlType::Text(v) => query.push_bind(v),
+                        SqlType::OptText(v) => query.push_bind(v),
                         SqlType::Timestamp(v) => query.push_bind(v),
+                        SqlType::IntArray(v) => query.push_bind(v),
+                        SqlType::FloatArray(v) => query.push_bind(v),
                     };
                 }
             });
 
+        if self.ignore_conflicts {
+            self.query_builder.push(" ON CONFLICT DO NOTHING");
+        }
+
         self.query_builder.build()
     }
 }
@@ -6,8 +6,14 @@ use chrono::{DateTime, Utc};
 #[derive(Clone)]
 pub enum SqlType {
     Int(i32),
+    OptInt(Option<i32>),
+    Float(f64),
+    Bool(bool),
     Text(String),
+    OptText(Option<String>),
     Timestamp(DateTime<Utc>),
+    IntArray(Vec<i32>),
+    FloatArray(Vec<f64>),
 }
 
 #[allow(dead_code)]
@@ -18,3 +24,10 @@ pub enum SqlOperator {
     Inf,
     ILike,
 }
+
+#[allow(dead_code)]
+#[derive(Clone)]
+pub enum SqlOrder {
+    Asc,
+    Desc,
+}
@@ -6,7 +6,7 @@ use sqlx::{
     query::QueryAs,
 };
 
-use super::{SqlOperator, SqlType};
+use super::{SqlOperator, SqlOrder, SqlType};
 
 #[derive(Clone)]
 pub struct SqlKey {
@@ -49,6 +49,12 @@ pub struct SqlFilter {
     value: SqlType,
 }
 
+#[derive(Clone)]
+pub struct SqlSort {
+    key: SqlKey,
+    order: SqlOrder,
+}
+
 #[allow(dead_code)]
 #[derive(Clone)]
 pub enum JoinType {
@@ -102,6 +108,8 @@ pub struct SelectQuery<'q, 's, T> {
     join: Vec<SqlJoin<'s>>,
     filter: Vec<SqlFilter>,
     group_by: Vec<SqlKey>,
+    sort: Vec<SqlSort>,
+    limit: Option<i64>,
     has_agg: bool,
     _marker: PhantomData<T>,
 }
@@ -124,6 +132,8 @@ where
             join: Vec::new(),
             filter: Vec::new(),
             group_by: Vec::new(),
+            sort: Vec::new(),
+            limit: None,
             has_agg: false,
             _marker: PhantomData,
         }
@@ -156,6 +166,17 @@ where
         });
     }
 
+    pub fn add_sort(&mut self, key: (&str, &str), order: SqlOrder) {
+        self.sort.push(SqlSort {
+            key: SqlKey::new(key),
+            order,
+        });
+    }
+
+    pub fn set_limit(&mut self, limit: i64) {
+        self.limit = Some(limit);
+    }
+
     pub fn build(&'q mut self) -> QueryAs<'q, Postgres, T, PgArguments> {
         // Add join fields in 'SELECT' statement
         for j in self.join.iter() {
@@ -169,7 +190,16 @@ where
 
             match j.row.row_type {
                 RowType::Single => {
-                    let mut str = format!("jsonb_build_object({})", fields);
+                    // Without any matching row, the object is 'NULL' rather than filled with 'NULL' values
+                    let joined_key = if j.key_2.table == j.row.table {
+                        &j.key_2
+                    } else {
+                        &j.key_1
+                    };
+                    let mut str = format!(
+                        "CASE WHEN {} IS NULL THEN NULL ELSE jsonb_build_object({}) END",
+                        joined_key, fields
+                    );
 
                     // If there is an aggregate row, we have to aggregate the single row
                     // Here we convert the row in an array and extract the first element
@@ -228,8 +258,14 @@ where
                 // Add value to compare to
                 match &f.value {
                     SqlType::Int(v) => self.query_builder.push_bind(v.clone()),
+                    SqlType::OptInt(v) => self.query_builder.push_bind(*v),
+                    SqlType::Float(v) => self.query_builder.push_bind(*v),
+                    SqlType::Bool(v) => self.query_builder.push_bind(*v),
                     SqlType::Text(v) => self.query_builder.push_bind(v.clone()),
+                    SqlType::OptText(v) => self.query_builder.push_bind(v.clone()),
                     SqlType::Timestamp(v) => self.query_builder.push_bind(v.clone()),
+                    SqlType::IntArray(v) => self.query_builder.push_bind(v.clone()),
+                    SqlType::FloatArray(v) => self.query_builder.push_bind(v.clone()),
                 };
 
                 // If not last element, add 'and' statement
@@ -245,6 +281,31 @@ where
                 .push(&format!(" GROUP BY {}", self.group_by.join(",")));
         }
 
+        // Add 'ORDER BY' statements
+        if !self.sort.is_empty() {
+            self.query_builder.push(" ORDER BY ");
+
+            let mut it = self.sort.iter().peekable();
+            while let Some(s) = it.next() {
+                self.query_builder.push(format!("{}", s.key));
+
+                match &s.order {
+                    SqlOrder::Asc => self.query_builder.push(" ASC"),
+                    SqlOrder::Desc => self.query_builder.push(" DESC"),
+                };
+
+                if it.peek().is_some() {
+                    self.query_builder.push(",");
+                }
+            }
+        }
+
+        // Add 'LIMIT' statement
+        if let Some(limit) = self.limit {
+            self.query_builder.push(" LIMIT ");
+            self.query_builder.push_bind(limit);
+        }
+
         self.query_builder.build_query_as::<T>()
     }
 }
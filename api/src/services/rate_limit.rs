@@ -0,0 +1,313 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use actix_web::{
+    HttpResponse,
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{self, HeaderName, HeaderValue},
+    middleware::Next,
+    web::Data,
+};
+use sqlx::{Execute, Pool, Postgres};
+use tracing::{debug, error, warn};
+
+use crate::{
+    AppState,
+    services::auth::{self, API_KEYS},
+    settings::{ENV, RateLimitBackend},
+};
+
+// Counters of the current window, the previous ones being purged periodically
+const HIT_QUERY: &str = "\
+    INSERT INTO rate_limits (key, window_start, count) \
+    VALUES ($1, $2, 1) \
+    ON CONFLICT (key) DO UPDATE \
+    SET count = CASE \
+        WHEN rate_limits.window_start = EXCLUDED.window_start THEN rate_limits.count + 1 \
+        ELSE 1 \
+    END, \
+    window_start = EXCLUDED.window_start \
+    RETURNING count";
+
+const PURGE_QUERY: &str = "DELETE FROM rate_limits WHERE window_start < $1";
+
+// Routes reading telemetry or replaying sessions, much more expensive than entity lists
+const TELEMETRY_ROUTES: [&str; 4] = [
+    "/sessions/{key}/compare",
+    "/sessions/{key}/lap-chart",
+    "/sessions/{key}/gaps",
+    "/sessions/{key}/replay",
+];
+
+pub type StoreResult<'a, T> =
+    Pin<Box<dyn Future<Output = Result<T, Box<dyn std::error::Error>>> + Send + 'a>>;
+
+// Counters of requests per client within fixed windows
+// Windows are aligned on the epoch, so every replica agrees on their boundaries
+pub trait RateLimitStore: Send + Sync {
+    // Count a request in the window starting at 'window_start' and return the count of the window
+    fn hit<'a>(&'a self, key: &'a str, window_start: u64) -> StoreResult<'a, u32>;
+
+    // Remove the counters of the windows started before 'before'
+    fn purge(&self, before: u64) -> StoreResult<'_, ()>;
+}
+
+pub fn from_settings(db: Arc<Pool<Postgres>>) -> Arc<dyn RateLimitStore> {
+    match ENV.rate_limit.backend {
+        RateLimitBackend::Memory => Arc::new(MemoryStore::default()),
+        RateLimitBackend::Postgres => Arc::new(PostgresStore { db }),
+    }
+}
+
+/* ////////////////////// */
+/* //// Memory Store //// */
+/* ////////////////////// */
+
+// Counters only known by this replica
+#[derive(Default)]
+pub struct MemoryStore {
+    counters: Mutex<HashMap<String, (u64, u32)>>,
+}
+
+impl RateLimitStore for MemoryStore {
+    fn hit<'a>(&'a self, key: &'a str, window_start: u64) -> StoreResult<'a, u32> {
+        // A poisoned lock only means a writer panicked, the counters are still valid
+        let mut counters = self.counters.lock().unwrap_or_else(|err| err.into_inner());
+
+        let counter = counters.entry(key.to_string()).or_insert((window_start, 0));
+        if counter.0 != window_start {
+            *counter = (window_start, 0);
+        }
+        counter.1 += 1;
+
+        let count = counter.1;
+        Box::pin(async move { Ok(count) })
+    }
+
+    fn purge(&self, before: u64) -> StoreResult<'_, ()> {
+        self.counters
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .retain(|_, (window_start, _)| *window_start >= before);
+
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/* //////////////////////// */
+/* //// Postgres Store //// */
+/* //////////////////////// */
+
+// Counters shared by every replica using the same database
+pub struct PostgresStore {
+    db: Arc<Pool<Postgres>>,
+}
+
+impl RateLimitStore for PostgresStore {
+    fn hit<'a>(&'a self, key: &'a str, window_start: u64) -> StoreResult<'a, u32> {
+        Box::pin(async move {
+            let query = sqlx::query_scalar::<_, i32>(HIT_QUERY)
+                .bind(key)
+                .bind(window_start as i64);
+            debug!("SQL query - {}", query.sql());
+
+            Ok(query.fetch_one(self.db.as_ref()).await? as u32)
+        })
+    }
+
+    fn purge(&self, before: u64) -> StoreResult<'_, ()> {
+        Box::pin(async move {
+            let query = sqlx::query(PURGE_QUERY).bind(before as i64);
+            debug!("SQL query - {}", query.sql());
+
+            query.execute(self.db.as_ref()).await?;
+            Ok(())
+        })
+    }
+}
+
+// Purge the counters of the past windows for as long as the server runs
+pub async fn purge_counters(store: Arc<dyn RateLimitStore>) {
+    let window = ENV.rate_limit.window.max(1);
+    loop {
+        tokio::time::sleep(Duration::from_secs(window)).await;
+
+        let (window_start, _) = current_window(window);
+        if let Err(err) = store.purge(window_start).await {
+            error!(error = ?err, "Failed to purge rate limit counters");
+        }
+    }
+}
+
+/* ///////////////////////// */
+/* //// HTTP Middleware //// */
+/* ///////////////////////// */
+
+// Limit the requests of each client per route group, clients being identified by API key or IP
+// Requests are let through when the store fails, to not turn its outage into the API's
+pub async fn limit_rate(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, actix_web::Error> {
+    let (group, quota) = route_group(&req);
+
+    let Some(store) = req
+        .app_data::<Data<AppState>>()
+        .map(|state| state.rate_limits.clone())
+        .filter(|_| quota > 0)
+    else {
+        return Ok(next.call(req).await?.map_into_left_body());
+    };
+
+    let (window_start, reset) = current_window(ENV.rate_limit.window);
+    let key = format!("{}:{}", group, client_key(&req));
+
+    let count = match store.hit(&key, window_start).await {
+        Ok(count) => count,
+        Err(err) => {
+            error!(error = ?err, "Failed to count request for rate limiting");
+            return Ok(next.call(req).await?.map_into_left_body());
+        }
+    };
+
+    let headers = rate_limit_headers(quota, count, reset);
+
+    if count > quota {
+        warn!(client = key, "Rate limit exceeded");
+
+        return Ok(req
+            .into_response(too_many_requests(headers, reset))
+            .map_into_right_body());
+    }
+
+    let mut res = next.call(req).await?;
+    for (name, value) in headers {
+        res.headers_mut()
+            .insert(HeaderName::from_static(name), HeaderValue::from(value));
+    }
+
+    Ok(res.map_into_left_body())
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+// Start of the current window and seconds until the next one
+fn current_window(window: u64) -> (u64, u64) {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    window_at(now, window)
+}
+
+fn window_at(now: u64, window: u64) -> (u64, u64) {
+    let window = window.max(1);
+    let window_start = now - now % window;
+
+    (window_start, window_start + window - now)
+}
+
+fn rate_limit_headers(quota: u32, count: u32, reset: u64) -> [(&'static str, u64); 3] {
+    [
+        ("ratelimit-limit", quota as u64),
+        ("ratelimit-remaining", quota.saturating_sub(count) as u64),
+        ("ratelimit-reset", reset),
+    ]
+}
+
+// Clients are told to retry once the window is over
+fn too_many_requests(headers: [(&'static str, u64); 3], reset: u64) -> HttpResponse {
+    let mut response = HttpResponse::TooManyRequests();
+    response.insert_header((header::RETRY_AFTER, reset));
+    for (name, value) in headers {
+        response.insert_header((name, value));
+    }
+
+    response.body("Too many requests")
+}
+
+// Group of the route and its quota per window, '0' disabling the limit
+fn route_group(req: &ServiceRequest) -> (&'static str, u32) {
+    let is_telemetry = req
+        .match_pattern()
+        .is_some_and(|pattern| TELEMETRY_ROUTES.contains(&pattern.as_str()));
+
+    if is_telemetry {
+        ("telemetry", ENV.rate_limit.telemetry_quota)
+    } else {
+        ("default", ENV.rate_limit.default_quota)
+    }
+}
+
+// Forwarded addresses can be forged, so they are only used behind a trusted proxy
+fn client_key(req: &ServiceRequest) -> String {
+    if let Some(key) = auth::bearer_token(req).and_then(|key| API_KEYS.find(key)) {
+        return format!("key:{}", key.name);
+    }
+
+    let ip = if ENV.rate_limit.trust_proxy {
+        req.connection_info()
+            .realip_remote_addr()
+            .map(str::to_string)
+    } else {
+        req.peer_addr().map(|addr| addr.ip().to_string())
+    };
+
+    format!("ip:{}", ip.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::{StatusCode, header};
+
+    use super::{MemoryStore, RateLimitStore, rate_limit_headers, too_many_requests, window_at};
+
+    #[test]
+    fn windows_are_aligned_on_epoch() {
+        assert_eq!(window_at(60, 60), (60, 60));
+        assert_eq!(window_at(119, 60), (60, 1));
+        assert_eq!(window_at(120, 60), (120, 60));
+
+        // A window of '0' would never end, it's taken as 1 second
+        assert_eq!(window_at(42, 0), (42, 1));
+    }
+
+    #[tokio::test]
+    async fn memory_store_counts_per_window() {
+        let store = MemoryStore::default();
+
+        assert_eq!(store.hit("default:ip:1", 60).await.unwrap(), 1);
+        assert_eq!(store.hit("default:ip:1", 60).await.unwrap(), 2);
+        assert_eq!(store.hit("default:ip:2", 60).await.unwrap(), 1);
+
+        // The count starts over with the next window
+        assert_eq!(store.hit("default:ip:1", 120).await.unwrap(), 1);
+
+        store.purge(120).await.unwrap();
+        assert_eq!(store.hit("default:ip:2", 120).await.unwrap(), 1);
+        assert_eq!(store.hit("default:ip:1", 120).await.unwrap(), 2);
+    }
+
+    #[test]
+    fn exceeded_quota_answers_retry_after() {
+        let headers = rate_limit_headers(10, 11, 42);
+        assert_eq!(headers[1], ("ratelimit-remaining", 0));
+
+        let response = too_many_requests(headers, 42);
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let header = |name| response.headers().get(name).and_then(|v| v.to_str().ok());
+        assert_eq!(header(header::RETRY_AFTER.as_str()), Some("42"));
+        assert_eq!(header("ratelimit-limit"), Some("10"));
+        assert_eq!(header("ratelimit-reset"), Some("42"));
+    }
+}
@@ -8,10 +8,20 @@ pub struct HostSettings {
     pub port: u16,
 }
 
+// TLS of the gRPC server, clients needing a certificate signed by 'ca' when set (mTLS)
+// Files are reloaded when modified, so certificates can be renewed without a restart
+#[derive(Debug, Deserialize)]
+pub struct TlsSettings {
+    pub cert: String,
+    pub key: String,
+    pub ca: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ServerSettings {
     pub http: HostSettings,
     pub grpc: HostSettings,
+    pub grpc_tls: Option<TlsSettings>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -33,11 +43,48 @@ pub struct RabbitMqSettings {
     pub queue: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitBackend {
+    Memory,
+    Postgres,
+}
+
+// Requests allowed per client and per 'window' seconds for each route group, '0' disabling it
+// Counters are only shared between replicas with the 'postgres' backend
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct RateLimitSettings {
+    pub backend: RateLimitBackend,
+    pub window: u64,
+    pub default_quota: u32,
+    pub telemetry_quota: u32,
+    pub trust_proxy: bool,
+}
+
+impl Default for RateLimitSettings {
+    fn default() -> Self {
+        RateLimitSettings {
+            backend: RateLimitBackend::Memory,
+            window: 60,
+            default_quota: 120,
+            telemetry_quota: 20,
+            trust_proxy: false,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Settings {
     pub server: ServerSettings,
     pub db: DbSettings,
     pub rabbitmq: RabbitMqSettings,
+    pub livetiming_url: String,
+    // API keys with the 'read' scope are required by every route when set
+    #[serde(default)]
+    pub require_read_key: bool,
+    #[serde(default)]
+    pub rate_limit: RateLimitSettings,
     pub rust_log: String,
 }
 
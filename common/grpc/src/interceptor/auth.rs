@@ -0,0 +1,52 @@
+use tonic::{
+    Request, Status,
+    metadata::{Ascii, MetadataMap, MetadataValue, errors::InvalidMetadataValue},
+    service::Interceptor,
+};
+
+use crate::interceptor::tracing::tracing_injector;
+
+/* ///////////////////////////////// */
+/* //// gRPC Client Interceptor //// */
+/* ///////////////////////////////// */
+
+// Inject the trace context and, when set, the API key of the client in the request metadata
+#[derive(Clone)]
+pub struct ClientInterceptor {
+    authorization: Option<MetadataValue<Ascii>>,
+}
+
+impl ClientInterceptor {
+    pub fn new(api_key: Option<&str>) -> Result<Self, InvalidMetadataValue> {
+        let authorization = api_key
+            .map(|key| MetadataValue::try_from(format!("Bearer {}", key)))
+            .transpose()?;
+
+        Ok(ClientInterceptor { authorization })
+    }
+}
+
+impl Interceptor for ClientInterceptor {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let mut request = tracing_injector(request)?;
+
+        if let Some(authorization) = &self.authorization {
+            request
+                .metadata_mut()
+                .insert("authorization", authorization.clone());
+        }
+
+        Ok(request)
+    }
+}
+
+/* ///////////////////// */
+/* //// gRPC Helper //// */
+/* ///////////////////// */
+
+pub fn bearer_token(metadata: &MetadataMap) -> Option<&str> {
+    metadata
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+}
@@ -0,0 +1,6 @@
+pub mod merge;
+pub mod models;
+pub mod state;
+
+pub use merge::merge;
+pub use state::SessionState;
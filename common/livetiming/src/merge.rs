@@ -0,0 +1,55 @@
+use serde_json::Value;
+
+// Key listing the entries removed from an object by an update
+const DELETED_KEY: &str = "_deleted";
+
+// Lists of the feeds hold at most a few hundred entries (e.g. race control messages)
+// Indexes past this one are skipped, so a bad update can't allocate an oversized list
+pub const MAX_LIST_INDEX: usize = 10_000;
+
+// Merge a Livetiming update into the state of its feed
+// Updates only hold the fields that changed, lists being updated as objects keyed by index
+// A list sent as a whole replaces the previous one, as do values of a different kind
+pub fn merge(target: &mut Value, update: Value) {
+    match (target, update) {
+        (Value::Object(target), Value::Object(update)) => {
+            for (key, value) in update {
+                if key == DELETED_KEY {
+                    delete(target, &value);
+                    continue;
+                }
+
+                merge(target.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (Value::Array(target), Value::Object(update)) => {
+            for (key, value) in update {
+                let Ok(index) = key.parse::<usize>() else {
+                    continue;
+                };
+                if index > MAX_LIST_INDEX {
+                    continue;
+                }
+
+                if index >= target.len() {
+                    target.resize(index + 1, Value::Null);
+                }
+                merge(&mut target[index], value);
+            }
+        }
+        (target, update) => *target = update,
+    }
+}
+
+fn delete(target: &mut serde_json::Map<String, Value>, keys: &Value) {
+    let Value::Array(keys) = keys else {
+        return;
+    };
+
+    for key in keys {
+        match key {
+            Value::String(key) => target.remove(key),
+            key => target.remove(&key.to_string()),
+        };
+    }
+}
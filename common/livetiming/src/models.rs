@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/* ////////////////////// */
+/* //// Timing Tower //// */
+/* ////////////////////// */
+
+// State of 'TimingData', lines are keyed by racing number
+// Fields depend on the kind of session, so all of them are optional
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct TimingTower {
+    #[serde(default)]
+    pub lines: BTreeMap<String, TowerLine>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct TowerLine {
+    pub position: Option<String>,
+    pub line: Option<i32>,
+    pub gap_to_leader: Option<String>,
+    pub interval_to_position_ahead: Option<TimingValue>,
+    pub number_of_laps: Option<i32>,
+    pub number_of_pit_stops: Option<i32>,
+    pub last_lap_time: Option<TimingValue>,
+    pub best_lap_time: Option<TimingValue>,
+    pub in_pit: Option<bool>,
+    pub pit_out: Option<bool>,
+    pub retired: Option<bool>,
+    pub stopped: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct TimingValue {
+    pub value: Option<String>,
+}
+
+/* ///////////////////////// */
+/* //// Timing App Data //// */
+/* ///////////////////////// */
+
+// State of 'TimingAppData', holding the tyre stints of each driver
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct TimingAppData {
+    #[serde(default)]
+    pub lines: BTreeMap<String, AppLine>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct AppLine {
+    pub racing_number: Option<String>,
+    pub line: Option<i32>,
+    #[serde(default)]
+    pub stints: Vec<Stint>,
+}
+
+// 'New' is sent as a string, 'true' or 'false'
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Stint {
+    pub compound: Option<String>,
+    pub new: Option<String>,
+    pub total_laps: Option<i32>,
+    pub start_laps: Option<i32>,
+}
+
+/* ///////////////////// */
+/* //// Driver List //// */
+/* ///////////////////// */
+
+// State of 'DriverList', drivers being keyed by racing number at the root of the feed
+// Other keys of the root (e.g. '_kf') are ignored
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(from = "BTreeMap<String, Value>")]
+pub struct DriverList {
+    pub drivers: BTreeMap<String, DriverEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct DriverEntry {
+    pub racing_number: Option<String>,
+    pub broadcast_name: Option<String>,
+    pub full_name: Option<String>,
+    pub tla: Option<String>,
+    pub line: Option<i32>,
+    pub team_name: Option<String>,
+    pub team_colour: Option<String>,
+}
+
+impl From<BTreeMap<String, Value>> for DriverList {
+    fn from(entries: BTreeMap<String, Value>) -> Self {
+        let drivers = entries
+            .into_iter()
+            .filter(|(key, _)| key.parse::<i32>().is_ok())
+            .filter_map(|(key, value)| Some((key, serde_json::from_value(value).ok()?)))
+            .collect();
+
+        DriverList { drivers }
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{
+    merge,
+    models::{DriverList, TimingAppData, TimingTower},
+};
+
+/* /////////////////////// */
+/* //// Session State //// */
+/* /////////////////////// */
+
+// State of every feed of a session, built by merging their updates in order
+// Times are offsets in milliseconds from the start of the session stream
+#[derive(Default)]
+pub struct SessionState {
+    feeds: HashMap<String, Value>,
+    updates: Vec<(i32, String, Value)>,
+    // Feeds whose updates are kept to rebuild past states, all of them when not set
+    history: Option<HashSet<String>>,
+}
+
+impl SessionState {
+    // Only the updates of the given feeds are kept, to bound the memory used by large feeds
+    pub fn with_history_of(feeds: &[&str]) -> Self {
+        SessionState {
+            history: Some(feeds.iter().map(|f| f.to_string()).collect()),
+            ..Default::default()
+        }
+    }
+
+    pub fn apply(&mut self, feed: &str, time: i32, update: Value) {
+        if self.history.as_ref().is_none_or(|h| h.contains(feed)) {
+            self.updates.push((time, feed.to_string(), update.clone()));
+        }
+
+        merge(
+            self.feeds.entry(feed.to_string()).or_insert(Value::Null),
+            update,
+        );
+    }
+
+    // State as it was at 'time', replaying the updates received until then
+    // Only the feeds with history are part of it
+    pub fn at(&self, time: i32) -> SessionState {
+        let mut state = SessionState {
+            history: self.history.clone(),
+            ..Default::default()
+        };
+
+        for (t, feed, update) in self.updates.iter().filter(|(t, _, _)| *t <= time) {
+            state.apply(feed, *t, update.clone());
+        }
+
+        state
+    }
+
+    pub fn feed(&self, feed: &str) -> Option<&Value> {
+        self.feeds.get(feed)
+    }
+
+    // Deserialize the state of a feed, 'None' if it was never received or doesn't match 'T'
+    pub fn snapshot<T>(&self, feed: &str) -> Option<T>
+    where
+        T: DeserializeOwned,
+    {
+        serde_json::from_value(self.feed(feed)?.clone()).ok()
+    }
+
+    pub fn timing_tower(&self) -> Option<TimingTower> {
+        self.snapshot("TimingData")
+    }
+
+    pub fn timing_app_data(&self) -> Option<TimingAppData> {
+        self.snapshot("TimingAppData")
+    }
+
+    pub fn driver_list(&self) -> Option<DriverList> {
+        self.snapshot("DriverList")
+    }
+}
@@ -0,0 +1,240 @@
+use std::path::PathBuf;
+
+use metrics_one_livetiming::{SessionState, merge::MAX_LIST_INDEX};
+use proptest::prelude::*;
+use serde_json::{Map, Value, json};
+
+// Feeds evolve without changing the kind of their fields, so keys are bound to a kind:
+// 'a' and 'b' hold scalars, 'c' and 'd' objects, 'e' and 'f' lists
+fn scalar() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::from),
+        any::<i32>().prop_map(Value::from),
+        "[a-z]{0,3}".prop_map(Value::from),
+    ]
+}
+
+fn object(depth: u32) -> BoxedStrategy<Value> {
+    let scalars = prop::collection::btree_map(prop_oneof![Just("a"), Just("b")], scalar(), 0..=2);
+    if depth == 0 {
+        return scalars.prop_map(|s| to_object([s])).boxed();
+    }
+
+    let objects =
+        prop::collection::btree_map(prop_oneof![Just("c"), Just("d")], object(depth - 1), 0..=2);
+    let lists = prop::collection::btree_map(
+        prop_oneof![Just("e"), Just("f")],
+        prop::collection::vec(prop_oneof![scalar(), object(depth - 1)], 0..4)
+            .prop_map(Value::Array),
+        0..=2,
+    );
+
+    (scalars, objects, lists)
+        .prop_map(|(s, o, l)| to_object([s, o, l]))
+        .boxed()
+}
+
+fn to_object<const N: usize>(maps: [std::collections::BTreeMap<&str, Value>; N]) -> Value {
+    Value::Object(
+        maps.into_iter()
+            .flatten()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect(),
+    )
+}
+
+// Update as Livetiming would send it to go from 'old' to 'new'
+// Only changed fields are sent, lists by index, removed keys being listed under '_deleted'
+fn diff(old: &Value, new: &Value) -> Option<Value> {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let mut update = Map::new();
+            for (key, value) in new {
+                match old.get(key) {
+                    Some(previous) => {
+                        if let Some(d) = diff(previous, value) {
+                            update.insert(key.clone(), d);
+                        }
+                    }
+                    None => {
+                        update.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+
+            let deleted: Vec<Value> = old
+                .keys()
+                .filter(|k| !new.contains_key(*k))
+                .map(|k| Value::from(k.as_str()))
+                .collect();
+            if !deleted.is_empty() {
+                update.insert("_deleted".to_string(), Value::Array(deleted));
+            }
+
+            (!update.is_empty()).then_some(Value::Object(update))
+        }
+        // Shrinking lists can't be sent by index, they are sent as a whole
+        (Value::Array(old), Value::Array(new)) if new.len() >= old.len() => {
+            let update: Map<String, Value> = new
+                .iter()
+                .enumerate()
+                .filter_map(|(i, value)| match old.get(i) {
+                    Some(previous) => Some((i.to_string(), diff(previous, value)?)),
+                    None => Some((i.to_string(), value.clone())),
+                })
+                .collect();
+
+            (!update.is_empty()).then_some(Value::Object(update))
+        }
+        (old, new) => (old != new).then(|| new.clone()),
+    }
+}
+
+// The first line of a stream is the whole state, the next ones the updates, one per second
+fn stream(states: &[Value]) -> Vec<(i32, Value)> {
+    let mut lines = vec![(0, states[0].clone())];
+    for (i, pair) in states.windows(2).enumerate() {
+        if let Some(update) = diff(&pair[0], &pair[1]) {
+            lines.push(((i as i32 + 1) * 1000, update));
+        }
+    }
+
+    lines
+}
+
+proptest! {
+    #[test]
+    fn replaying_stream_gives_final_state(states in prop::collection::vec(object(3), 1..6)) {
+        let mut state = SessionState::default();
+        for (time, update) in stream(&states) {
+            state.apply("TimingData", time, update);
+        }
+
+        prop_assert_eq!(state.feed("TimingData"), states.last());
+    }
+
+    #[test]
+    fn state_at_time_gives_intermediate_state(states in prop::collection::vec(object(3), 1..6)) {
+        let mut state = SessionState::default();
+        for (time, update) in stream(&states) {
+            state.apply("TimingData", time, update);
+        }
+
+        for (i, expected) in states.iter().enumerate() {
+            let past = state.at(i as i32 * 1000);
+            prop_assert_eq!(past.feed("TimingData"), Some(expected));
+        }
+    }
+}
+
+#[test]
+fn typed_snapshots_read_merged_state() {
+    let mut state = SessionState::default();
+    state.apply(
+        "TimingData",
+        0,
+        json!({ "Lines": { "1": { "Position": "2", "NumberOfLaps": 1 } } }),
+    );
+    state.apply(
+        "TimingData",
+        1000,
+        json!({ "Lines": { "1": { "Position": "1", "LastLapTime": { "Value": "1:32.456" } } } }),
+    );
+    state.apply(
+        "TimingAppData",
+        0,
+        json!({ "Lines": { "1": { "RacingNumber": "1", "Stints": [{ "Compound": "SOFT" }] } } }),
+    );
+    state.apply(
+        "TimingAppData",
+        1000,
+        json!({ "Lines": { "1": { "Stints": { "1": { "Compound": "HARD", "New": "true" } } } } }),
+    );
+    state.apply(
+        "DriverList",
+        0,
+        json!({ "1": { "RacingNumber": "1", "Tla": "VER" }, "_kf": true }),
+    );
+
+    let tower = state.timing_tower().unwrap();
+    assert_eq!(tower.lines["1"].position.as_deref(), Some("1"));
+    assert_eq!(tower.lines["1"].number_of_laps, Some(1));
+
+    let app_data = state.timing_app_data().unwrap();
+    let compounds: Vec<Option<&str>> = app_data.lines["1"]
+        .stints
+        .iter()
+        .map(|s| s.compound.as_deref())
+        .collect();
+    assert_eq!(compounds, [Some("SOFT"), Some("HARD")]);
+
+    let drivers = state.driver_list().unwrap();
+    assert_eq!(drivers.drivers.len(), 1);
+    assert_eq!(drivers.drivers["1"].tla.as_deref(), Some("VER"));
+
+    let past = state.at(0).timing_tower().unwrap();
+    assert_eq!(past.lines["1"].position.as_deref(), Some("2"));
+}
+
+// Livetiming files start with a BOM, stream lines being formatted as 'hh:mm:ss.mmm{...}'
+fn read_fixture(name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+    let text = std::fs::read_to_string(path).unwrap();
+    text.trim_start_matches('\u{feff}').to_string()
+}
+
+fn parse_stream(text: &str) -> Vec<(i32, Value)> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (time, data) = line.split_at(line.find('{').unwrap());
+            let parts: Vec<f64> = time.split(':').map(|p| p.parse().unwrap()).collect();
+            let time = ((parts[0] * 3600.0 + parts[1] * 60.0 + parts[2]) * 1000.0).round();
+            (time as i32, serde_json::from_str(data.trim()).unwrap())
+        })
+        .collect()
+}
+
+#[test]
+fn replaying_recorded_stream_gives_keyframe() {
+    for feed in ["TimingData", "TimingAppData"] {
+        let mut state = SessionState::default();
+        for (time, update) in parse_stream(&read_fixture(&format!("{}.jsonStream", feed))) {
+            state.apply(feed, time, update);
+        }
+
+        let keyframe: Value =
+            serde_json::from_str(&read_fixture(&format!("{}.json", feed))).unwrap();
+        assert_eq!(state.feed(feed), Some(&keyframe), "{}", feed);
+    }
+}
+
+#[test]
+fn oversized_list_index_is_skipped() {
+    let mut state = SessionState::default();
+    state.apply(
+        "TimingAppData",
+        0,
+        json!({ "Stints": [{ "Compound": "SOFT" }] }),
+    );
+    state.apply(
+        "TimingAppData",
+        1000,
+        json!({ "Stints": { "4000000000": { "Compound": "HARD" } } }),
+    );
+    state.apply(
+        "TimingAppData",
+        2000,
+        json!({ "Stints": { MAX_LIST_INDEX.to_string(): { "Compound": "MEDIUM" } } }),
+    );
+
+    let stints = state.feed("TimingAppData").unwrap()["Stints"]
+        .as_array()
+        .unwrap();
+    assert_eq!(stints.len(), MAX_LIST_INDEX + 1);
+    assert_eq!(stints[0]["Compound"], "SOFT");
+    assert_eq!(stints[MAX_LIST_INDEX]["Compound"], "MEDIUM");
+}
@@ -1,7 +1,26 @@
-use tracing::{debug, error, info, instrument};
+use lapin::{
+    BasicProperties,
+    types::{AMQPValue, FieldTable},
+};
+use opentelemetry::{global, propagation::Injector};
+use serde::Serialize;
+use tracing::{Span, debug, error, info, instrument, trace};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 pub mod models;
 
+pub const MEETINGS_QUEUE: &str = "fetch.meetings";
+pub const SESSIONS_QUEUE: &str = "fetch.sessions";
+pub const TRACK_MAPS_QUEUE: &str = "fetch.track_maps";
+
+// Live updates are broadcast to every API replica, each one having its own queue
+pub const LIVE_EXCHANGE: &str = "live.updates";
+
+// Messages rejected on the fetch queues are routed to their dead letter queue by this exchange
+// Queues are pointed to it by the 'fetch-dead-letters' policy of the broker, set by docker compose:
+// rabbitmqctl set_policy --apply-to queues fetch-dead-letters '^fetch\.[^.]+$' '{"dead-letter-exchange":"fetch.dead"}'
+pub const DEAD_LETTER_EXCHANGE: &str = "fetch.dead";
+
 // TODO: Refactor into a class and split into different functions
 #[instrument(name = "RabbitMQ connection", skip_all)]
 pub async fn get_rabbitmq_channel(
@@ -27,21 +46,309 @@ pub async fn get_rabbitmq_channel(
         error!(error = ?err, "Failed to create RabbitMQ channel");
     })?;
 
+    declare_queue(&channel, queue).await?;
+
+    Ok(channel)
+}
+
+// Messages of a queue rejected by its consumer are moved to this queue, to be inspected or requeued
+pub fn dead_letter_queue(queue: &str) -> String {
+    format!("{}.dead", queue)
+}
+
+// Queues are declared along their dead letter queue, bound to the dead letter exchange
+// Dead lettering is set by policy, as the arguments of queues already declared can't change
+pub async fn declare_queue(channel: &lapin::Channel, queue: &str) -> Result<(), lapin::Error> {
+    let dead_letter_queue = dead_letter_queue(queue);
+
+    channel
+        .exchange_declare(
+            DEAD_LETTER_EXCHANGE,
+            lapin::ExchangeKind::Direct,
+            lapin::options::ExchangeDeclareOptions {
+                durable: true,
+                ..Default::default()
+            },
+            FieldTable::default(),
+        )
+        .await
+        .inspect_err(|err| {
+            error!(error = ?err, "Failed to declare RabbitMQ exchange");
+        })?;
+
+    for name in [dead_letter_queue.as_str(), queue] {
+        channel
+            .queue_declare(
+                name,
+                lapin::options::QueueDeclareOptions {
+                    durable: true,
+                    ..Default::default()
+                },
+                FieldTable::default(),
+            )
+            .await
+            .inspect_err(|err| {
+                error!(error = ?err, "Failed to declare RabbitMQ queue");
+            })?;
+
+        info!(queue = ?name, "Queue declared");
+    }
+
+    // Dead lettered messages keep their routing key, the name of the queue they were published to
     channel
+        .queue_bind(
+            &dead_letter_queue,
+            DEAD_LETTER_EXCHANGE,
+            queue,
+            lapin::options::QueueBindOptions::default(),
+            FieldTable::default(),
+        )
+        .await
+        .inspect_err(|err| {
+            error!(error = ?err, "Failed to bind RabbitMQ dead letter queue");
+        })?;
+
+    Ok(())
+}
+
+// Number of messages ready in a queue and of its consumers
+// The queue has to exist, the channel being closed by the broker otherwise
+pub async fn queue_depth(
+    channel: &lapin::Channel,
+    queue: &str,
+) -> Result<(u32, u32), lapin::Error> {
+    let queue = channel
         .queue_declare(
             queue,
             lapin::options::QueueDeclareOptions {
+                passive: true,
+                ..Default::default()
+            },
+            FieldTable::default(),
+        )
+        .await
+        .inspect_err(|err| {
+            error!(error = ?err, "Failed to inspect RabbitMQ queue");
+        })?;
+
+    Ok((queue.message_count(), queue.consumer_count()))
+}
+
+// Move the messages of the dead letter queue back to the queue, returning how many were moved
+// Only the messages present when called are moved, so ones failing again aren't looped on
+pub async fn requeue_dead_letters(
+    channel: &lapin::Channel,
+    queue: &str,
+) -> Result<u32, lapin::Error> {
+    let dead_letter_queue = dead_letter_queue(queue);
+    let (nb_messages, _) = queue_depth(channel, &dead_letter_queue).await?;
+
+    let mut nb_requeued = 0;
+    while nb_requeued < nb_messages {
+        let Some(message) = channel
+            .basic_get(
+                &dead_letter_queue,
+                lapin::options::BasicGetOptions::default(),
+            )
+            .await?
+        else {
+            break;
+        };
+
+        channel
+            .basic_publish(
+                "",
+                queue,
+                lapin::options::BasicPublishOptions::default(),
+                &message.delivery.data,
+                message.delivery.properties.clone(),
+            )
+            .await?
+            .await?;
+
+        message
+            .delivery
+            .ack(lapin::options::BasicAckOptions::default())
+            .await?;
+        nb_requeued += 1;
+    }
+
+    info!(queue = ?queue, "{} dead lettered messages requeued", nb_requeued);
+
+    Ok(nb_requeued)
+}
+
+pub async fn declare_fanout_exchange(
+    channel: &lapin::Channel,
+    exchange: &str,
+) -> Result<(), lapin::Error> {
+    channel
+        .exchange_declare(
+            exchange,
+            lapin::ExchangeKind::Fanout,
+            lapin::options::ExchangeDeclareOptions {
                 durable: true,
                 ..Default::default()
             },
             lapin::types::FieldTable::default(),
         )
         .await
+        .inspect_err(|err| {
+            error!(error = ?err, "Failed to declare RabbitMQ exchange");
+        })?;
+
+    info!(exchange = ?exchange, "Exchange declared");
+
+    Ok(())
+}
+
+// Declare a queue named by the broker and bind it to the exchange
+// The queue is deleted once the connection is closed, so messages don't pile up without consumer
+pub async fn bind_exclusive_queue(
+    channel: &lapin::Channel,
+    exchange: &str,
+) -> Result<String, lapin::Error> {
+    let queue = channel
+        .queue_declare(
+            "",
+            lapin::options::QueueDeclareOptions {
+                exclusive: true,
+                auto_delete: true,
+                ..Default::default()
+            },
+            lapin::types::FieldTable::default(),
+        )
+        .await
         .inspect_err(|err| {
             error!(error = ?err, "Failed to declare RabbitMQ queue");
         })?;
 
-    info!(queue = ?queue, "Queue declared");
+    channel
+        .queue_bind(
+            queue.name().as_str(),
+            exchange,
+            "",
+            lapin::options::QueueBindOptions::default(),
+            lapin::types::FieldTable::default(),
+        )
+        .await
+        .inspect_err(|err| {
+            error!(error = ?err, "Failed to bind RabbitMQ queue");
+        })?;
+
+    info!(queue = ?queue.name(), exchange = ?exchange, "Queue bound to exchange");
 
-    Ok(channel)
+    Ok(queue.name().to_string())
+}
+
+/* /////////////////////////// */
+/* //// RabbitMQ Injector //// */
+/* /////////////////////////// */
+
+pub struct AmqpHeaderInjector<'a> {
+    pub headers: &'a mut FieldTable,
+}
+
+impl Injector for AmqpHeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        self.headers
+            .insert(key.into(), AMQPValue::LongString(value.into()));
+    }
+}
+
+/* //////////////////////////// */
+/* //// RabbitMQ Publisher //// */
+/* //////////////////////////// */
+
+// Serialize the payload to JSON and publish it on the queue with the current trace context
+// Resolves once the broker acknowledged the message
+pub async fn publish<T>(
+    channel: &lapin::Channel,
+    queue: &str,
+    payload: &T,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    T: Serialize,
+{
+    publish_message(channel, "", queue, payload).await
+}
+
+// Publish the payload to every queue bound to the exchange
+pub async fn broadcast<T>(
+    channel: &lapin::Channel,
+    exchange: &str,
+    payload: &T,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    T: Serialize,
+{
+    publish_message(channel, exchange, "", payload).await
+}
+
+async fn publish_message<T>(
+    channel: &lapin::Channel,
+    exchange: &str,
+    routing_key: &str,
+    payload: &T,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    T: Serialize,
+{
+    let time = std::time::Instant::now();
+
+    // Encode payload into JSON
+    let body = serde_json::to_vec(payload).inspect_err(|err| {
+        error!(error = ?err, "Failed to serialize queue payload");
+    })?;
+    trace!("Serialized queue payload in {:?}", time.elapsed());
+
+    // Inject trace context into RabbitMQ headers
+    let mut headers = FieldTable::default();
+    let context = Span::current().context();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(
+            &context,
+            &mut AmqpHeaderInjector {
+                headers: &mut headers,
+            },
+        )
+    });
+
+    let properties = BasicProperties::default()
+        .with_headers(headers)
+        .with_content_type("application/json".into());
+
+    // Send request to the queue
+    let confirmation = channel
+        .basic_publish(
+            exchange,
+            routing_key,
+            lapin::options::BasicPublishOptions::default(),
+            &body,
+            properties,
+        )
+        .await
+        .inspect_err(|err| {
+            error!(
+                error = ?err,
+                exchange = ?exchange,
+                routing_key = ?routing_key,
+                "Failed to publish message to the queue"
+            );
+        })?;
+    trace!(
+        "Published message to '{}{}' in {:?}",
+        exchange,
+        routing_key,
+        time.elapsed()
+    );
+
+    // Check if acknowledgement received
+    // TODO: Check if producer acknowledgement is necessary ?
+    confirmation.await.inspect_err(|err| {
+        error!(error = ?err, "Failed to receive queue confirmation");
+    })?;
+    trace!("Acknowledgement received in {:?}", time.elapsed());
+
+    Ok(())
 }
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+// Update of a live session feed as received from Livetiming, 'data' only holding what changed
+// 'time' is the offset in milliseconds from the start of the live session
+#[derive(Deserialize, Serialize, Debug)]
+pub struct LiveUpdate {
+    pub session_key: i32,
+    pub kind: String,
+    pub time: i32,
+    pub data: serde_json::Value,
+}
@@ -4,4 +4,14 @@ use serde::{Deserialize, Serialize};
 pub struct Meetings {
     pub keys: Vec<i32>,
     pub year: i32,
+    // Id of the tracked job, its status being reported once processed
+    #[serde(default)]
+    pub job: Option<i32>,
+}
+
+impl Meetings {
+    // Key of the tracked job fetching the calendar of a season, shared by every producer to coalesce
+    pub fn job_key(year: i32) -> String {
+        format!("meetings:{}", year)
+    }
 }
@@ -1,3 +1,7 @@
+mod live;
 mod meetings;
+mod sessions;
 
+pub use live::*;
 pub use meetings::*;
+pub use sessions::*;
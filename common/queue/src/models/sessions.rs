@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+// Feeds fetched by a session job, all of them unless 'feeds' is set
+pub const SESSION_FEEDS: [&str; 9] = [
+    "laps",
+    "pit_stops",
+    "race_control",
+    "results",
+    "track_status",
+    "weather",
+    "team_radio",
+    "telemetry",
+    "positions",
+];
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Session {
+    pub key: i32,
+    pub path: String,
+    #[serde(default)]
+    pub feeds: Option<Vec<String>>,
+    // Id of the tracked job, its status being reported once processed
+    #[serde(default)]
+    pub job: Option<i32>,
+}
+
+impl Session {
+    // Key of the tracked job fetching the feeds of a session, shared by every producer to coalesce
+    // Once done, the job fetching every feed records the ingestion of the session
+    pub fn job_key(session_key: i32, feeds: Option<&[String]>) -> String {
+        match feeds {
+            Some(feeds) => format!("session:{}:{}", session_key, feeds.join(",")),
+            None => format!("session:{}", session_key),
+        }
+    }
+
+    pub fn has_feed(&self, feed: &str) -> bool {
+        self.feeds
+            .as_ref()
+            .is_none_or(|feeds| feeds.iter().any(|f| f == feed))
+    }
+}
@@ -1,6 +1,10 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
+
+use tokio_rustls::rustls::ClientConfig;
 use tracing::{debug, instrument, trace};
 
+use crate::tls::{ReloadableConfig, tls_connector};
+
 #[derive(Debug, Clone)]
 pub struct ShutdownSignalError;
 
@@ -15,10 +19,12 @@ impl std::fmt::Display for ShutdownSignalError {
 
 impl std::error::Error for ShutdownSignalError {}
 
+// Connections are made over TLS with the config when given, 'addr' keeping its 'http://' scheme
 #[instrument(name = "gRPC connection", skip_all)]
 pub async fn try_get_grpc_channel(
     addr: impl AsRef<str>,
     interval: Duration,
+    tls: Option<Arc<ReloadableConfig<ClientConfig>>>,
 ) -> Result<tonic::transport::Channel, Box<dyn std::error::Error>> {
     debug!(
         "Connection process to gRPC service on {} initiated",
@@ -33,7 +39,16 @@ pub async fn try_get_grpc_channel(
             loop {
                 trace!("Connection attempt {}...", count,);
 
-                match endpoint.connect().await {
+                let channel = match &tls {
+                    Some(config) => {
+                        endpoint
+                            .connect_with_connector(tls_connector(config.clone()))
+                            .await
+                    }
+                    None => endpoint.connect().await,
+                };
+
+                match channel {
                     Ok(channel) => {
                         debug!("Connection to gRPC service established");
                         return Ok(channel);
@@ -1,3 +1,4 @@
 pub mod grpc;
 pub mod otel;
+pub mod tls;
 pub mod utils;
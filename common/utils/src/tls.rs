@@ -0,0 +1,323 @@
+use std::{
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
+};
+
+use hyper_util::rt::TokioIo;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::Semaphore,
+};
+use tokio_rustls::{
+    TlsAcceptor, TlsConnector,
+    rustls::{
+        ClientConfig, RootCertStore, ServerConfig,
+        crypto::ring,
+        pki_types::{CertificateDer, PrivateKeyDer, ServerName, pem::PemObject},
+        server::WebPkiClientVerifier,
+    },
+    server::TlsStream,
+};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::Uri;
+use tracing::{debug, info, instrument, warn};
+
+// Files changed are taken into account after this interval at most
+const RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+
+// Connections handshaking at the same time before new ones wait to be accepted
+const ACCEPT_BACKLOG: usize = 128;
+
+// Accepting fails when the process runs out of file descriptors, retried after this delay
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_millis(100);
+
+// Clients not completing their handshake in time are dropped, to not hold a connection forever
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// gRPC is served over HTTP/2 only
+const ALPN_H2: &[u8] = b"h2";
+
+type LoadResult<C> = Result<C, Box<dyn std::error::Error + Send + Sync>>;
+
+/* /////////////////////////// */
+/* //// Reloadable Config //// */
+/* /////////////////////////// */
+
+// TLS config built from certificate files, rebuilt when one of them is modified
+// Connections already established keep the config they were opened with
+pub struct ReloadableConfig<C> {
+    current: RwLock<Arc<C>>,
+    files: Vec<PathBuf>,
+    load: Box<dyn Fn() -> LoadResult<C> + Send + Sync>,
+}
+
+impl<C: Send + Sync + 'static> ReloadableConfig<C> {
+    fn new<F>(files: Vec<PathBuf>, load: F) -> LoadResult<Arc<Self>>
+    where
+        F: Fn() -> LoadResult<C> + Send + Sync + 'static,
+    {
+        let config = Arc::new(ReloadableConfig {
+            current: RwLock::new(Arc::new(load()?)),
+            files,
+            load: Box::new(load),
+        });
+
+        // Stops once the config is dropped
+        tokio::spawn(Self::watch(Arc::downgrade(&config)));
+
+        Ok(config)
+    }
+
+    pub fn current(&self) -> Arc<C> {
+        // A poisoned lock only means a writer panicked, the config itself is still valid
+        self.current
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    fn modified(&self) -> Vec<Option<SystemTime>> {
+        self.files
+            .iter()
+            .map(|file| std::fs::metadata(file).and_then(|m| m.modified()).ok())
+            .collect()
+    }
+
+    // Files are polled, an invalid config being ignored until they are modified again
+    async fn watch(config: std::sync::Weak<Self>) {
+        let Some(mut modified) = config.upgrade().map(|c| c.modified()) else {
+            return;
+        };
+
+        loop {
+            tokio::time::sleep(RELOAD_INTERVAL).await;
+
+            let Some(config) = config.upgrade() else {
+                return;
+            };
+
+            let last_modified = config.modified();
+            if last_modified == modified {
+                continue;
+            }
+            modified = last_modified;
+
+            match (config.load)() {
+                Ok(new_config) => {
+                    *config
+                        .current
+                        .write()
+                        .unwrap_or_else(|err| err.into_inner()) = Arc::new(new_config);
+                    info!(files = ?config.files, "TLS certificates reloaded");
+                }
+                Err(err) => {
+                    warn!(error = %err, files = ?config.files, "Failed to reload TLS certificates, previous ones kept")
+                }
+            }
+        }
+    }
+}
+
+/* //////////////////// */
+/* //// TLS Server //// */
+/* //////////////////// */
+
+// Server presenting 'cert', clients needing a certificate signed by 'ca' when set (mTLS)
+pub fn server_config(
+    cert: &str,
+    key: &str,
+    ca: Option<&str>,
+) -> LoadResult<Arc<ReloadableConfig<ServerConfig>>> {
+    let (cert, key, ca) = (
+        PathBuf::from(cert),
+        PathBuf::from(key),
+        ca.map(PathBuf::from),
+    );
+
+    let mut files = vec![cert.clone(), key.clone()];
+    files.extend(ca.clone());
+
+    ReloadableConfig::new(files, move || {
+        let provider = Arc::new(ring::default_provider());
+        let builder = ServerConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()?;
+
+        let builder = match &ca {
+            Some(ca) => builder.with_client_cert_verifier(
+                WebPkiClientVerifier::builder_with_provider(load_roots(ca)?, provider).build()?,
+            ),
+            None => builder.with_no_client_auth(),
+        };
+
+        let mut config = builder.with_single_cert(load_certs(&cert)?, load_key(&key)?)?;
+        config.alpn_protocols = vec![ALPN_H2.to_vec()];
+
+        Ok(config)
+    })
+}
+
+// Connections of the listener once their TLS handshake succeeded, to be served by tonic
+// Handshakes are run concurrently so a slow client doesn't hold the others
+pub fn tls_incoming(
+    listener: TcpListener,
+    config: Arc<ReloadableConfig<ServerConfig>>,
+) -> ReceiverStream<Result<TlsStream<TcpStream>, std::io::Error>> {
+    let (sender, receiver) = tokio::sync::mpsc::channel(ACCEPT_BACKLOG);
+    let handshakes = Arc::new(Semaphore::new(ACCEPT_BACKLOG));
+
+    tokio::spawn(async move {
+        loop {
+            // The semaphore is never closed
+            let Ok(permit) = handshakes.clone().acquire_owned().await else {
+                return;
+            };
+
+            let (stream, addr) = tokio::select! {
+                // Server shut down
+                _ = sender.closed() => return,
+                res = listener.accept() => match res {
+                    Ok(res) => res,
+                    Err(err) => {
+                        warn!(error = %err, "Failed to accept gRPC connection");
+                        tokio::time::sleep(ACCEPT_ERROR_DELAY).await;
+                        continue;
+                    }
+                },
+            };
+
+            let acceptor = TlsAcceptor::from(config.current());
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                // Held until the connection is handed over to the server
+                let _permit = permit;
+
+                match accept(acceptor, stream, addr).await {
+                    Ok(stream) => {
+                        let _ = sender.send(Ok(stream)).await;
+                    }
+                    Err(err) => warn!(error = %err, peer = %addr, "TLS handshake failed"),
+                }
+            });
+        }
+    });
+
+    ReceiverStream::new(receiver)
+}
+
+async fn accept(
+    acceptor: TlsAcceptor,
+    stream: TcpStream,
+    addr: SocketAddr,
+) -> Result<TlsStream<TcpStream>, std::io::Error> {
+    stream.set_nodelay(true)?;
+    let stream = tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream))
+        .await
+        .map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::TimedOut, "TLS handshake timed out")
+        })??;
+    debug!(peer = %addr, "TLS connection established");
+
+    Ok(stream)
+}
+
+/* //////////////////// */
+/* //// TLS Client //// */
+/* //////////////////// */
+
+// Client trusting servers signed by 'ca', presenting 'identity' as '(cert, key)' when set (mTLS)
+pub fn client_config(
+    ca: &str,
+    identity: Option<(&str, &str)>,
+) -> LoadResult<Arc<ReloadableConfig<ClientConfig>>> {
+    let ca = PathBuf::from(ca);
+    let identity = identity.map(|(cert, key)| (PathBuf::from(cert), PathBuf::from(key)));
+
+    let mut files = vec![ca.clone()];
+    files.extend(
+        identity
+            .iter()
+            .flat_map(|(cert, key)| [cert.clone(), key.clone()]),
+    );
+
+    ReloadableConfig::new(files, move || {
+        let provider = Arc::new(ring::default_provider());
+        let builder = ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()?
+            .with_root_certificates(load_roots(&ca)?);
+
+        let mut config = match &identity {
+            Some((cert, key)) => {
+                builder.with_client_auth_cert(load_certs(cert)?, load_key(key)?)?
+            }
+            None => builder.with_no_client_auth(),
+        };
+        config.alpn_protocols = vec![ALPN_H2.to_vec()];
+
+        Ok(config)
+    })
+}
+
+// Connector of a tonic endpoint, opening each connection with the current config
+// The endpoint keeps its 'http://' scheme, TLS being handled here instead of by tonic
+pub fn tls_connector(
+    config: Arc<ReloadableConfig<ClientConfig>>,
+) -> impl tower::Service<
+    Uri,
+    Response = TokioIo<tokio_rustls::client::TlsStream<TcpStream>>,
+    Error = Box<dyn std::error::Error + Send + Sync>,
+    Future = impl Send,
+> + Send
++ 'static {
+    tower::service_fn(move |uri: Uri| {
+        let connector = TlsConnector::from(config.current());
+
+        async move { connect(connector, uri).await.map(TokioIo::new) }
+    })
+}
+
+#[instrument(name = "TLS connection", skip_all)]
+async fn connect(
+    connector: TlsConnector,
+    uri: Uri,
+) -> LoadResult<tokio_rustls::client::TlsStream<TcpStream>> {
+    let host = uri.host().ok_or("Missing host in gRPC address")?;
+    let port = uri.port_u16().ok_or("Missing port in gRPC address")?;
+
+    let stream = TcpStream::connect((host, port)).await?;
+    stream.set_nodelay(true)?;
+
+    // Server certificate has to be issued for the host the client connects to
+    let server_name = ServerName::try_from(host.to_string())?;
+    let stream = connector.connect(server_name, stream).await?;
+    debug!("TLS connection to {}:{} established", host, port);
+
+    Ok(stream)
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+fn load_certs(path: &PathBuf) -> LoadResult<Vec<CertificateDer<'static>>> {
+    let certs = CertificateDer::pem_file_iter(path)?.collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        return Err(format!("No certificate found in {}", path.display()).into());
+    }
+
+    Ok(certs)
+}
+
+fn load_key(path: &PathBuf) -> LoadResult<PrivateKeyDer<'static>> {
+    Ok(PrivateKeyDer::from_pem_file(path)?)
+}
+
+fn load_roots(path: &PathBuf) -> LoadResult<Arc<RootCertStore>> {
+    let mut roots = RootCertStore::empty();
+    for cert in load_certs(path)? {
+        roots.add(cert)?;
+    }
+
+    Ok(Arc::new(roots))
+}
@@ -54,6 +54,18 @@ ALTER TABLE IF EXISTS public.drivers
 
 
 
+DROP TABLE IF EXISTS public.pit_stops;
+DROP TABLE IF EXISTS public.race_control_messages;
+DROP TABLE IF EXISTS public.track_status;
+DROP VIEW IF EXISTS public.sessions_weather;
+DROP VIEW IF EXISTS public.meetings_weather;
+DROP TABLE IF EXISTS public.weather_samples;
+DROP TABLE IF EXISTS public.team_radio;
+DROP TABLE IF EXISTS public.laps;
+DROP TABLE IF EXISTS public.session_results;
+DROP TABLE IF EXISTS public.car_data_laps;
+DROP TABLE IF EXISTS public.car_positions;
+DROP TABLE IF EXISTS public.track_maps;
 DROP TABLE IF EXISTS public.sessions;
 DROP TABLE IF EXISTS public.meetings;
 
@@ -101,6 +113,354 @@ ALTER TABLE IF EXISTS public.sessions
 
 
 
+CREATE TABLE IF NOT EXISTS public.pit_stops
+(
+    id serial PRIMARY KEY,
+    session_key integer NOT NULL,
+    driver_number integer NOT NULL,
+    lap integer NOT NULL,
+    in_time integer,
+    out_time integer,
+    lane_duration integer,
+    stationary_duration integer,
+    UNIQUE (session_key, driver_number, lap)
+)
+WITH (
+    OIDS = FALSE
+);
+
+ALTER TABLE IF EXISTS public.pit_stops
+    ADD FOREIGN KEY (session_key)
+    REFERENCES public.sessions (key) MATCH SIMPLE
+    ON UPDATE NO ACTION
+    ON DELETE NO ACTION
+    NOT VALID;
+
+
+
+CREATE TABLE IF NOT EXISTS public.race_control_messages
+(
+    id serial PRIMARY KEY,
+    session_key integer NOT NULL,
+    date TIMESTAMPTZ NOT NULL,
+    time integer NOT NULL,
+    lap integer,
+    category character varying(255) NOT NULL,
+    flag character varying(255),
+    scope character varying(255),
+    sector integer,
+    driver_number integer,
+    status character varying(255),
+    mode character varying(255),
+    message text NOT NULL,
+    deleted_lap integer,
+    UNIQUE (session_key, date, message)
+)
+WITH (
+    OIDS = FALSE
+);
+
+ALTER TABLE IF EXISTS public.race_control_messages
+    ADD FOREIGN KEY (session_key)
+    REFERENCES public.sessions (key) MATCH SIMPLE
+    ON UPDATE NO ACTION
+    ON DELETE NO ACTION
+    NOT VALID;
+
+
+
+CREATE TABLE IF NOT EXISTS public.track_status
+(
+    id serial PRIMARY KEY,
+    session_key integer NOT NULL,
+    status character varying(255) NOT NULL,
+    message character varying(255) NOT NULL,
+    start_time integer NOT NULL,
+    end_time integer,
+    start_lap integer,
+    end_lap integer,
+    UNIQUE (session_key, start_time)
+)
+WITH (
+    OIDS = FALSE
+);
+
+ALTER TABLE IF EXISTS public.track_status
+    ADD FOREIGN KEY (session_key)
+    REFERENCES public.sessions (key) MATCH SIMPLE
+    ON UPDATE NO ACTION
+    ON DELETE NO ACTION
+    NOT VALID;
+
+
+
+CREATE TABLE IF NOT EXISTS public.weather_samples
+(
+    id serial PRIMARY KEY,
+    session_key integer NOT NULL,
+    time integer NOT NULL,
+    air_temp double precision NOT NULL,
+    track_temp double precision NOT NULL,
+    humidity double precision NOT NULL,
+    pressure double precision NOT NULL,
+    wind_speed double precision NOT NULL,
+    wind_direction integer NOT NULL,
+    rainfall boolean NOT NULL,
+    UNIQUE (session_key, time)
+)
+WITH (
+    OIDS = FALSE
+);
+
+ALTER TABLE IF EXISTS public.weather_samples
+    ADD FOREIGN KEY (session_key)
+    REFERENCES public.sessions (key) MATCH SIMPLE
+    ON UPDATE NO ACTION
+    ON DELETE NO ACTION
+    NOT VALID;
+
+CREATE OR REPLACE VIEW public.sessions_weather AS
+    SELECT
+        session_key,
+        MIN(air_temp) AS air_temp_min,
+        MAX(air_temp) AS air_temp_max,
+        AVG(air_temp) AS air_temp_avg,
+        MIN(track_temp) AS track_temp_min,
+        MAX(track_temp) AS track_temp_max,
+        AVG(track_temp) AS track_temp_avg,
+        AVG(humidity) AS humidity_avg,
+        AVG(wind_speed) AS wind_speed_avg,
+        BOOL_OR(rainfall) AS rainfall
+    FROM public.weather_samples
+    GROUP BY session_key;
+
+CREATE OR REPLACE VIEW public.meetings_weather AS
+    SELECT
+        sessions.meeting_key,
+        MIN(air_temp) AS air_temp_min,
+        MAX(air_temp) AS air_temp_max,
+        AVG(air_temp) AS air_temp_avg,
+        MIN(track_temp) AS track_temp_min,
+        MAX(track_temp) AS track_temp_max,
+        AVG(track_temp) AS track_temp_avg,
+        AVG(humidity) AS humidity_avg,
+        AVG(wind_speed) AS wind_speed_avg,
+        BOOL_OR(rainfall) AS rainfall
+    FROM public.weather_samples
+    JOIN public.sessions ON sessions.key = weather_samples.session_key
+    GROUP BY sessions.meeting_key;
+
+
+
+CREATE TABLE IF NOT EXISTS public.team_radio
+(
+    id serial PRIMARY KEY,
+    session_key integer NOT NULL,
+    date TIMESTAMPTZ NOT NULL,
+    driver_number integer NOT NULL,
+    path character varying(255) NOT NULL,
+    UNIQUE (session_key, path)
+)
+WITH (
+    OIDS = FALSE
+);
+
+ALTER TABLE IF EXISTS public.team_radio
+    ADD FOREIGN KEY (session_key)
+    REFERENCES public.sessions (key) MATCH SIMPLE
+    ON UPDATE NO ACTION
+    ON DELETE NO ACTION
+    NOT VALID;
+
+
+-- 'time' is the offset in milliseconds from the start of the session stream when crossing the line
+CREATE TABLE IF NOT EXISTS public.laps
+(
+    id serial PRIMARY KEY,
+    session_key integer NOT NULL,
+    driver_number integer NOT NULL,
+    lap_number integer NOT NULL,
+    time integer NOT NULL,
+    lap_time integer,
+    sector_1 integer,
+    sector_2 integer,
+    sector_3 integer,
+    speed_i1 integer,
+    speed_i2 integer,
+    speed_fl integer,
+    speed_st integer,
+    UNIQUE (session_key, driver_number, lap_number)
+)
+WITH (
+    OIDS = FALSE
+);
+
+ALTER TABLE IF EXISTS public.laps
+    ADD FOREIGN KEY (session_key)
+    REFERENCES public.sessions (key) MATCH SIMPLE
+    ON UPDATE NO ACTION
+    ON DELETE NO ACTION
+    NOT VALID;
+
+
+
+-- 'team_id' is the team the driver raced for in the session, as named by its driver list
+CREATE TABLE IF NOT EXISTS public.session_results
+(
+    id serial PRIMARY KEY,
+    session_key integer NOT NULL,
+    driver_number integer NOT NULL,
+    position integer,
+    retired boolean NOT NULL,
+    team_id integer,
+    UNIQUE (session_key, driver_number)
+)
+WITH (
+    OIDS = FALSE
+);
+
+ALTER TABLE IF EXISTS public.session_results
+    ADD FOREIGN KEY (session_key)
+    REFERENCES public.sessions (key) MATCH SIMPLE
+    ON UPDATE NO ACTION
+    ON DELETE NO ACTION
+    NOT VALID;
+
+
+
+-- Samples of a lap are stored as arrays, 'time' being the offset in milliseconds from the start of the lap
+CREATE TABLE IF NOT EXISTS public.car_data_laps
+(
+    id serial PRIMARY KEY,
+    session_key integer NOT NULL,
+    driver_number integer NOT NULL,
+    lap_number integer NOT NULL,
+    time integer[] NOT NULL,
+    speed integer[] NOT NULL,
+    throttle integer[] NOT NULL,
+    brake integer[] NOT NULL,
+    rpm integer[] NOT NULL,
+    gear integer[] NOT NULL,
+    drs integer[] NOT NULL,
+    UNIQUE (session_key, driver_number, lap_number)
+)
+WITH (
+    OIDS = FALSE
+);
+
+ALTER TABLE IF EXISTS public.car_data_laps
+    ADD FOREIGN KEY (session_key)
+    REFERENCES public.sessions (key) MATCH SIMPLE
+    ON UPDATE NO ACTION
+    ON DELETE NO ACTION
+    NOT VALID;
+
+
+
+-- Positions of a car during the whole session, 'time' being the offset in milliseconds from the start of the stream
+-- Coordinates are in decimeters
+CREATE TABLE IF NOT EXISTS public.car_positions
+(
+    id serial PRIMARY KEY,
+    session_key integer NOT NULL,
+    driver_number integer NOT NULL,
+    time integer[] NOT NULL,
+    x integer[] NOT NULL,
+    y integer[] NOT NULL,
+    z integer[] NOT NULL,
+    UNIQUE (session_key, driver_number)
+)
+WITH (
+    OIDS = FALSE
+);
+
+ALTER TABLE IF EXISTS public.car_positions
+    ADD FOREIGN KEY (session_key)
+    REFERENCES public.sessions (key) MATCH SIMPLE
+    ON UPDATE NO ACTION
+    ON DELETE NO ACTION
+    NOT VALID;
+
+
+
+-- Outline of the circuit, normalised between 0 and 1 after a rotation in degrees
+-- 'sectors' holds the indexes of the points starting each sector
+CREATE TABLE IF NOT EXISTS public.track_maps
+(
+    id serial PRIMARY KEY,
+    meeting_key integer UNIQUE NOT NULL,
+    session_key integer NOT NULL,
+    rotation double precision NOT NULL,
+    x double precision[] NOT NULL,
+    y double precision[] NOT NULL,
+    sectors integer[] NOT NULL
+)
+WITH (
+    OIDS = FALSE
+);
+
+ALTER TABLE IF EXISTS public.track_maps
+    ADD FOREIGN KEY (meeting_key)
+    REFERENCES public.meetings (key) MATCH SIMPLE
+    ON UPDATE NO ACTION
+    ON DELETE NO ACTION
+    NOT VALID;
+
+
+
+DROP TABLE IF EXISTS public.jobs;
+
+-- Jobs sent to the queue, 'status' being one of 'queued', 'done' or 'failed'
+-- 'key' identifies what is fetched so a job already done isn't sent again
+CREATE TABLE IF NOT EXISTS public.jobs
+(
+    id serial PRIMARY KEY,
+    key character varying(255) UNIQUE NOT NULL,
+    status character varying(31) NOT NULL DEFAULT 'queued',
+    error text,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+)
+WITH (
+    OIDS = FALSE
+);
+
+
+
+DROP TABLE IF EXISTS public.api_keys;
+
+-- Keys are only stored as their SHA-256 hash, 'scopes' being among 'read', 'admin' and 'ingest'
+CREATE TABLE IF NOT EXISTS public.api_keys
+(
+    id serial PRIMARY KEY,
+    name character varying(255) UNIQUE NOT NULL,
+    hash character(64) UNIQUE NOT NULL,
+    scopes character varying(31)[] NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    revoked_at TIMESTAMPTZ
+)
+WITH (
+    OIDS = FALSE
+);
+
+
+
+DROP TABLE IF EXISTS public.rate_limits;
+
+-- Requests per client of the current rate limit window, 'window_start' being in seconds since epoch
+-- Counters are transient, so they aren't written to the WAL
+CREATE UNLOGGED TABLE IF NOT EXISTS public.rate_limits
+(
+    key character varying(255) PRIMARY KEY,
+    window_start bigint NOT NULL,
+    count integer NOT NULL
+)
+WITH (
+    OIDS = FALSE
+);
+
+
+
 CREATE TABLE IF NOT EXISTS public.teams_images
 (
   team_id integer unique NOT NULL,
@@ -0,0 +1,186 @@
+// Local Livetiming hub replaying recorded '.jsonStream' files, to run live sessions offline
+//
+// Usage: cargo run -p metrics_one_worker --example stub_hub -- <session directory> [port] [speed]
+// The worker is then started with 'LIVE_HUB_URL=http://127.0.0.1:<port>/signalr'
+// Port '0' binds an ephemeral port, the one bound being printed
+
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::Value;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    time::Instant,
+};
+use tokio_tungstenite::tungstenite::Message;
+
+pub struct Settings {
+    pub directory: PathBuf,
+    pub speed: f64,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let directory = PathBuf::from(args.next().ok_or("Missing session directory")?);
+    let port: u16 = args.next().map(|p| p.parse()).transpose()?.unwrap_or(5000);
+    let speed: f64 = args.next().map(|s| s.parse()).transpose()?.unwrap_or(1.0);
+
+    let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+    println!(
+        "Stub hub listening on http://{}/signalr",
+        listener.local_addr()?
+    );
+
+    Ok(serve(listener, Settings { directory, speed }).await?)
+}
+
+// Every connection replays the session from its start
+pub async fn serve(listener: TcpListener, settings: Settings) -> std::io::Result<()> {
+    let settings = Arc::new(settings);
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let settings = settings.clone();
+
+        tokio::spawn(async move {
+            if let Err(err) = handle(stream, &settings).await {
+                eprintln!("Connection failed: {}", err);
+            }
+        });
+    }
+}
+
+// Negotiation is a plain HTTP request, the connection itself a websocket upgrade
+async fn handle(
+    mut stream: TcpStream,
+    settings: &Settings,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut buffer = [0; 1024];
+    let size = stream.peek(&mut buffer).await?;
+    let request = String::from_utf8_lossy(&buffer[..size]);
+
+    if request
+        .lines()
+        .next()
+        .is_some_and(|l| l.contains("/negotiate"))
+    {
+        let _ = stream.read(&mut buffer).await?;
+
+        let body = r#"{"ConnectionToken":"stub","ConnectionId":"stub","ProtocolVersion":"1.5"}"#;
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).await?;
+        return Ok(());
+    }
+
+    let mut socket = tokio_tungstenite::accept_async(stream).await?;
+
+    // Wait for the subscription to know which feeds to replay
+    let feeds: Vec<String> = loop {
+        let Some(message) = socket.next().await else {
+            return Ok(());
+        };
+        let Message::Text(text) = message? else {
+            continue;
+        };
+
+        let invocation: Value = serde_json::from_str(&text)?;
+        if invocation["M"] == "Subscribe" {
+            break serde_json::from_value(invocation["A"][0].clone())?;
+        }
+    };
+
+    // The first entry of each stream is the state of the feed when subscribing
+    let mut snapshot = serde_json::Map::new();
+    let mut updates: Vec<(i32, String, Value)> = Vec::new();
+    for feed in feeds {
+        let path = settings.directory.join(format!("{}.jsonStream", feed));
+        let Ok(text) = tokio::fs::read_to_string(&path).await else {
+            continue;
+        };
+
+        let mut entries = text.lines().filter_map(parse_line);
+        if let Some((_, data)) = entries.next() {
+            snapshot.insert(feed.clone(), data);
+        }
+        updates.extend(entries.map(|(time, data)| (time, feed.clone(), data)));
+    }
+    updates.sort_by_key(|(time, _, _)| *time);
+
+    let result = serde_json::json!({ "R": snapshot, "I": "1" });
+    socket
+        .send(Message::Text(result.to_string().into()))
+        .await?;
+
+    // Replay starts with the first update, its date being the one of the recording
+    let origin = updates
+        .first()
+        .map(|(time, _, _)| *time)
+        .unwrap_or_default();
+    let date = snapshot
+        .get("SessionInfo")
+        .and_then(session_start)
+        .unwrap_or_else(chrono::Utc::now)
+        + chrono::Duration::milliseconds(i64::from(origin));
+    let start = Instant::now();
+
+    for (time, feed, data) in updates {
+        let offset = f64::from(time - origin) / settings.speed;
+        tokio::time::sleep_until(start + Duration::from_millis(offset as u64)).await;
+
+        // Dates keep the offsets of the recording, whatever the speed
+        let date = date + chrono::Duration::milliseconds(i64::from(time - origin));
+        let frame = serde_json::json!({
+            "C": "stub",
+            "M": [{ "H": "Streaming", "M": "feed", "A": [feed, data, date.to_rfc3339()] }],
+        });
+        socket.send(Message::Text(frame.to_string().into())).await?;
+    }
+
+    socket.close(None).await?;
+    Ok(())
+}
+
+// Streams start with the session, its date being local to the circuit
+fn session_start(info: &Value) -> Option<chrono::DateTime<chrono::Utc>> {
+    let start =
+        chrono::NaiveDateTime::parse_from_str(info["StartDate"].as_str()?, "%Y-%m-%dT%H:%M:%S")
+            .ok()?;
+
+    let offset = info["GmtOffset"].as_str()?;
+    let sign = if offset.starts_with('-') { -1 } else { 1 };
+    let split: Vec<i64> = offset
+        .trim_start_matches('-')
+        .split(':')
+        .map(|s| s.parse().ok())
+        .collect::<Option<_>>()?;
+    let [hours, minutes, seconds] = split.as_slice() else {
+        return None;
+    };
+
+    let offset = sign * (hours * 3600 + minutes * 60 + seconds);
+    Some(start.and_utc() - chrono::Duration::seconds(offset))
+}
+
+// Lines are formatted as 'hh:mm:ss.mmm{...}', or 'hh:mm:ss.mmm"..."' for compressed feeds
+fn parse_line(line: &str) -> Option<(i32, Value)> {
+    let line = line.trim_start_matches('\u{feff}').trim();
+    let split = line.find(['{', '"'])?;
+    let (time, data) = line.split_at(split);
+
+    let split: Vec<&str> = time.split(':').collect();
+    let [hours, minutes, seconds] = split.as_slice() else {
+        return None;
+    };
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    let seconds: f64 = seconds.parse().ok()?;
+    let time = (hours * 3600 + minutes * 60) * 1000 + (seconds * 1000.0).round() as i32;
+
+    Some((time, serde_json::from_str(data).ok()?))
+}
@@ -0,0 +1,172 @@
+use std::collections::{BTreeMap, HashMap};
+
+use clap::Args;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tracing::{debug, info, instrument, warn};
+
+use crate::{
+    commands::store::{self, MirrorStore},
+    fetch::source::SOURCE,
+    models::Meetings,
+};
+
+// Hashes of the mirrored files, stored along them to skip the unchanged ones on the next run
+const MANIFEST_PATH: &str = ".manifest.json";
+
+#[derive(Args)]
+pub struct MirrorArgs {
+    /// Season to mirror
+    #[arg(long)]
+    pub year: i32,
+
+    /// Only mirror this meeting
+    #[arg(long)]
+    pub meeting: Option<i32>,
+
+    /// Only mirror this session
+    #[arg(long)]
+    pub session: Option<i32>,
+
+    /// Local directory, or bucket as 's3://{bucket}/{prefix}'
+    #[arg(long)]
+    pub to: String,
+}
+
+// Files of a session are listed in its own 'Index.json'
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SessionIndex {
+    #[serde(default)]
+    feeds: HashMap<String, FeedFiles>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct FeedFiles {
+    key_frame_path: Option<String>,
+    stream_path: Option<String>,
+}
+
+/* //////////////////////// */
+/* //// Mirror Command //// */
+/* //////////////////////// */
+
+// The mirror keeps the layout of Livetiming API, so it can be read back with 'LIVETIMING_DIR'
+// or by pointing 'LIVETIMING_URL' to the bucket
+#[instrument(name = "[Command] Mirror", skip_all, fields(year = args.year), err)]
+pub async fn run(args: MirrorArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let time = std::time::Instant::now();
+
+    let store = store::from_destination(&args.to)?;
+    let mut mirror = Mirror::load(store).await?;
+
+    let index_path = format!("{}/Index.json", args.year);
+    let index = mirror.copy(&index_path).await?;
+    let meetings: Meetings = serde_json::from_str(index.trim_start_matches('\u{feff}'))?;
+
+    let sessions = meetings
+        .meetings
+        .iter()
+        .filter(|m| args.meeting.is_none_or(|key| key == m.key))
+        .flat_map(|m| m.sessions.iter())
+        .filter(|s| args.session.is_none_or(|key| key == s.key));
+
+    for session in sessions {
+        // Sessions without path haven't taken place yet
+        let Some(path) = session.path.as_deref() else {
+            debug!("Session {} has no data yet, skipping", session.key);
+            continue;
+        };
+
+        if let Err(err) = mirror.copy_session(path).await {
+            warn!(error = %err, "Failed to mirror session {}", session.key);
+        }
+
+        // Saved after each session, so an interrupted mirror doesn't copy them again
+        mirror.save().await?;
+    }
+
+    info!(
+        "{} files copied, {} unchanged and {} failed in {:?}",
+        mirror.nb_copied,
+        mirror.nb_unchanged,
+        mirror.nb_failed,
+        time.elapsed()
+    );
+
+    Ok(())
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+struct Mirror {
+    store: Box<dyn MirrorStore>,
+    manifest: BTreeMap<String, String>,
+    nb_copied: usize,
+    nb_unchanged: usize,
+    nb_failed: usize,
+}
+
+impl Mirror {
+    async fn load(store: Box<dyn MirrorStore>) -> Result<Self, Box<dyn std::error::Error>> {
+        let manifest = match store.get(MANIFEST_PATH).await? {
+            Some(content) => serde_json::from_slice(&content)?,
+            None => BTreeMap::new(),
+        };
+
+        Ok(Mirror {
+            store,
+            manifest,
+            nb_copied: 0,
+            nb_unchanged: 0,
+            nb_failed: 0,
+        })
+    }
+
+    async fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let content = serde_json::to_vec_pretty(&self.manifest)?;
+        self.store.put(MANIFEST_PATH, &content).await
+    }
+
+    async fn copy_session(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let index = self.copy(&format!("{}Index.json", path)).await?;
+        let index: SessionIndex = serde_json::from_str(index.trim_start_matches('\u{feff}'))?;
+
+        let files = index
+            .feeds
+            .into_values()
+            .flat_map(|f| [f.key_frame_path, f.stream_path])
+            .flatten();
+
+        // A missing feed doesn't prevent the others from being mirrored
+        for file in files {
+            if let Err(err) = self.copy(&format!("{}{}", path, file)).await {
+                warn!(error = %err, "Failed to mirror {}{}", path, file);
+                self.nb_failed += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Copy a file to the store unless its content is the one already mirrored
+    async fn copy(&mut self, path: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let content = SOURCE.get(path).await?.content;
+        let hash = hex::encode(Sha256::digest(content.as_bytes()));
+
+        if self.manifest.get(path) == Some(&hash) {
+            debug!("{} unchanged, skipping", path);
+            self.nb_unchanged += 1;
+            return Ok(content);
+        }
+
+        self.store.put(path, content.as_bytes()).await?;
+        self.manifest.insert(path.to_string(), hash);
+        self.nb_copied += 1;
+
+        Ok(content)
+    }
+}
@@ -0,0 +1,24 @@
+pub mod mirror;
+pub mod store;
+
+use clap::{Parser, Subcommand};
+
+// Without command, the worker consumes the jobs of the queues
+#[derive(Parser)]
+#[command(version, about = "MetricsOne worker")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Copy Livetiming files of a year, a meeting or a session to a directory or a bucket
+    Mirror(mirror::MirrorArgs),
+}
+
+pub async fn run(command: Command) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        Command::Mirror(args) => mirror::run(args).await,
+    }
+}
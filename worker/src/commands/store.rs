@@ -0,0 +1,211 @@
+use std::{future::Future, path::PathBuf, pin::Pin};
+
+use hmac::{Hmac, Mac};
+use reqwest::{StatusCode, Url};
+use sha2::{Digest, Sha256};
+use tracing::debug;
+
+use crate::{fetch::source::relative_path, settings::ENV};
+
+pub type StoreResult<'a, T> =
+    Pin<Box<dyn Future<Output = Result<T, Box<dyn std::error::Error>>> + Send + 'a>>;
+
+// Destination of a mirror, 'path' being relative to its root with the layout of Livetiming API
+pub trait MirrorStore: Send + Sync {
+    // 'None' when the file doesn't exist
+    fn get<'a>(&'a self, path: &'a str) -> StoreResult<'a, Option<Vec<u8>>>;
+    fn put<'a>(&'a self, path: &'a str, content: &'a [u8]) -> StoreResult<'a, ()>;
+}
+
+// Destinations are either a local directory or a bucket, given as 's3://{bucket}/{prefix}'
+pub fn from_destination(
+    destination: &str,
+) -> Result<Box<dyn MirrorStore>, Box<dyn std::error::Error>> {
+    match destination.strip_prefix("s3://") {
+        Some(location) => {
+            let (bucket, prefix) = location.split_once('/').unwrap_or((location, ""));
+            Ok(Box::new(BucketStore::new(bucket, prefix)?))
+        }
+        None => Ok(Box::new(DirectoryStore {
+            root: PathBuf::from(destination),
+        })),
+    }
+}
+
+/* ///////////////////////// */
+/* //// Directory Store //// */
+/* ///////////////////////// */
+
+// Paths are checked like the ones read by 'DirectorySource', to not write outside of the root
+pub struct DirectoryStore {
+    root: PathBuf,
+}
+
+impl MirrorStore for DirectoryStore {
+    fn get<'a>(&'a self, path: &'a str) -> StoreResult<'a, Option<Vec<u8>>> {
+        Box::pin(async move {
+            let file = self.root.join(relative_path(path)?);
+            match tokio::fs::read(file).await {
+                Ok(content) => Ok(Some(content)),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(err) => Err(err.into()),
+            }
+        })
+    }
+
+    fn put<'a>(&'a self, path: &'a str, content: &'a [u8]) -> StoreResult<'a, ()> {
+        Box::pin(async move {
+            let file = self.root.join(relative_path(path)?);
+            if let Some(parent) = file.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+
+            debug!("Write {}", file.display());
+            tokio::fs::write(file, content).await?;
+            Ok(())
+        })
+    }
+}
+
+/* ////////////////////// */
+/* //// Bucket Store //// */
+/* ////////////////////// */
+
+// S3 compatible bucket (e.g. MinIO), addressed path-style and signed with AWS Signature V4
+pub struct BucketStore {
+    endpoint: Url,
+    region: String,
+    access_key: String,
+    secret_key: String,
+    bucket: String,
+    prefix: String,
+    client: reqwest::Client,
+}
+
+impl BucketStore {
+    pub fn new(bucket: &str, prefix: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let Some(s3) = ENV.s3.as_ref() else {
+            return Err("S3 settings are needed to mirror to a bucket".into());
+        };
+
+        Ok(BucketStore {
+            endpoint: Url::parse(&s3.endpoint)?,
+            region: s3.region.clone(),
+            access_key: s3.access_key.clone(),
+            secret_key: s3.secret_key.clone(),
+            bucket: bucket.to_string(),
+            prefix: prefix.trim_matches('/').to_string(),
+            client: reqwest::Client::new(),
+        })
+    }
+
+    fn request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        content: &[u8],
+    ) -> reqwest::RequestBuilder {
+        let key = match self.prefix.as_str() {
+            "" => path.to_string(),
+            prefix => format!("{}/{}", prefix, path),
+        };
+        let uri = format!("/{}/{}", self.bucket, encode_key(&key));
+
+        let host = match self.endpoint.port() {
+            Some(port) => format!("{}:{}", self.endpoint.host_str().unwrap_or_default(), port),
+            None => self.endpoint.host_str().unwrap_or_default().to_string(),
+        };
+
+        let now = chrono::Utc::now();
+        let date = now.format("%Y%m%d").to_string();
+        let timestamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let content_hash = hex::encode(Sha256::digest(content));
+
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            method, uri, host, content_hash, timestamp, signed_headers, content_hash
+        );
+
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            timestamp,
+            scope,
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let key = [date.as_str(), &self.region, "s3", "aws4_request"]
+            .iter()
+            .fold(
+                format!("AWS4{}", self.secret_key).into_bytes(),
+                |key, part| hmac(&key, part.as_bytes()),
+            );
+        let signature = hex::encode(hmac(&key, string_to_sign.as_bytes()));
+
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key, scope, signed_headers, signature
+        );
+
+        let url = format!("{}{}", self.endpoint.as_str().trim_end_matches('/'), uri);
+        self.client
+            .request(method, url)
+            .header("x-amz-content-sha256", content_hash)
+            .header("x-amz-date", timestamp)
+            .header(reqwest::header::AUTHORIZATION, authorization)
+    }
+}
+
+impl MirrorStore for BucketStore {
+    fn get<'a>(&'a self, path: &'a str) -> StoreResult<'a, Option<Vec<u8>>> {
+        Box::pin(async move {
+            let res = self.request(reqwest::Method::GET, path, &[]).send().await?;
+
+            match res.status() {
+                StatusCode::NOT_FOUND => Ok(None),
+                status if status.is_success() => Ok(Some(res.bytes().await?.to_vec())),
+                status => Err(format!("Failed to get '{}' from bucket: {}", path, status).into()),
+            }
+        })
+    }
+
+    fn put<'a>(&'a self, path: &'a str, content: &'a [u8]) -> StoreResult<'a, ()> {
+        Box::pin(async move {
+            debug!("Upload {} to bucket {}", path, self.bucket);
+            let res = self
+                .request(reqwest::Method::PUT, path, content)
+                .body(content.to_vec())
+                .send()
+                .await?;
+
+            if !res.status().is_success() {
+                return Err(format!("Failed to put '{}' in bucket: {}", path, res.status()).into());
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+// Object keys are URI encoded as in the signature, except for the '/' separators
+fn encode_key(key: &str) -> String {
+    key.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+
+use lapin::types::{AMQPValue, FieldTable};
+use opentelemetry::{KeyValue, global, metrics::Counter, propagation::Extractor};
+use serde::de::DeserializeOwned;
+use tokio_stream::StreamExt;
+use tracing::{Instrument, Span, error, info, info_span, trace};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+// TODO: Move to common crate and move to traditional struct
+struct AmqpHeaderExtractor {
+    headers: HashMap<String, String>,
+}
+
+impl AmqpHeaderExtractor {
+    fn from_field_table(field_table: &FieldTable) -> Self {
+        let headers = field_table
+            .inner()
+            .iter()
+            .filter_map(|(k, v)| match v {
+                AMQPValue::LongString(s) => Some((k.to_string(), s.to_string())),
+                _ => None,
+            })
+            .collect();
+
+        Self { headers }
+    }
+}
+
+impl Extractor for AmqpHeaderExtractor {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.headers.get(key).map(|v| v.as_str())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.headers.keys().map(|k| k.as_str()).collect()
+    }
+}
+
+pub async fn get_consumer(
+    channel: &lapin::Channel,
+    queue: &str,
+) -> Result<lapin::Consumer, lapin::Error> {
+    let consumer = channel
+        .basic_consume(
+            queue,
+            &format!("worker.{}", queue), // Tags have to be unique on a channel
+            lapin::options::BasicConsumeOptions::default(),
+            lapin::types::FieldTable::default(),
+        )
+        .await
+        .inspect_err(|err| {
+            error!(error = ?err, "Consumer failed");
+        })?;
+
+    info!(
+        "RabbitMQ consumer setup completed and listening to queue '{}'",
+        queue
+    );
+
+    Ok(consumer)
+}
+
+// Consume messages from the queue until the consumer fails
+// Each message is deserialized into 'P' and processed by 'job', then acknowledged on success
+pub async fn consume<P, F, Fut>(mut consumer: lapin::Consumer, counter: &Counter<u64>, job: F)
+where
+    P: DeserializeOwned,
+    F: Fn(P) -> Fut,
+    Fut: Future<Output = Result<(), Box<dyn std::error::Error>>>,
+{
+    let queue = consumer.queue().to_string();
+
+    while let Some(delivery) = consumer.next().await {
+        let delivery = match delivery {
+            Ok(delivery) => delivery,
+            Err(err) => {
+                error!(error = ?err, "Error in RabbitMQ consumer, stopping consumption");
+                counter.add(
+                    1,
+                    &[
+                        KeyValue::new("message.status", "failed"),
+                        KeyValue::new("message.queue", queue.clone()),
+                    ],
+                );
+
+                break;
+            }
+        };
+
+        // Get Trace context from request metadata
+        let parent_cx = if let Some(headers) = delivery.properties.headers() {
+            global::get_text_map_propagator(|propagator| {
+                propagator.extract(&AmqpHeaderExtractor::from_field_table(headers))
+            })
+        } else {
+            Span::current().context()
+        };
+
+        let span = info_span!("Message consumer", queue = queue);
+        span.set_parent(parent_cx);
+
+        async {
+            // Deserialize the message
+            let payload: P = match serde_json::from_slice(&delivery.data) {
+                Ok(payload) => payload,
+                Err(err) => {
+                    error!(error = ?err, "Failed to deserialize message payload, discarding message");
+                    counter.add(
+                        1,
+                        &[
+                            KeyValue::new("message.status", "failed"),
+                            KeyValue::new("message.queue", queue.clone()),
+                        ],
+                    );
+
+                    if let Err(err) = delivery
+                        .nack(lapin::options::BasicNackOptions::default())
+                        .await
+                    {
+                        error!(error = ?err, "Failed to nack message");
+                    }
+                    return;
+                }
+            };
+
+            // The message is now correctly deserialized, we can process it
+            match job(payload).await {
+                Ok(_) => {
+                    trace!("Successfully processed message");
+                    counter.add(
+                        1,
+                        &[
+                            KeyValue::new("message.status", "success"),
+                            KeyValue::new("message.queue", queue.clone()),
+                        ],
+                    );
+
+                    if let Err(err) = delivery
+                        .ack(lapin::options::BasicAckOptions::default())
+                        .await
+                    {
+                        error!(error = ?err, "Failed to ack message");
+                    }
+                }
+                Err(err) => {
+                    error!(error = ?err, "Failed to process message");
+                    counter.add(
+                        1,
+                        &[
+                            KeyValue::new("message.status", "failed"),
+                            KeyValue::new("message.queue", queue.clone()),
+                        ],
+                    );
+
+                    if let Err(err) = delivery
+                        .nack(lapin::options::BasicNackOptions::default())
+                        .await
+                    {
+                        error!(error = ?err, "Failed to nack message");
+                    }
+                }
+            }
+        }
+        .instrument(span)
+        .await;
+    }
+}
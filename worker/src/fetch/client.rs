@@ -0,0 +1,373 @@
+use std::{collections::HashMap, path::PathBuf, sync::Mutex, time::Duration};
+
+use opentelemetry::{KeyValue, global, metrics::Counter};
+use reqwest::{StatusCode, header};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::Semaphore;
+use tracing::{debug, trace, warn};
+
+use crate::{fetch::rate_limit::TokenBucket, settings::ClientSettings};
+
+// Bodies kept in memory to answer conditional requests, larger ones are only cached on disk
+const MEMORY_CACHE_MAX_SIZE: usize = 1024 * 1024;
+// Total size of the bodies kept in memory, the least recently used ones being evicted past it
+const MEMORY_CACHE_CAPACITY: usize = 32 * 1024 * 1024;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+// Content of a file, 'modified' being false when it's the same as the last time it was fetched
+pub struct Fetched {
+    pub content: String,
+    pub modified: bool,
+}
+
+/* /////////////////////////// */
+/* //// Livetiming Client //// */
+/* /////////////////////////// */
+
+// HTTP client sending conditional requests for the files already fetched
+// Failed requests are retried with an exponential backoff and jitter
+// Requests are rate limited and their concurrency bounded, to not overload Livetiming API
+pub struct LivetimingClient {
+    client: reqwest::Client,
+    retries: u32,
+    cache: ResponseCache,
+    bucket: TokenBucket,
+    permits: Semaphore,
+    throttled: Counter<u64>,
+}
+
+impl LivetimingClient {
+    pub fn new(settings: &ClientSettings) -> Result<Self, reqwest::Error> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(settings.timeout))
+            .user_agent(&settings.user_agent)
+            .build()?;
+
+        let throttled = global::meter("metrics-one-worker")
+            .u64_counter("m1.livetiming.throttled")
+            .with_description("Requests to Livetiming API delayed by the client")
+            .build();
+
+        Ok(LivetimingClient {
+            client,
+            retries: settings.retries,
+            cache: ResponseCache::new(settings.cache_dir.as_deref()),
+            bucket: TokenBucket::new(settings.rate_limit, settings.burst),
+            permits: Semaphore::new(settings.max_concurrency.max(1)),
+            throttled,
+        })
+    }
+
+    pub async fn get(&self, url: &str) -> Result<Fetched, Box<dyn std::error::Error>> {
+        let cached = self.cache.get(url);
+        let mut attempt = 0;
+
+        loop {
+            let mut request = self.client.get(url);
+            if let Some(cached) = cached.as_ref() {
+                if let Some(etag) = cached.etag.as_deref() {
+                    request = request.header(header::IF_NONE_MATCH, etag);
+                }
+                if let Some(last_modified) = cached.last_modified.as_deref() {
+                    request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+                }
+            }
+
+            // The permit is held until the body is read
+            let permit = match self.permits.try_acquire() {
+                Ok(permit) => permit,
+                Err(_) => {
+                    self.throttle("concurrency");
+                    self.permits.acquire().await?
+                }
+            };
+            if self.bucket.acquire().await {
+                self.throttle("rate_limit");
+            }
+
+            let mut retry_after = None;
+            let error = match request.send().await {
+                Ok(res) if res.status() == StatusCode::NOT_MODIFIED => {
+                    let Some(cached) = cached else {
+                        return Err("Livetiming API answered a request that wasn't conditional with 'Not Modified'".into());
+                    };
+
+                    debug!("{} not modified", url);
+                    return Ok(Fetched {
+                        content: cached.body,
+                        modified: false,
+                    });
+                }
+                Ok(res) if res.status().is_success() => {
+                    let etag = header_value(&res, header::ETAG);
+                    let last_modified = header_value(&res, header::LAST_MODIFIED);
+                    let body = res.text().await?;
+
+                    if etag.is_some() || last_modified.is_some() {
+                        self.cache.put(
+                            url,
+                            CacheEntry {
+                                etag,
+                                last_modified,
+                                body: body.clone(),
+                            },
+                        );
+                    }
+
+                    return Ok(Fetched {
+                        content: body,
+                        modified: true,
+                    });
+                }
+                Ok(res) if is_retryable(res.status()) => {
+                    retry_after = header_value(&res, header::RETRY_AFTER)
+                        .and_then(|value| parse_retry_after(&value));
+                    format!("Livetiming API responded with {}", res.status())
+                }
+                Ok(res) => {
+                    return Err(format!("Livetiming API responded with {}", res.status()).into());
+                }
+                Err(err) if err.is_timeout() || err.is_connect() || err.is_request() => {
+                    err.to_string()
+                }
+                Err(err) => return Err(err.into()),
+            };
+
+            drop(permit);
+
+            attempt += 1;
+            if attempt > self.retries {
+                return Err(format!(
+                    "Failed to fetch {} after {} attempts: {}",
+                    url, attempt, error
+                )
+                .into());
+            }
+
+            // The server asking to slow down, every request waits
+            let mut delay = retry_delay(attempt);
+            if let Some(retry_after) = retry_after {
+                self.throttle("retry_after");
+                self.bucket.pause(retry_after).await;
+                delay = delay.max(retry_after);
+            }
+
+            warn!("Failed to fetch {} ({}), retry in {:?}", url, error, delay);
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    fn throttle(&self, reason: &'static str) {
+        trace!("Request to Livetiming API throttled by {}", reason);
+        self.throttled
+            .add(1, &[KeyValue::new("throttle.reason", reason)]);
+    }
+}
+
+/* //////////////////////// */
+/* //// Response Cache //// */
+/* //////////////////////// */
+
+#[derive(Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: String,
+}
+
+// Responses are kept in memory, and in a directory when one is configured to survive restarts
+// Files of the directory are named after the hash of their URL
+struct ResponseCache {
+    memory: Mutex<MemoryCache>,
+    directory: Option<PathBuf>,
+}
+
+impl ResponseCache {
+    fn new(directory: Option<&str>) -> Self {
+        ResponseCache {
+            memory: Mutex::new(MemoryCache::new(MEMORY_CACHE_CAPACITY)),
+            directory: directory.filter(|d| !d.is_empty()).map(PathBuf::from),
+        }
+    }
+
+    fn get(&self, url: &str) -> Option<CacheEntry> {
+        if let Some(entry) = self.memory.lock().ok()?.get(url) {
+            return Some(entry);
+        }
+
+        let file = self.file(url)?;
+        let content = std::fs::read(file).ok()?;
+        serde_json::from_slice(&content).ok()
+    }
+
+    fn put(&self, url: &str, entry: CacheEntry) {
+        if let Some(file) = self.file(url) {
+            let written = std::fs::create_dir_all(file.parent().unwrap_or(&file)).and_then(|_| {
+                std::fs::write(&file, serde_json::to_vec(&entry).unwrap_or_default())
+            });
+            if let Err(err) = written {
+                warn!(error = ?err, "Failed to write cache entry of {}", url);
+            }
+        }
+
+        if entry.body.len() <= MEMORY_CACHE_MAX_SIZE
+            && let Ok(mut memory) = self.memory.lock()
+        {
+            memory.insert(url, entry);
+        }
+    }
+
+    fn file(&self, url: &str) -> Option<PathBuf> {
+        let hash = hex::encode(Sha256::digest(url.as_bytes()));
+        Some(self.directory.as_ref()?.join(format!("{}.json", hash)))
+    }
+}
+
+// Entries bounded by the total size of their bodies, evicted from the least recently used
+// Entries are few enough for the eviction to scan them, instead of keeping them ordered
+struct MemoryCache {
+    entries: HashMap<String, (CacheEntry, u64)>,
+    capacity: usize,
+    size: usize,
+    clock: u64,
+}
+
+impl MemoryCache {
+    fn new(capacity: usize) -> Self {
+        MemoryCache {
+            entries: HashMap::new(),
+            capacity,
+            size: 0,
+            clock: 0,
+        }
+    }
+
+    fn get(&mut self, url: &str) -> Option<CacheEntry> {
+        self.clock += 1;
+
+        let (entry, used_at) = self.entries.get_mut(url)?;
+        *used_at = self.clock;
+        Some(entry.clone())
+    }
+
+    fn insert(&mut self, url: &str, entry: CacheEntry) {
+        self.clock += 1;
+
+        self.size += entry.body.len();
+        if let Some((previous, _)) = self.entries.insert(url.to_string(), (entry, self.clock)) {
+            self.size -= previous.body.len();
+        }
+
+        while self.size > self.capacity {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, used_at))| *used_at)
+                .map(|(url, _)| url.clone())
+            else {
+                break;
+            };
+
+            if let Some((evicted, _)) = self.entries.remove(&oldest) {
+                self.size -= evicted.body.len();
+            }
+        }
+    }
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+fn header_value(res: &reqwest::Response, name: header::HeaderName) -> Option<String> {
+    Some(res.headers().get(name)?.to_str().ok()?.to_string())
+}
+
+// 'Retry-After' is either a number of seconds or a date
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value.trim()).ok()?;
+    (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+        .to_std()
+        .ok()
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+// Exponential backoff, with a random jitter so retries of concurrent requests are spread
+fn retry_delay(attempt: u32) -> Duration {
+    let delay = RETRY_BASE_DELAY * 2u32.pow(attempt.saturating_sub(1).min(6));
+    let jitter = rand::random_range(0..=delay.as_millis() as u64);
+    delay + Duration::from_millis(jitter)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use reqwest::StatusCode;
+
+    use super::{CacheEntry, MemoryCache, is_retryable, parse_retry_after};
+
+    fn entry(size: usize) -> CacheEntry {
+        CacheEntry {
+            etag: Some("etag".to_string()),
+            last_modified: None,
+            body: "x".repeat(size),
+        }
+    }
+
+    #[test]
+    fn memory_cache_evicts_least_recently_used() {
+        let mut cache = MemoryCache::new(10);
+        cache.insert("a", entry(4));
+        cache.insert("b", entry(4));
+
+        // 'a' is used after 'b', so 'b' is evicted first
+        assert!(cache.get("a").is_some());
+        cache.insert("c", entry(4));
+
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("c").is_some());
+        assert_eq!(cache.size, 8);
+    }
+
+    #[test]
+    fn memory_cache_replaces_entries() {
+        let mut cache = MemoryCache::new(10);
+        cache.insert("a", entry(4));
+        cache.insert("a", entry(6));
+
+        assert_eq!(cache.size, 6);
+        assert_eq!(cache.get("a").map(|e| e.body.len()), Some(6));
+    }
+
+    #[test]
+    fn retry_after_is_seconds_or_date() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after(" 0 "), Some(Duration::ZERO));
+
+        let date = (chrono::Utc::now() + chrono::TimeDelta::seconds(60)).to_rfc2822();
+        let delay = parse_retry_after(&date).unwrap();
+        assert!(delay > Duration::from_secs(55) && delay <= Duration::from_secs(60));
+
+        // A date already past gives no delay to wait for
+        let date = (chrono::Utc::now() - chrono::TimeDelta::seconds(60)).to_rfc2822();
+        assert_eq!(parse_retry_after(&date), None);
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn too_many_requests_is_retried() {
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!is_retryable(StatusCode::NOT_FOUND));
+    }
+}
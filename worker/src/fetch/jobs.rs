@@ -0,0 +1,49 @@
+use metrics_one_grpc::proto::{
+    RegisterJobRequest, RegisterJobResponse, UpdateJobRequest,
+    insert_service_client::InsertServiceClient,
+};
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+use tracing::{debug, warn};
+
+// Report the outcome of a tracked job to the API service
+// A failed report doesn't fail the job, it's only sent again if the job is
+pub async fn report<F>(
+    api_client: &mut InsertServiceClient<InterceptedService<Channel, F>>,
+    job: Option<i32>,
+    result: &Result<(), Box<dyn std::error::Error>>,
+) where
+    F: tonic::service::Interceptor,
+{
+    let Some(id) = job else {
+        return;
+    };
+
+    let request = UpdateJobRequest {
+        id,
+        done: result.is_ok(),
+        error: result.as_ref().err().map(|err| err.to_string()),
+    };
+
+    match api_client.update_job(request).await {
+        Ok(_) => debug!("Job {} reported", id),
+        Err(err) => warn!(error = ?err, "Failed to report job {}", id),
+    }
+}
+
+// Register a job produced by the worker, coalescing it with the same job in flight if any
+// Only jobs registered as new have to be sent to the queue
+pub async fn register<F>(
+    api_client: &mut InsertServiceClient<InterceptedService<Channel, F>>,
+    key: &str,
+    force: bool,
+) -> Result<RegisterJobResponse, tonic::Status>
+where
+    F: tonic::service::Interceptor,
+{
+    let request = RegisterJobRequest {
+        key: key.to_string(),
+        force,
+    };
+
+    Ok(api_client.register_job(request).await?.into_inner())
+}
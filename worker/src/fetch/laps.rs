@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use metrics_one_grpc::proto::{
+    InsertLapsRequest, insert_laps_request::Lap, insert_service_client::InsertServiceClient,
+};
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+use tracing::{info, instrument, trace};
+
+use crate::{
+    fetch::livetiming::{self, StreamEntry},
+    models::TimingData,
+};
+
+#[instrument(name = "[Feed] Laps", skip_all, err)]
+pub async fn fetch_feed<F>(
+    api_client: &mut InsertServiceClient<InterceptedService<Channel, F>>,
+    params: &metrics_one_queue::models::Session,
+    timing: &[StreamEntry<TimingData>],
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: tonic::service::Interceptor,
+{
+    let time = std::time::Instant::now();
+
+    let laps = build_laps(timing);
+    trace!("Data processed in {:?}", time.elapsed());
+
+    let nb_laps = laps.len();
+    if nb_laps == 0 {
+        info!("No lap found");
+        return Ok(());
+    }
+
+    //Send request for processing to API
+    trace!("Send {} laps to API for insertion", nb_laps);
+    api_client
+        .insert_laps(InsertLapsRequest {
+            session_key: params.key,
+            laps,
+        })
+        .await?;
+
+    info!(
+        "{} laps fetched and processed by API service sucessfully in {:?}",
+        nb_laps,
+        time.elapsed(),
+    );
+
+    Ok(())
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+#[derive(Default)]
+struct DriverLapState {
+    laps: i32,
+    current: Option<usize>,
+    sectors: [Option<i32>; 3],
+    speeds: [Option<i32>; 4],
+}
+
+impl DriverLapState {
+    // The next lap is considered started once one of its first sectors is known
+    fn lap_started(&self) -> bool {
+        self.sectors[..2].iter().any(Option::is_some)
+    }
+}
+
+// A lap is completed when 'NumberOfLaps' increases, its time being the line crossing
+// 'LastLapTime' is sent along the crossing and is attached to the lap just completed
+// Sectors and speeds are collected on the lap being driven,
+// the ones measured at the finish line may arrive after the crossing
+pub fn build_laps(timing: &[StreamEntry<TimingData>]) -> Vec<Lap> {
+    let mut laps: Vec<Lap> = Vec::new();
+    let mut states: HashMap<i32, DriverLapState> = HashMap::new();
+
+    for entry in timing {
+        for (number, line) in entry.data.lines.iter() {
+            let Ok(driver_number) = number.parse::<i32>() else {
+                continue;
+            };
+            let state = states.entry(driver_number).or_default();
+
+            let sectors = line
+                .sectors
+                .as_ref()
+                .map(|s| s.entries())
+                .unwrap_or_default();
+            for (i, sector) in sectors {
+                let Some(sector_time) =
+                    sector.value.as_deref().and_then(livetiming::parse_lap_time)
+                else {
+                    continue;
+                };
+
+                match (i, state.current) {
+                    // Last sector of a lap already completed, as the next lap isn't started yet
+                    (2, Some(current)) if !state.lap_started() => {
+                        laps[current].sector_3.get_or_insert(sector_time);
+                    }
+                    (0..=2, _) => state.sectors[i] = Some(sector_time),
+                    _ => (),
+                }
+            }
+
+            let speeds = line
+                .speeds
+                .as_ref()
+                .map(|s| [s.i1.as_ref(), s.i2.as_ref(), s.fl.as_ref(), s.st.as_ref()])
+                .unwrap_or_default();
+            for (i, speed) in speeds.into_iter().enumerate() {
+                let Some(speed) = speed
+                    .and_then(|s| s.value.as_deref())
+                    .and_then(|v| v.trim().parse::<i32>().ok())
+                else {
+                    continue;
+                };
+
+                match (i, state.current) {
+                    // Finish line speed of a lap already completed
+                    (2, Some(current)) if !state.lap_started() => {
+                        laps[current].speed_fl.get_or_insert(speed);
+                    }
+                    _ => state.speeds[i] = Some(speed),
+                }
+            }
+
+            if let Some(nb_laps) = line.number_of_laps
+                && nb_laps > state.laps
+            {
+                state.laps = nb_laps;
+
+                let [sector_1, sector_2, sector_3] = std::mem::take(&mut state.sectors);
+                let [speed_i1, speed_i2, speed_fl, speed_st] = std::mem::take(&mut state.speeds);
+                laps.push(Lap {
+                    driver_number,
+                    lap_number: nb_laps,
+                    time: entry.time,
+                    lap_time: None,
+                    sector_1,
+                    sector_2,
+                    sector_3,
+                    speed_i1,
+                    speed_i2,
+                    speed_fl,
+                    speed_st,
+                });
+                state.current = Some(laps.len() - 1);
+            }
+
+            let lap_time = line
+                .last_lap_time
+                .as_ref()
+                .and_then(|t| t.value.as_deref())
+                .and_then(livetiming::parse_lap_time);
+
+            if let (Some(lap_time), Some(i)) = (lap_time, state.current) {
+                laps[i].lap_time.get_or_insert(lap_time);
+            }
+        }
+    }
+
+    laps
+}
+
+// Start and end of a lap, as offsets from the start of the session stream
+pub struct LapWindow {
+    pub lap_number: i32,
+    pub start: i32,
+    pub end: i32,
+}
+
+// A lap starts at the previous line crossing, the first one from its lap time when known
+pub fn build_lap_windows(laps: &[Lap]) -> HashMap<i32, Vec<LapWindow>> {
+    let mut windows: HashMap<i32, Vec<LapWindow>> = HashMap::new();
+    let mut previous: HashMap<i32, i32> = HashMap::new();
+
+    for lap in laps {
+        let start = match previous.insert(lap.driver_number, lap.time) {
+            Some(start) => Some(start),
+            None => lap.lap_time.map(|t| lap.time - t),
+        };
+
+        if let Some(start) = start {
+            windows
+                .entry(lap.driver_number)
+                .or_default()
+                .push(LapWindow {
+                    lap_number: lap.lap_number,
+                    start,
+                    end: lap.time,
+                });
+        }
+    }
+
+    windows
+}
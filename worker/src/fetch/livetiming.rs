@@ -0,0 +1,185 @@
+use std::io::Read;
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use flate2::read::DeflateDecoder;
+use prost_types::Timestamp;
+use serde::de::DeserializeOwned;
+use tracing::warn;
+
+use crate::fetch::source::SOURCE;
+
+/* //////////////////////// */
+/* //// Livetiming API //// */
+/* //////////////////////// */
+
+// Entry of a '.jsonStream' file, 'time' is the offset in milliseconds from the start of the stream
+pub struct StreamEntry<T> {
+    pub time: i32,
+    pub data: T,
+}
+
+// Fetch a file from the configured Livetiming source, 'path' is relative to its root
+pub async fn get(path: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let text = SOURCE.get(path).await?.content;
+
+    // Livetiming files start with a BOM that needs to be removed before parsing
+    Ok(text.trim_start_matches('\u{feff}').trim().to_string())
+}
+
+pub async fn get_json<T>(path: &str) -> Result<T, Box<dyn std::error::Error>>
+where
+    T: DeserializeOwned,
+{
+    Ok(serde_json::from_str(&get(path).await?)?)
+}
+
+// Parsed file along with whether it changed since the last time this worker fetched it
+// Only the fetching side knows what it did with the previous content, so it's the one to skip it
+pub async fn get_json_modified<T>(path: &str) -> Result<(T, bool), Box<dyn std::error::Error>>
+where
+    T: DeserializeOwned,
+{
+    let fetched = SOURCE.get(path).await?;
+
+    let text = fetched.content.trim_start_matches('\u{feff}').trim();
+    Ok((serde_json::from_str(text)?, fetched.modified))
+}
+
+pub async fn get_stream<T>(path: &str) -> Result<Vec<StreamEntry<T>>, Box<dyn std::error::Error>>
+where
+    T: DeserializeOwned,
+{
+    let text = get(path).await?;
+    Ok(parse_stream(&text))
+}
+
+// Compressed streams ('.z.jsonStream') hold base64 encoded deflated JSON
+pub async fn get_compressed_stream<T>(
+    path: &str,
+) -> Result<Vec<StreamEntry<T>>, Box<dyn std::error::Error>>
+where
+    T: DeserializeOwned,
+{
+    let text = get(path).await?;
+    Ok(parse_compressed_stream(&text))
+}
+
+// Each line of a stream is formatted as 'hh:mm:ss.mmm{...}'
+// Lines that can't be parsed are skipped so a single bad line doesn't discard the whole feed
+pub fn parse_stream<T>(text: &str) -> Vec<StreamEntry<T>>
+where
+    T: DeserializeOwned,
+{
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim_start_matches('\u{feff}').trim();
+            if line.is_empty() {
+                return None;
+            }
+
+            let Some(split) = line.find('{') else {
+                warn!("Invalid stream line, skipping: {}", line);
+                return None;
+            };
+
+            let (time, data) = line.split_at(split);
+
+            let Some(time) = parse_stream_time(time) else {
+                warn!("Invalid stream timestamp, skipping: {}", time);
+                return None;
+            };
+
+            match serde_json::from_str(data) {
+                Ok(data) => Some(StreamEntry { time, data }),
+                Err(err) => {
+                    warn!(error = ?err, "Failed to parse stream line, skipping");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+// Each line of a compressed stream is formatted as 'hh:mm:ss.mmm"..."'
+pub fn parse_compressed_stream<T>(text: &str) -> Vec<StreamEntry<T>>
+where
+    T: DeserializeOwned,
+{
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim_start_matches('\u{feff}').trim();
+            if line.is_empty() {
+                return None;
+            }
+
+            let Some(split) = line.find('"') else {
+                warn!("Invalid stream line, skipping: {}", line);
+                return None;
+            };
+
+            let (time, data) = line.split_at(split);
+
+            let Some(time) = parse_stream_time(time) else {
+                warn!("Invalid stream timestamp, skipping: {}", time);
+                return None;
+            };
+
+            match decompress(data.trim_matches('"')) {
+                Ok(data) => Some(StreamEntry { time, data }),
+                Err(err) => {
+                    warn!(error = ?err, "Failed to parse compressed stream line, skipping");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+pub fn decompress<T>(data: &str) -> Result<T, Box<dyn std::error::Error>>
+where
+    T: DeserializeOwned,
+{
+    let bytes = STANDARD.decode(data)?;
+
+    let mut json = String::new();
+    DeflateDecoder::new(bytes.as_slice()).read_to_string(&mut json)?;
+
+    Ok(serde_json::from_str(&json)?)
+}
+
+// Convert a 'hh:mm:ss.mmm' timestamp into milliseconds
+fn parse_stream_time(s: &str) -> Option<i32> {
+    let split: Vec<&str> = s.split(':').collect();
+    if split.len() != 3 {
+        return None;
+    }
+
+    let hours: i32 = split[0].parse().ok()?;
+    let minutes: i32 = split[1].parse().ok()?;
+    let seconds: f64 = split[2].parse().ok()?;
+
+    Some((hours * 3600 + minutes * 60) * 1000 + (seconds * 1000.0).round() as i32)
+}
+
+// Convert a duration in seconds as sent by Livetiming (e.g. '22.456') into milliseconds
+pub fn parse_seconds(s: &str) -> Option<i32> {
+    let seconds: f64 = s.trim().parse().ok()?;
+    Some((seconds * 1000.0).round() as i32)
+}
+
+// Convert a lap time as sent by Livetiming (e.g. '1:32.456') into milliseconds
+pub fn parse_lap_time(s: &str) -> Option<i32> {
+    match s.trim().split_once(':') {
+        Some((minutes, seconds)) => {
+            let minutes: i32 = minutes.parse().ok()?;
+            Some(minutes * 60_000 + parse_seconds(seconds)?)
+        }
+        None => parse_seconds(s),
+    }
+}
+
+// Convert a timestamp into milliseconds since epoch
+pub fn to_millis(ts: &Option<Timestamp>) -> Option<i64> {
+    let ts = ts.as_ref()?;
+    Some(ts.seconds * 1000 + (ts.nanos / 1_000_000) as i64)
+}
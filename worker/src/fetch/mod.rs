@@ -1 +1,20 @@
+pub mod client;
+pub mod jobs;
+pub mod laps;
+pub mod livetiming;
 pub mod meetings;
+pub mod pit_stops;
+pub mod positions;
+pub mod race_control;
+pub mod rate_limit;
+pub mod results;
+pub mod sessions;
+pub mod source;
+pub mod team_radio;
+pub mod telemetry;
+pub mod track_map;
+pub mod track_status;
+pub mod weather;
+
+#[cfg(test)]
+mod tests;
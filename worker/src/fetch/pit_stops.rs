@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+
+use metrics_one_grpc::proto::{
+    InsertPitStopsRequest, insert_pit_stops_request::PitStop,
+    insert_service_client::InsertServiceClient,
+};
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+use tracing::{debug, info, instrument, trace};
+
+use crate::{
+    fetch::livetiming::{self, StreamEntry},
+    models::{PitLaneTime, PitLaneTimeCollection, PitStopSeries, TimingData},
+};
+
+#[instrument(name = "[Feed] Pit Stops", skip_all, err)]
+pub async fn fetch_feed<F>(
+    api_client: &mut InsertServiceClient<InterceptedService<Channel, F>>,
+    params: &metrics_one_queue::models::Session,
+    timing: &[StreamEntry<TimingData>],
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: tonic::service::Interceptor,
+{
+    let time = std::time::Instant::now();
+
+    // Pit lane entries and exits are inferred from timing data
+    let mut pit_stops = infer_pit_stops(timing);
+
+    // Pit lane durations are missing from older sessions
+    match livetiming::get_stream::<PitLaneTimeCollection>(&format!(
+        "{}PitLaneTimeCollection.jsonStream",
+        params.path
+    ))
+    .await
+    {
+        Ok(lane_times) => merge_lane_times(&mut pit_stops, lane_times),
+        Err(err) => debug!(error = ?err, "Pit lane times not available"),
+    }
+    trace!("Pit lane times fetched in {:?}", time.elapsed());
+
+    // Stationary times are only available from 2024
+    match livetiming::get_stream::<PitStopSeries>(&format!(
+        "{}PitStopSeries.jsonStream",
+        params.path
+    ))
+    .await
+    {
+        Ok(series) => merge_stop_series(&mut pit_stops, series),
+        Err(err) => debug!(error = ?err, "Pit stop series not available"),
+    }
+    trace!("Pit stop series fetched in {:?}", time.elapsed());
+
+    pit_stops.sort_by_key(|p| (p.lap, p.driver_number));
+
+    let nb_pit_stops = pit_stops.len();
+    if nb_pit_stops == 0 {
+        info!("No pit stop found");
+        return Ok(());
+    }
+
+    //Send request for processing to API
+    trace!("Send {} pit stops to API for insertion", nb_pit_stops);
+    api_client
+        .insert_pit_stops(InsertPitStopsRequest {
+            session_key: params.key,
+            pit_stops,
+        })
+        .await?;
+
+    info!(
+        "{} pit stops fetched and processed by API service sucessfully in {:?}",
+        nb_pit_stops,
+        time.elapsed(),
+    );
+
+    Ok(())
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+#[derive(Default)]
+struct DriverPitState {
+    laps: i32,
+    in_pit: bool,
+    current: Option<usize>,
+}
+
+// Build pit stops from 'InPit' and 'PitOut' flags of timing data
+// The lap of the stop is the one being driven when entering the pit lane
+fn infer_pit_stops(timing: &[StreamEntry<TimingData>]) -> Vec<PitStop> {
+    let mut pit_stops: Vec<PitStop> = Vec::new();
+    let mut states: HashMap<i32, DriverPitState> = HashMap::new();
+
+    for entry in timing {
+        for (number, line) in entry.data.lines.iter() {
+            let Ok(driver_number) = number.parse::<i32>() else {
+                continue;
+            };
+            let state = states.entry(driver_number).or_default();
+
+            if let Some(laps) = line.number_of_laps {
+                state.laps = laps;
+            }
+
+            match line.in_pit {
+                Some(true) if !state.in_pit => {
+                    state.in_pit = true;
+
+                    // Cars leaving the pit lane to start the session are not pit stops
+                    if state.laps > 0 {
+                        pit_stops.push(PitStop {
+                            driver_number,
+                            lap: state.laps + 1,
+                            in_time: Some(entry.time),
+                            ..Default::default()
+                        });
+                        state.current = Some(pit_stops.len() - 1);
+                    }
+                }
+                Some(false) => state.in_pit = false,
+                _ => (),
+            }
+
+            if let Some(true) = line.pit_out {
+                state.in_pit = false;
+
+                if let Some(i) = state.current.take() {
+                    pit_stops[i].out_time = Some(entry.time);
+                }
+            }
+        }
+    }
+
+    pit_stops
+}
+
+// Find the pit stop of a driver on a lap, allowing one lap of difference
+// as the lap reported by Livetiming can be either the in or the out lap
+fn find_pit_stop(pit_stops: &mut [PitStop], driver_number: i32, lap: i32) -> Option<&mut PitStop> {
+    let index = pit_stops
+        .iter()
+        .position(|p| p.driver_number == driver_number && p.lap == lap)
+        .or_else(|| {
+            pit_stops
+                .iter()
+                .position(|p| p.driver_number == driver_number && (p.lap - lap).abs() <= 1)
+        })?;
+
+    pit_stops.get_mut(index)
+}
+
+fn merge_lane_times(
+    pit_stops: &mut Vec<PitStop>,
+    lane_times: Vec<StreamEntry<PitLaneTimeCollection>>,
+) {
+    for entry in lane_times {
+        for t in entry.data.pit_times.into_values() {
+            let Ok(t) = serde_json::from_value::<PitLaneTime>(t) else {
+                continue;
+            };
+
+            let (Ok(driver_number), Ok(lap)) = (t.racing_number.parse(), t.lap.parse()) else {
+                continue;
+            };
+            let lane_duration = livetiming::parse_seconds(&t.duration);
+
+            match find_pit_stop(pit_stops, driver_number, lap) {
+                Some(p) => p.lane_duration = lane_duration.or(p.lane_duration),
+                None => pit_stops.push(PitStop {
+                    driver_number,
+                    lap,
+                    lane_duration,
+                    ..Default::default()
+                }),
+            }
+        }
+    }
+}
+
+fn merge_stop_series(pit_stops: &mut Vec<PitStop>, series: Vec<StreamEntry<PitStopSeries>>) {
+    for entry in series {
+        for s in entry.data.entries() {
+            let (Ok(driver_number), Ok(lap)) = (s.racing_number.parse(), s.lap.parse()) else {
+                continue;
+            };
+            let stationary_duration = livetiming::parse_seconds(&s.pit_stop_time);
+            let lane_duration = livetiming::parse_seconds(&s.pit_lane_time);
+
+            match find_pit_stop(pit_stops, driver_number, lap) {
+                Some(p) => {
+                    p.stationary_duration = stationary_duration.or(p.stationary_duration);
+                    p.lane_duration = p.lane_duration.or(lane_duration);
+                }
+                None => pit_stops.push(PitStop {
+                    driver_number,
+                    lap,
+                    lane_duration,
+                    stationary_duration,
+                    ..Default::default()
+                }),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::{infer_pit_stops, merge_lane_times, merge_stop_series};
+    use crate::fetch::livetiming::StreamEntry;
+
+    fn entries<T: serde::de::DeserializeOwned>(lines: Vec<(i32, Value)>) -> Vec<StreamEntry<T>> {
+        lines
+            .into_iter()
+            .map(|(time, data)| StreamEntry {
+                time,
+                data: serde_json::from_value(data).unwrap(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pit_stops_are_inferred_from_flags() {
+        let timing = entries(vec![
+            // Leaving the pit lane to start the session
+            (
+                0,
+                json!({ "Lines": { "1": { "InPit": true, "NumberOfLaps": 0 } } }),
+            ),
+            (
+                1_000,
+                json!({ "Lines": { "1": { "InPit": false, "PitOut": true } } }),
+            ),
+            (600_000, json!({ "Lines": { "1": { "NumberOfLaps": 10 } } })),
+            (650_000, json!({ "Lines": { "1": { "InPit": true } } })),
+            // Flag sent again while in the pit lane
+            (660_000, json!({ "Lines": { "1": { "InPit": true } } })),
+            (
+                672_500,
+                json!({ "Lines": { "1": { "InPit": false, "PitOut": true } } }),
+            ),
+            (
+                680_000,
+                json!({ "Lines": { "44": { "NumberOfLaps": 12, "InPit": true } } }),
+            ),
+        ]);
+
+        let pit_stops: Vec<_> = infer_pit_stops(&timing)
+            .iter()
+            .map(|p| (p.driver_number, p.lap, p.in_time, p.out_time))
+            .collect();
+
+        // The lap of the stop is the one being driven, a car still in the pit lane has no exit
+        assert_eq!(
+            pit_stops,
+            vec![
+                (1, 11, Some(650_000), Some(672_500)),
+                (44, 13, Some(680_000), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn durations_are_merged_on_adjacent_laps() {
+        let timing = entries(vec![
+            (0, json!({ "Lines": { "1": { "NumberOfLaps": 10 } } })),
+            (650_000, json!({ "Lines": { "1": { "InPit": true } } })),
+        ]);
+        let mut pit_stops = infer_pit_stops(&timing);
+
+        // Reported on the out lap, one after the inferred one
+        merge_lane_times(
+            &mut pit_stops,
+            entries(vec![(
+                672_500,
+                json!({ "PitTimes": {
+                    "1": { "RacingNumber": "1", "Duration": "22.5", "Lap": "12" },
+                    "_deleted": ["44"]
+                } }),
+            )]),
+        );
+        merge_stop_series(
+            &mut pit_stops,
+            entries(vec![(
+                680_000,
+                json!({ "PitTimes": {
+                    "1": [{ "PitStop": {
+                        "RacingNumber": "1", "PitStopTime": "2.4", "PitLaneTime": "22.6", "Lap": "11"
+                    } }],
+                    "44": { "0": { "PitStop": {
+                        "RacingNumber": "44", "PitStopTime": "3.1", "PitLaneTime": "23.0", "Lap": "20"
+                    } } }
+                } }),
+            )]),
+        );
+
+        let pit_stops: Vec<_> = pit_stops
+            .iter()
+            .map(|p| {
+                let durations = (p.lane_duration, p.stationary_duration);
+                (p.driver_number, p.lap, p.in_time, durations)
+            })
+            .collect();
+
+        // Lane duration of the collection is kept over the one of the series
+        assert_eq!(
+            pit_stops,
+            vec![
+                (1, 11, Some(650_000), (Some(22_500), Some(2_400))),
+                (44, 20, None, (Some(23_000), Some(3_100))),
+            ]
+        );
+    }
+}
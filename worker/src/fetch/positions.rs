@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+
+use metrics_one_grpc::proto::{InsertPositionsRequest, insert_service_client::InsertServiceClient};
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+use tracing::{info, instrument, trace};
+
+use crate::{
+    fetch::livetiming::{self, StreamEntry},
+    models::PositionData,
+};
+
+#[instrument(name = "[Feed] Positions", skip_all, err)]
+pub async fn fetch_feed<F>(
+    api_client: &mut InsertServiceClient<InterceptedService<Channel, F>>,
+    params: &metrics_one_queue::models::Session,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: tonic::service::Interceptor,
+{
+    let time = std::time::Instant::now();
+
+    let positions = livetiming::get_compressed_stream::<PositionData>(&format!(
+        "{}Position.z.jsonStream",
+        params.path
+    ))
+    .await?;
+    trace!("Position data fetched in {:?}", time.elapsed());
+
+    let positions = build_positions(positions);
+    trace!("Data processed in {:?}", time.elapsed());
+
+    if positions.is_empty() {
+        info!("No position data found");
+        return Ok(());
+    }
+
+    // Positions are sent per driver to keep requests under the gRPC message size limit
+    let mut nb_positions = 0;
+    for (driver_number, request) in positions {
+        nb_positions += request.time.len();
+
+        //Send request for processing to API
+        trace!(
+            "Send {} positions of driver {} to API for insertion",
+            request.time.len(),
+            driver_number
+        );
+        api_client
+            .insert_positions(InsertPositionsRequest {
+                session_key: params.key,
+                ..request
+            })
+            .await?;
+    }
+
+    info!(
+        "{} positions fetched and processed by API service sucessfully in {:?}",
+        nb_positions,
+        time.elapsed(),
+    );
+
+    Ok(())
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+// Entries are sent in batches, the time of each one is given relatively to the last one of its batch
+fn build_positions(
+    positions: Vec<StreamEntry<PositionData>>,
+) -> BTreeMap<i32, InsertPositionsRequest> {
+    let mut samples: BTreeMap<i32, Vec<(i32, i32, i32, i32)>> = BTreeMap::new();
+
+    for batch in positions {
+        let Some(last) = batch
+            .data
+            .position
+            .last()
+            .and_then(|p| livetiming::to_millis(&p.timestamp))
+        else {
+            continue;
+        };
+
+        for entry in batch.data.position.iter() {
+            let Some(utc) = livetiming::to_millis(&entry.timestamp) else {
+                continue;
+            };
+            let time = batch.time - (last - utc) as i32;
+
+            for (number, car) in entry.entries.iter() {
+                let Ok(driver_number) = number.parse::<i32>() else {
+                    continue;
+                };
+
+                samples.entry(driver_number).or_default().push((
+                    time,
+                    car.x as i32,
+                    car.y as i32,
+                    car.z as i32,
+                ));
+            }
+        }
+    }
+
+    samples
+        .into_iter()
+        .map(|(driver_number, mut samples)| {
+            samples.sort_by_key(|s| s.0);
+            samples.dedup_by_key(|s| s.0);
+
+            let mut request = InsertPositionsRequest {
+                driver_number,
+                ..Default::default()
+            };
+            for (time, x, y, z) in samples {
+                request.time.push(time);
+                request.x.push(x);
+                request.y.push(y);
+                request.z.push(z);
+            }
+
+            (driver_number, request)
+        })
+        .collect()
+}
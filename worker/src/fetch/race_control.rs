@@ -0,0 +1,89 @@
+use metrics_one_grpc::proto::{
+    InsertRaceControlRequest, insert_race_control_request::Message,
+    insert_service_client::InsertServiceClient,
+};
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+use tracing::{info, instrument, trace};
+
+use crate::{
+    fetch::livetiming::{self, StreamEntry},
+    models::RaceControlMessages,
+};
+
+#[instrument(name = "[Feed] Race Control", skip_all, err)]
+pub async fn fetch_feed<F>(
+    api_client: &mut InsertServiceClient<InterceptedService<Channel, F>>,
+    params: &metrics_one_queue::models::Session,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: tonic::service::Interceptor,
+{
+    let time = std::time::Instant::now();
+
+    // The stream gives the time of each message from the start of the session
+    let race_control = livetiming::get_stream::<RaceControlMessages>(&format!(
+        "{}RaceControlMessages.jsonStream",
+        params.path
+    ))
+    .await?;
+    trace!("Race control messages fetched in {:?}", time.elapsed());
+
+    let messages = build_messages(race_control);
+    trace!("Data processed in {:?}", time.elapsed());
+
+    let nb_messages = messages.len();
+    if nb_messages == 0 {
+        info!("No race control message found");
+        return Ok(());
+    }
+
+    //Send request for processing to API
+    trace!("Send {} messages to API for insertion", nb_messages);
+    api_client
+        .insert_race_control(InsertRaceControlRequest {
+            session_key: params.key,
+            messages,
+        })
+        .await?;
+
+    info!(
+        "{} race control messages fetched and processed by API service sucessfully in {:?}",
+        nb_messages,
+        time.elapsed(),
+    );
+
+    Ok(())
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+pub fn build_messages(race_control: Vec<StreamEntry<RaceControlMessages>>) -> Vec<Message> {
+    race_control
+        .into_iter()
+        .flat_map(|entry| {
+            let time = entry.time;
+            entry
+                .data
+                .messages
+                .into_values()
+                .into_iter()
+                .map(move |m| (time, m))
+        })
+        .map(|(time, m)| Message {
+            driver_number: m.driver_number(),
+            deleted_lap: m.deleted_lap(),
+            time,
+            date: m.utc,
+            lap: m.lap,
+            category: m.category,
+            flag: m.flag,
+            scope: m.scope,
+            sector: m.sector,
+            status: m.status,
+            mode: m.mode,
+            message: m.message,
+        })
+        .collect()
+}
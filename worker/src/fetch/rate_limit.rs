@@ -0,0 +1,129 @@
+use std::time::Duration;
+
+use tokio::{sync::Mutex, time::Instant};
+
+/* ////////////////////// */
+/* //// Token Bucket //// */
+/* ////////////////////// */
+
+// Requests take a token each, tokens being refilled at 'rate' per second up to 'burst'
+// The bucket can also be paused, when the server asks to slow down
+// 'rate' must be positive and 'burst' at least 1, as checked when settings are loaded
+pub struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    tokens: f64,
+    refilled_at: Instant,
+    paused_until: Option<Instant>,
+}
+
+impl TokenBucket {
+    pub fn new(rate: f64, burst: u32) -> Self {
+        let burst = f64::from(burst);
+
+        TokenBucket {
+            rate,
+            burst,
+            state: Mutex::new(BucketState {
+                tokens: burst,
+                refilled_at: Instant::now(),
+                paused_until: None,
+            }),
+        }
+    }
+
+    // Wait for a token, returns 'true' if the request had to wait for it
+    pub async fn acquire(&self) -> bool {
+        let mut throttled = false;
+
+        loop {
+            let wait = {
+                let mut state = self.state.lock().await;
+                let now = Instant::now();
+
+                match state.paused_until {
+                    Some(until) if until > now => until - now,
+                    _ => {
+                        let elapsed = now - state.refilled_at;
+                        state.tokens =
+                            (state.tokens + elapsed.as_secs_f64() * self.rate).min(self.burst);
+                        state.refilled_at = now;
+
+                        if state.tokens >= 1.0 {
+                            state.tokens -= 1.0;
+                            return throttled;
+                        }
+
+                        Duration::from_secs_f64((1.0 - state.tokens) / self.rate)
+                    }
+                }
+            };
+
+            throttled = true;
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    // No token is given until the delay is over
+    pub async fn pause(&self, delay: Duration) {
+        let mut state = self.state.lock().await;
+        let until = Instant::now() + delay;
+
+        if state.paused_until.is_none_or(|u| u < until) {
+            state.paused_until = Some(until);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::time::Instant;
+
+    use super::TokenBucket;
+
+    // Time is paused, so waiting for a token advances the clock right away
+    #[tokio::test(start_paused = true)]
+    async fn burst_runs_out() {
+        let bucket = TokenBucket::new(10.0, 2);
+        let start = Instant::now();
+
+        assert!(!bucket.acquire().await);
+        assert!(!bucket.acquire().await);
+
+        // A token is refilled every 100ms once the burst is spent
+        assert!(bucket.acquire().await);
+        assert_eq!(start.elapsed(), Duration::from_millis(100));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn tokens_refill_up_to_burst() {
+        let bucket = TokenBucket::new(10.0, 2);
+        assert!(!bucket.acquire().await);
+        assert!(!bucket.acquire().await);
+
+        tokio::time::sleep(Duration::from_secs(10)).await;
+
+        assert!(!bucket.acquire().await);
+        assert!(!bucket.acquire().await);
+        assert!(bucket.acquire().await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn pause_delays_every_request() {
+        let bucket = TokenBucket::new(10.0, 2);
+        let start = Instant::now();
+
+        bucket.pause(Duration::from_secs(2)).await;
+        // A shorter pause doesn't end the current one
+        bucket.pause(Duration::from_secs(1)).await;
+
+        assert!(bucket.acquire().await);
+        assert_eq!(start.elapsed(), Duration::from_secs(2));
+    }
+}
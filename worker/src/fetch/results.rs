@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+
+use metrics_one_grpc::proto::{
+    InsertSessionResultsRequest, insert_service_client::InsertServiceClient,
+    insert_session_results_request::DriverResult,
+};
+use metrics_one_livetiming::models::DriverList;
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+use tracing::{info, instrument, trace};
+
+use crate::{
+    fetch::livetiming::{self, StreamEntry},
+    models::TimingData,
+};
+
+#[instrument(name = "[Feed] Results", skip_all, err)]
+pub async fn fetch_feed<F>(
+    api_client: &mut InsertServiceClient<InterceptedService<Channel, F>>,
+    params: &metrics_one_queue::models::Session,
+    timing: &[StreamEntry<TimingData>],
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: tonic::service::Interceptor,
+{
+    let time = std::time::Instant::now();
+
+    // Teams are given by the driver list, drivers possibly changing team during the season
+    let drivers =
+        livetiming::get_json::<DriverList>(&format!("{}DriverList.json", params.path)).await?;
+    trace!("Driver list fetched in {:?}", time.elapsed());
+
+    let results = build_results(timing, &drivers);
+    trace!("Data processed in {:?}", time.elapsed());
+
+    let nb_results = results.len();
+    if nb_results == 0 {
+        info!("No result found");
+        return Ok(());
+    }
+
+    //Send request for processing to API
+    trace!("Send {} results to API for insertion", nb_results);
+    api_client
+        .insert_session_results(InsertSessionResultsRequest {
+            session_key: params.key,
+            results,
+        })
+        .await?;
+
+    info!(
+        "{} results fetched and processed by API service sucessfully in {:?}",
+        nb_results,
+        time.elapsed(),
+    );
+
+    Ok(())
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+// The classification is the last state of timing data once the session is over
+fn build_results(timing: &[StreamEntry<TimingData>], drivers: &DriverList) -> Vec<DriverResult> {
+    let mut results: BTreeMap<i32, DriverResult> = BTreeMap::new();
+
+    for entry in timing {
+        for (number, line) in entry.data.lines.iter() {
+            let Ok(driver_number) = number.parse::<i32>() else {
+                continue;
+            };
+            let result = results.entry(driver_number).or_insert(DriverResult {
+                driver_number,
+                team_name: drivers
+                    .drivers
+                    .get(number)
+                    .and_then(|d| d.team_name.clone()),
+                ..Default::default()
+            });
+
+            if let Some(position) = &line.position {
+                result.position = position.parse().ok();
+            }
+
+            if let Some(retired) = line.retired {
+                result.retired = retired;
+            }
+        }
+    }
+
+    results.into_values().collect()
+}
@@ -0,0 +1,92 @@
+use metrics_one_grpc::proto::insert_service_client::InsertServiceClient;
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+use tracing::{debug, info, instrument, trace};
+
+use crate::{
+    fetch::{
+        jobs, laps, livetiming, pit_stops, positions, race_control, results, team_radio, telemetry,
+        track_status, weather,
+    },
+    models::TimingData,
+};
+
+// Feeds built from the timing data
+const TIMING_FEEDS: [&str; 5] = ["laps", "pit_stops", "results", "track_status", "telemetry"];
+
+#[instrument(name = "[Job] Fetch Session", skip_all, fields(session = params.key), err)]
+pub async fn fetch_job<F>(
+    mut api_client: InsertServiceClient<InterceptedService<Channel, F>>,
+    params: metrics_one_queue::models::Session,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: tonic::service::Interceptor,
+{
+    let result = fetch_session(&mut api_client, &params).await;
+    jobs::report(&mut api_client, params.job, &result).await;
+
+    result
+}
+
+async fn fetch_session<F>(
+    api_client: &mut InsertServiceClient<InterceptedService<Channel, F>>,
+    params: &metrics_one_queue::models::Session,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: tonic::service::Interceptor,
+{
+    debug!("Fetch Session process initiated");
+    let time = std::time::Instant::now();
+
+    // Timing data is used by several feeds, so it's only fetched once
+    let timing = if TIMING_FEEDS.iter().any(|feed| params.has_feed(feed)) {
+        livetiming::get_stream::<TimingData>(&format!("{}TimingData.jsonStream", params.path))
+            .await?
+    } else {
+        Vec::new()
+    };
+    trace!("Timing data fetched in {:?}", time.elapsed());
+
+    // Each feed is fetched from Livetiming and sent to API service for insertion
+    // A failing feed doesn't prevent the others from being processed
+    let mut results = Vec::new();
+    if params.has_feed("laps") {
+        results.push(laps::fetch_feed(api_client, params, &timing).await);
+    }
+    if params.has_feed("pit_stops") {
+        results.push(pit_stops::fetch_feed(api_client, params, &timing).await);
+    }
+    if params.has_feed("race_control") {
+        results.push(race_control::fetch_feed(api_client, params).await);
+    }
+    if params.has_feed("results") {
+        results.push(results::fetch_feed(api_client, params, &timing).await);
+    }
+    if params.has_feed("track_status") {
+        results.push(track_status::fetch_feed(api_client, params, &timing).await);
+    }
+    if params.has_feed("weather") {
+        results.push(weather::fetch_feed(api_client, params).await);
+    }
+    if params.has_feed("team_radio") {
+        results.push(team_radio::fetch_feed(api_client, params).await);
+    }
+    if params.has_feed("telemetry") {
+        results.push(telemetry::fetch_feed(api_client, params, &timing).await);
+    }
+    if params.has_feed("positions") {
+        results.push(positions::fetch_feed(api_client, params).await);
+    }
+
+    let nb_failed = results.iter().filter(|r| r.is_err()).count();
+    if nb_failed > 0 {
+        return Err(format!("{} feeds failed to be processed", nb_failed).into());
+    }
+
+    info!(
+        "Session {} fetched and processed by API service sucessfully in {:?}",
+        params.key,
+        time.elapsed(),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use std::{
+    future::Future,
+    path::{Component, Path, PathBuf},
+    pin::Pin,
+};
+
+use once_cell::sync::Lazy;
+use tracing::{debug, info};
+
+use crate::{
+    fetch::client::{Fetched, LivetimingClient},
+    settings::ENV,
+};
+
+pub type SourceResult<'a> =
+    Pin<Box<dyn Future<Output = Result<Fetched, Box<dyn std::error::Error>>> + Send + 'a>>;
+
+// Where Livetiming files are read from, 'path' being relative to the root of the API
+// (e.g. '2024/Index.json' or a session path followed by the feed file name)
+pub trait LivetimingSource: Send + Sync {
+    fn get<'a>(&'a self, path: &'a str) -> SourceResult<'a>;
+}
+
+// Files are read from a local directory when one is configured, from Livetiming API otherwise
+pub static SOURCE: Lazy<Box<dyn LivetimingSource>> =
+    Lazy::new(
+        || match ENV.livetiming_dir.as_deref().filter(|d| !d.is_empty()) {
+            Some(directory) => {
+                info!("Livetiming files are read from {}", directory);
+                Box::new(DirectorySource::new(directory))
+            }
+            // Use of 'expect' here as the worker can't fetch anything without its client
+            None => Box::new(
+                HttpSource::new(&ENV.livetiming_url).expect("Failed to build Livetiming client"),
+            ),
+        },
+    );
+
+/* ///////////////////// */
+/* //// HTTP Source //// */
+/* ///////////////////// */
+
+pub struct HttpSource {
+    base_url: String,
+    client: LivetimingClient,
+}
+
+impl HttpSource {
+    pub fn new(base_url: &str) -> Result<Self, reqwest::Error> {
+        Ok(HttpSource {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client: LivetimingClient::new(&ENV.client)?,
+        })
+    }
+}
+
+impl LivetimingSource for HttpSource {
+    fn get<'a>(&'a self, path: &'a str) -> SourceResult<'a> {
+        Box::pin(async move {
+            let api_url = format!("{}/{}", self.base_url, path);
+
+            debug!("Fetch data from {}", api_url);
+            self.client.get(&api_url).await
+        })
+    }
+}
+
+/* ////////////////////////// */
+/* //// Directory Source //// */
+/* ////////////////////////// */
+
+// Recorded tree of Livetiming files, laid out as the API ('{year}/Index.json', session folders)
+pub struct DirectorySource {
+    root: PathBuf,
+}
+
+impl DirectorySource {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        DirectorySource { root: root.into() }
+    }
+}
+
+impl LivetimingSource for DirectorySource {
+    fn get<'a>(&'a self, path: &'a str) -> SourceResult<'a> {
+        Box::pin(async move {
+            let file = self.root.join(relative_path(path)?);
+
+            // Recorded files don't change, but are read as new so jobs are always processed
+            debug!("Read data from {}", file.display());
+            let content = tokio::fs::read_to_string(&file)
+                .await
+                .map_err(|err| format!("Failed to read {}: {}", file.display(), err))?;
+
+            Ok(Fetched {
+                content,
+                modified: true,
+            })
+        })
+    }
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+// Paths come from Livetiming data, they must not escape the directory they are joined to
+pub fn relative_path(path: &str) -> Result<&Path, Box<dyn std::error::Error>> {
+    let relative = Path::new(path);
+    if !relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return Err(format!("Invalid Livetiming path '{}'", path).into());
+    }
+
+    Ok(relative)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::relative_path;
+
+    #[test]
+    fn relative_path_stays_in_directory() {
+        assert!(relative_path("2024/Index.json").is_ok());
+        assert!(relative_path("2024/2024-03-02_Bahrain_Grand_Prix/2024-03-02_Race/").is_ok());
+
+        for path in [
+            "/etc/passwd",
+            "../Index.json",
+            "2024/../../Index.json",
+            "./Index.json",
+        ] {
+            assert!(relative_path(path).is_err(), "{}", path);
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use metrics_one_grpc::proto::{
+    InsertTeamRadioRequest, insert_service_client::InsertServiceClient,
+    insert_team_radio_request::Capture,
+};
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+use tracing::{info, instrument, trace};
+
+use crate::{fetch::livetiming, models::TeamRadio};
+
+#[instrument(name = "[Feed] Team Radio", skip_all, err)]
+pub async fn fetch_feed<F>(
+    api_client: &mut InsertServiceClient<InterceptedService<Channel, F>>,
+    params: &metrics_one_queue::models::Session,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: tonic::service::Interceptor,
+{
+    let time = std::time::Instant::now();
+
+    let team_radio =
+        livetiming::get_stream::<TeamRadio>(&format!("{}TeamRadio.jsonStream", params.path))
+            .await?;
+    trace!("Team radio fetched in {:?}", time.elapsed());
+
+    let captures: Vec<Capture> = team_radio
+        .into_iter()
+        .flat_map(|entry| entry.data.captures.into_values())
+        .filter_map(|c| {
+            Some(Capture {
+                date: c.utc,
+                driver_number: c.racing_number.parse().ok()?,
+                path: c.path,
+            })
+        })
+        .collect();
+    trace!("Data processed in {:?}", time.elapsed());
+
+    let nb_captures = captures.len();
+    if nb_captures == 0 {
+        info!("No team radio found");
+        return Ok(());
+    }
+
+    //Send request for processing to API
+    trace!(
+        "Send {} team radio captures to API for insertion",
+        nb_captures
+    );
+    api_client
+        .insert_team_radio(InsertTeamRadioRequest {
+            session_key: params.key,
+            captures,
+        })
+        .await?;
+
+    info!(
+        "{} team radio captures fetched and processed by API service sucessfully in {:?}",
+        nb_captures,
+        time.elapsed(),
+    );
+
+    Ok(())
+}
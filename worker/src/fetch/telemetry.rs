@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+
+use metrics_one_grpc::proto::{
+    InsertCarDataRequest, insert_car_data_request::Lap, insert_service_client::InsertServiceClient,
+};
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+use tracing::{info, instrument, trace};
+
+use crate::{
+    fetch::{
+        laps::{self, LapWindow},
+        livetiming::{self, StreamEntry},
+    },
+    models::{CarData, TimingData},
+};
+
+#[instrument(name = "[Feed] Telemetry", skip_all, err)]
+pub async fn fetch_feed<F>(
+    api_client: &mut InsertServiceClient<InterceptedService<Channel, F>>,
+    params: &metrics_one_queue::models::Session,
+    timing: &[StreamEntry<TimingData>],
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: tonic::service::Interceptor,
+{
+    let time = std::time::Instant::now();
+
+    let car_data = livetiming::get_compressed_stream::<CarData>(&format!(
+        "{}CarData.z.jsonStream",
+        params.path
+    ))
+    .await?;
+    trace!("Car data fetched in {:?}", time.elapsed());
+
+    let samples = build_samples(car_data);
+    let windows = laps::build_lap_windows(&laps::build_laps(timing));
+    trace!("Data processed in {:?}", time.elapsed());
+
+    if samples.is_empty() {
+        info!("No car data found");
+        return Ok(());
+    }
+
+    // Laps are sent per driver to keep requests under the gRPC message size limit
+    let mut nb_laps = 0;
+    for (driver_number, samples) in samples.iter() {
+        let laps: Vec<Lap> = windows
+            .get(driver_number)
+            .map(|w| split_laps(samples, w))
+            .unwrap_or_default();
+
+        if laps.is_empty() {
+            continue;
+        }
+        nb_laps += laps.len();
+
+        //Send request for processing to API
+        trace!(
+            "Send {} laps of car data of driver {} to API for insertion",
+            laps.len(),
+            driver_number
+        );
+        api_client
+            .insert_car_data(InsertCarDataRequest {
+                session_key: params.key,
+                driver_number: *driver_number,
+                laps,
+            })
+            .await?;
+    }
+
+    info!(
+        "{} laps of car data fetched and processed by API service sucessfully in {:?}",
+        nb_laps,
+        time.elapsed(),
+    );
+
+    Ok(())
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+// Channels in order: speed, throttle, brake, RPM, gear, DRS
+struct Sample {
+    time: i32,
+    channels: [i32; 6],
+}
+
+// Entries are sent in batches, the time of each one is given relatively to the last one of its batch
+fn build_samples(car_data: Vec<StreamEntry<CarData>>) -> BTreeMap<i32, Vec<Sample>> {
+    let mut samples: BTreeMap<i32, Vec<Sample>> = BTreeMap::new();
+
+    for batch in car_data {
+        let Some(last) = batch
+            .data
+            .entries
+            .last()
+            .and_then(|e| livetiming::to_millis(&e.utc))
+        else {
+            continue;
+        };
+
+        for entry in batch.data.entries.iter() {
+            let Some(utc) = livetiming::to_millis(&entry.utc) else {
+                continue;
+            };
+            let time = batch.time - (last - utc) as i32;
+
+            for (number, car) in entry.cars.iter() {
+                let Ok(driver_number) = number.parse::<i32>() else {
+                    continue;
+                };
+
+                samples.entry(driver_number).or_default().push(Sample {
+                    time,
+                    channels: ["2", "4", "5", "0", "3", "45"].map(|c| car.get(c)),
+                });
+            }
+        }
+    }
+
+    for driver_samples in samples.values_mut() {
+        driver_samples.sort_by_key(|s| s.time);
+    }
+
+    samples
+}
+
+fn split_laps(samples: &[Sample], windows: &[LapWindow]) -> Vec<Lap> {
+    windows
+        .iter()
+        .filter_map(|w| {
+            let from = samples.partition_point(|s| s.time < w.start);
+            let to = samples.partition_point(|s| s.time <= w.end);
+            if from >= to {
+                return None;
+            }
+
+            let mut lap = Lap {
+                lap_number: w.lap_number,
+                ..Default::default()
+            };
+
+            for s in &samples[from..to] {
+                lap.time.push(s.time - w.start);
+                lap.speed.push(s.channels[0]);
+                lap.throttle.push(s.channels[1]);
+                lap.brake.push(s.channels[2]);
+                lap.rpm.push(s.channels[3]);
+                lap.gear.push(s.channels[4]);
+                lap.drs.push(s.channels[5]);
+            }
+
+            Some(lap)
+        })
+        .collect()
+}
@@ -0,0 +1,121 @@
+use metrics_one_queue::models::{Meetings, Session};
+
+use crate::{
+    fetch,
+    testing::{self, Recorder},
+};
+
+// Jobs read the recorded files through 'DirectorySource', as when 'LIVETIMING_DIR' is set
+
+#[tokio::test]
+async fn meetings_job_sends_the_calendar() {
+    testing::init_env();
+
+    let recorder = Recorder::default();
+    let api_client = recorder.serve().await;
+
+    let params = Meetings {
+        keys: vec![],
+        year: 2024,
+        job: None,
+    };
+    fetch::meetings::fetch_job(api_client, params)
+        .await
+        .unwrap();
+
+    let recorded = recorder.recorded();
+    let [request] = recorded.meetings.as_slice() else {
+        panic!(
+            "Expected one meetings request, got {}",
+            recorded.meetings.len()
+        );
+    };
+    assert_eq!(request.year, 2024);
+
+    let [meeting] = request.meetings.as_slice() else {
+        panic!("Expected one meeting, got {}", request.meetings.len());
+    };
+    assert_eq!((meeting.key, meeting.number), (1229, 1));
+    assert_eq!(meeting.name, "Bahrain Grand Prix");
+
+    let [session] = meeting.sessions.as_slice() else {
+        panic!("Expected one session, got {}", meeting.sessions.len());
+    };
+    assert_eq!(session.key, 9472);
+    assert_eq!(session.path, testing::SESSION_PATH);
+
+    // Dates are local to the circuit, 'GmtOffset' being removed to get them in UTC
+    let start_date = session.start_date.as_ref().map(|d| d.seconds);
+    assert_eq!(start_date, Some(1709391600));
+}
+
+#[tokio::test]
+async fn meetings_job_skips_stored_meetings() {
+    testing::init_env();
+
+    let recorder = Recorder::default();
+    let api_client = recorder.serve().await;
+
+    let params = Meetings {
+        keys: vec![1229],
+        year: 2024,
+        job: None,
+    };
+    fetch::meetings::fetch_job(api_client, params)
+        .await
+        .unwrap();
+
+    assert!(recorder.recorded().meetings.is_empty());
+}
+
+#[tokio::test]
+async fn session_job_sends_laps_and_race_control() {
+    testing::init_env();
+
+    let recorder = Recorder::default();
+    let api_client = recorder.serve().await;
+
+    let params = Session {
+        key: 9472,
+        path: testing::SESSION_PATH.to_string(),
+        feeds: Some(vec!["laps".to_string(), "race_control".to_string()]),
+        job: None,
+    };
+    fetch::sessions::fetch_job(api_client, params)
+        .await
+        .unwrap();
+
+    let recorded = recorder.recorded();
+
+    let laps: Vec<_> = recorded
+        .laps
+        .iter()
+        .flat_map(|r| r.laps.iter().map(move |l| (r.session_key, l)))
+        .map(|(session_key, l)| (session_key, l.driver_number, l.lap_number, l.lap_time))
+        .collect();
+    assert_eq!(laps.len(), 4);
+    for lap in [
+        (9472, 1, 1, Some(91500)),
+        (9472, 1, 2, Some(90800)),
+        (9472, 44, 1, Some(92800)),
+        (9472, 44, 2, Some(91900)),
+    ] {
+        assert!(laps.contains(&lap), "Missing lap {:?} in {:?}", lap, laps);
+    }
+
+    // Unlike a live session, the messages sent before the first update are part of the session
+    let messages: Vec<_> = recorded
+        .race_control
+        .iter()
+        .flat_map(|r| r.messages.iter().map(move |m| (r.session_key, m)))
+        .map(|(session_key, m)| (session_key, m.driver_number, m.deleted_lap))
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            (9472, None, None),
+            (9472, Some(44), Some(1)),
+            (9472, None, None)
+        ]
+    );
+}
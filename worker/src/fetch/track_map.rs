@@ -0,0 +1,181 @@
+use metrics_one_grpc::proto::{InsertTrackMapRequest, insert_service_client::InsertServiceClient};
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+use tracing::{debug, info, instrument, trace};
+
+use crate::{
+    fetch::{jobs, laps, livetiming},
+    models::{PositionData, TimingData},
+};
+
+// Minimum number of points to consider an outline as complete
+const MIN_POINTS: usize = 50;
+
+#[instrument(name = "[Job] Fetch Track Map", skip_all, fields(session = params.key), err)]
+pub async fn fetch_job<F>(
+    mut api_client: InsertServiceClient<InterceptedService<Channel, F>>,
+    params: metrics_one_queue::models::Session,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: tonic::service::Interceptor,
+{
+    let result = fetch_track_map(&mut api_client, &params).await;
+    jobs::report(&mut api_client, params.job, &result).await;
+
+    result
+}
+
+async fn fetch_track_map<F>(
+    api_client: &mut InsertServiceClient<InterceptedService<Channel, F>>,
+    params: &metrics_one_queue::models::Session,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: tonic::service::Interceptor,
+{
+    debug!("Fetch Track Map process initiated");
+    let time = std::time::Instant::now();
+
+    // The outline is drawn from the fastest lap of the session, being the cleanest one
+    let timing =
+        livetiming::get_stream::<TimingData>(&format!("{}TimingData.jsonStream", params.path))
+            .await?;
+    let laps = laps::build_laps(&timing);
+    let windows = laps::build_lap_windows(&laps);
+    trace!("Timing data fetched in {:?}", time.elapsed());
+
+    let Some((lap, window)) = laps
+        .iter()
+        .filter(|l| l.lap_time.is_some())
+        .filter_map(|l| {
+            let window = windows
+                .get(&l.driver_number)?
+                .iter()
+                .find(|w| w.lap_number == l.lap_number)?;
+            Some((l, window))
+        })
+        .min_by_key(|(l, _)| l.lap_time)
+    else {
+        return Err("No timed lap found to draw the track map".into());
+    };
+
+    let positions = livetiming::get_compressed_stream::<PositionData>(&format!(
+        "{}Position.z.jsonStream",
+        params.path
+    ))
+    .await?;
+    trace!("Position data fetched in {:?}", time.elapsed());
+
+    // Entries are sent in batches, the time of each one is given relatively to the last one of its batch
+    let driver_number = lap.driver_number.to_string();
+    let mut points: Vec<(i32, f64, f64)> = Vec::new();
+    for batch in positions {
+        let Some(last) = batch
+            .data
+            .position
+            .last()
+            .and_then(|p| livetiming::to_millis(&p.timestamp))
+        else {
+            continue;
+        };
+
+        for entry in batch.data.position.iter() {
+            let Some(utc) = livetiming::to_millis(&entry.timestamp) else {
+                continue;
+            };
+            let time = batch.time - (last - utc) as i32;
+
+            if time < window.start || time > window.end {
+                continue;
+            }
+
+            if let Some(car) = entry.entries.get(&driver_number)
+                && car.status.as_deref() != Some("OffTrack")
+            {
+                points.push((time, car.x, car.y));
+            }
+        }
+    }
+    points.sort_by_key(|p| p.0);
+    points.dedup_by(|a, b| a.1 == b.1 && a.2 == b.2);
+
+    if points.len() < MIN_POINTS {
+        return Err(format!("Not enough position data ({} points)", points.len()).into());
+    }
+
+    // Sectors start at the line crossing and after the first two sector times
+    // Only the timing sectors are known, marshal sectors and corners not being in the feeds
+    let mut sectors = vec![0];
+    let mut boundary = window.start;
+    for sector_time in [lap.sector_1, lap.sector_2].into_iter().flatten() {
+        boundary += sector_time;
+        sectors.push(points.partition_point(|p| p.0 < boundary) as i32);
+    }
+
+    let (rotation, x, y) = normalise(&points);
+    trace!("Data processed in {:?}", time.elapsed());
+
+    //Send request for processing to API
+    trace!("Send track map of {} points to API for insertion", x.len());
+    api_client
+        .insert_track_map(InsertTrackMapRequest {
+            session_key: params.key,
+            rotation,
+            x,
+            y,
+            sectors,
+        })
+        .await?;
+
+    info!(
+        "Track map of session {} fetched and processed by API service sucessfully in {:?}",
+        params.key,
+        time.elapsed(),
+    );
+
+    Ok(())
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+// Rotate the outline so its principal axis is horizontal, then scale it between 0 and 1
+// The aspect ratio is kept, the rotation being returned in degrees
+fn normalise(points: &[(i32, f64, f64)]) -> (f64, Vec<f64>, Vec<f64>) {
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.2).sum::<f64>() / n;
+
+    let (mut sxx, mut syy, mut sxy) = (0.0, 0.0, 0.0);
+    for (_, x, y) in points {
+        sxx += (x - mean_x).powi(2);
+        syy += (y - mean_y).powi(2);
+        sxy += (x - mean_x) * (y - mean_y);
+    }
+    let angle = 0.5 * (2.0 * sxy).atan2(sxx - syy);
+
+    let (sin, cos) = (-angle).sin_cos();
+    let rotated: Vec<(f64, f64)> = points
+        .iter()
+        .map(|(_, x, y)| {
+            let (x, y) = (x - mean_x, y - mean_y);
+            (x * cos - y * sin, x * sin + y * cos)
+        })
+        .collect();
+
+    let min_x = rotated.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+    let min_y = rotated.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+    let max_x = rotated
+        .iter()
+        .map(|p| p.0)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let max_y = rotated
+        .iter()
+        .map(|p| p.1)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let scale = (max_x - min_x).max(max_y - min_y).max(f64::EPSILON);
+
+    let x = rotated.iter().map(|p| (p.0 - min_x) / scale).collect();
+    let y = rotated.iter().map(|p| (p.1 - min_y) / scale).collect();
+
+    (angle.to_degrees(), x, y)
+}
@@ -0,0 +1,122 @@
+use metrics_one_grpc::proto::{
+    InsertTrackStatusRequest, insert_service_client::InsertServiceClient,
+    insert_track_status_request::TrackStatus,
+};
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+use tracing::{info, instrument, trace};
+
+use crate::{
+    fetch::livetiming::{self, StreamEntry},
+    models::{self, TimingData},
+};
+
+#[instrument(name = "[Feed] Track Status", skip_all, err)]
+pub async fn fetch_feed<F>(
+    api_client: &mut InsertServiceClient<InterceptedService<Channel, F>>,
+    params: &metrics_one_queue::models::Session,
+    timing: &[StreamEntry<TimingData>],
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: tonic::service::Interceptor,
+{
+    let time = std::time::Instant::now();
+
+    // The stream is required as the final file only holds the last status
+    let track_status = livetiming::get_stream::<models::TrackStatus>(&format!(
+        "{}TrackStatus.jsonStream",
+        params.path
+    ))
+    .await?;
+    trace!("Track status fetched in {:?}", time.elapsed());
+
+    let statuses = build_intervals(&track_status, timing);
+    trace!("Data processed in {:?}", time.elapsed());
+
+    let nb_statuses = statuses.len();
+    if nb_statuses == 0 {
+        info!("No track status found");
+        return Ok(());
+    }
+
+    //Send request for processing to API
+    trace!("Send {} track statuses to API for insertion", nb_statuses);
+    api_client
+        .insert_track_status(InsertTrackStatusRequest {
+            session_key: params.key,
+            statuses,
+        })
+        .await?;
+
+    info!(
+        "{} track statuses fetched and processed by API service sucessfully in {:?}",
+        nb_statuses,
+        time.elapsed(),
+    );
+
+    Ok(())
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+// Each status lasts until the next one, the last one has no end
+// Laps are the ones driven by the leader at the start and end of the interval
+fn build_intervals(
+    track_status: &[StreamEntry<models::TrackStatus>],
+    timing: &[StreamEntry<TimingData>],
+) -> Vec<TrackStatus> {
+    let mut leader = LeaderLaps::new(timing);
+
+    let starts: Vec<Option<i32>> = track_status.iter().map(|s| leader.lap_at(s.time)).collect();
+
+    track_status
+        .iter()
+        .enumerate()
+        .map(|(i, s)| TrackStatus {
+            status: s.data.status.clone(),
+            message: s.data.message.clone(),
+            start_time: s.time,
+            end_time: track_status.get(i + 1).map(|next| next.time),
+            start_lap: starts[i],
+            end_lap: starts.get(i + 1).copied().flatten(),
+        })
+        .collect()
+}
+
+// Walk through timing data to find the lap driven by the leader at a given time
+// Queries have to be made in chronological order
+struct LeaderLaps<'a> {
+    timing: &'a [StreamEntry<TimingData>],
+    index: usize,
+    laps: i32,
+}
+
+impl<'a> LeaderLaps<'a> {
+    fn new(timing: &'a [StreamEntry<TimingData>]) -> Self {
+        Self {
+            timing,
+            index: 0,
+            laps: 0,
+        }
+    }
+
+    fn lap_at(&mut self, time: i32) -> Option<i32> {
+        while let Some(entry) = self.timing.get(self.index) {
+            if entry.time > time {
+                break;
+            }
+
+            for line in entry.data.lines.values() {
+                if let Some(laps) = line.number_of_laps {
+                    self.laps = self.laps.max(laps);
+                }
+            }
+
+            self.index += 1;
+        }
+
+        // No lap can be given without timing data
+        (!self.timing.is_empty()).then_some(self.laps + 1)
+    }
+}
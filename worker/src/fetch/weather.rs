@@ -0,0 +1,81 @@
+use metrics_one_grpc::proto::{
+    InsertWeatherRequest, insert_service_client::InsertServiceClient,
+    insert_weather_request::Sample,
+};
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+use tracing::{info, instrument, trace, warn};
+
+use crate::{
+    fetch::livetiming::{self, StreamEntry},
+    models::WeatherData,
+};
+
+#[instrument(name = "[Feed] Weather", skip_all, err)]
+pub async fn fetch_feed<F>(
+    api_client: &mut InsertServiceClient<InterceptedService<Channel, F>>,
+    params: &metrics_one_queue::models::Session,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: tonic::service::Interceptor,
+{
+    let time = std::time::Instant::now();
+
+    let weather =
+        livetiming::get_stream::<WeatherData>(&format!("{}WeatherData.jsonStream", params.path))
+            .await?;
+    trace!("Weather data fetched in {:?}", time.elapsed());
+
+    let samples: Vec<Sample> = weather
+        .into_iter()
+        .filter_map(|entry| {
+            let time = entry.time;
+            parse_sample(entry).or_else(|| {
+                warn!("Invalid weather sample at {}ms, skipping", time);
+                None
+            })
+        })
+        .collect();
+    trace!("Data processed in {:?}", time.elapsed());
+
+    let nb_samples = samples.len();
+    if nb_samples == 0 {
+        info!("No weather sample found");
+        return Ok(());
+    }
+
+    //Send request for processing to API
+    trace!("Send {} weather samples to API for insertion", nb_samples);
+    api_client
+        .insert_weather(InsertWeatherRequest {
+            session_key: params.key,
+            samples,
+        })
+        .await?;
+
+    info!(
+        "{} weather samples fetched and processed by API service sucessfully in {:?}",
+        nb_samples,
+        time.elapsed(),
+    );
+
+    Ok(())
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+fn parse_sample(entry: StreamEntry<WeatherData>) -> Option<Sample> {
+    let w = entry.data;
+
+    Some(Sample {
+        time: entry.time,
+        air_temp: w.air_temp.trim().parse().ok()?,
+        track_temp: w.track_temp.trim().parse().ok()?,
+        humidity: w.humidity.trim().parse().ok()?,
+        pressure: w.pressure.trim().parse().ok()?,
+        wind_speed: w.wind_speed.trim().parse().ok()?,
+        wind_direction: w.wind_direction.trim().parse().ok()?,
+        rainfall: w.rainfall.trim() != "0",
+    })
+}
@@ -0,0 +1,186 @@
+pub mod signalr;
+pub mod state;
+
+#[cfg(test)]
+mod tests;
+
+use std::{sync::Arc, time::Duration};
+
+use metrics_one_grpc::proto::{
+    InsertLapsRequest, InsertRaceControlRequest, insert_service_client::InsertServiceClient,
+};
+use metrics_one_queue::models::LiveUpdate;
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+use tracing::{debug, error, info, instrument, trace, warn};
+
+use crate::live::{signalr::HubConnection, state::LiveSession};
+
+// Feeds followed during a live session, compressed ones being suffixed by '.z'
+const FEEDS: [&str; 14] = [
+    "Heartbeat",
+    "SessionInfo",
+    "SessionStatus",
+    "TrackStatus",
+    "TimingData",
+    "TimingAppData",
+    "DriverList",
+    "RaceControlMessages",
+    "ExtrapolatedClock",
+    "LapCount",
+    "WeatherData",
+    "TeamRadio",
+    "CarData.z",
+    "Position.z",
+];
+
+// Feeds pushed to API service as they are received, with the kind of update they are sent as
+const FORWARDED_FEEDS: [(&str, &str); 3] = [
+    ("TimingData", "timing"),
+    ("RaceControlMessages", "race_control"),
+    ("TrackStatus", "track_status"),
+];
+
+// Updates are persisted in batches, to not send a request on each update
+const PERSIST_INTERVAL: Duration = Duration::from_secs(10);
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/* ////////////////////// */
+/* //// Live Service //// */
+/* ////////////////////// */
+
+// Follow the Livetiming hub for as long as the worker runs, reconnecting when it's lost
+pub async fn run<F>(
+    mut api_client: InsertServiceClient<InterceptedService<Channel, F>>,
+    rabbitmq: Arc<lapin::Channel>,
+    hub_url: &str,
+) where
+    F: tonic::service::Interceptor,
+{
+    loop {
+        if let Err(err) = listen(&mut api_client, &rabbitmq, hub_url).await {
+            error!(error = %err, "Live session interrupted");
+        }
+
+        debug!("Reconnect to Livetiming hub in {:?}", RECONNECT_DELAY);
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+#[instrument(name = "[Live] Listen", skip_all, err)]
+async fn listen<F>(
+    api_client: &mut InsertServiceClient<InterceptedService<Channel, F>>,
+    rabbitmq: &lapin::Channel,
+    hub_url: &str,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: tonic::service::Interceptor,
+{
+    let mut connection = HubConnection::connect(hub_url).await?;
+    let snapshot = connection.subscribe(&FEEDS).await?;
+    info!("Connected to Livetiming hub on {}", hub_url);
+
+    let mut session = LiveSession::from_snapshot(snapshot);
+    let mut interval = tokio::time::interval(PERSIST_INTERVAL);
+
+    loop {
+        tokio::select! {
+            updates = connection.next() => {
+                let Some(updates) = updates? else {
+                    info!("Livetiming hub closed the connection");
+                    break;
+                };
+
+                for update in updates {
+                    // A new session starts on the same connection, the previous one is over
+                    if update.feed == "SessionInfo"
+                        && let Some(key) = update.data["Key"].as_i64()
+                        && session.key().is_some_and(|k| k as i64 != key)
+                    {
+                        persist_logged(api_client, &mut session).await;
+                        session = LiveSession::default();
+                    }
+
+                    let kind = FORWARDED_FEEDS
+                        .iter()
+                        .find(|(feed, _)| *feed == update.feed)
+                        .map(|(_, kind)| (kind.to_string(), update.data.clone()));
+
+                    let time = session.apply(update);
+
+                    if let (Some((kind, data)), Some(session_key)) = (kind, session.key()) {
+                        forward(rabbitmq, LiveUpdate { session_key, kind, time, data }).await;
+                    }
+                }
+            }
+            _ = interval.tick() => persist_logged(api_client, &mut session).await,
+        }
+    }
+
+    persist(api_client, &mut session).await
+}
+
+// Live updates are best effort, a failure doesn't interrupt the session
+async fn forward(rabbitmq: &lapin::Channel, update: LiveUpdate) {
+    if let Err(err) =
+        metrics_one_queue::broadcast(rabbitmq, metrics_one_queue::LIVE_EXCHANGE, &update).await
+    {
+        warn!(error = %err, "Failed to forward live update");
+    }
+}
+
+// Data not sent is kept by the session, so a failure doesn't interrupt it and is retried later
+async fn persist_logged<F>(
+    api_client: &mut InsertServiceClient<InterceptedService<Channel, F>>,
+    session: &mut LiveSession,
+) where
+    F: tonic::service::Interceptor,
+{
+    if let Err(err) = persist(api_client, session).await {
+        error!(error = %err, "Failed to persist live session");
+    }
+}
+
+// Send the data completed since the last call to API service for insertion
+async fn persist<F>(
+    api_client: &mut InsertServiceClient<InterceptedService<Channel, F>>,
+    session: &mut LiveSession,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: tonic::service::Interceptor,
+{
+    let Some(session_key) = session.key() else {
+        trace!("No session followed yet");
+        return Ok(());
+    };
+
+    let laps = session.pending_laps();
+    if !laps.is_empty() {
+        let nb_laps = laps.len();
+        api_client
+            .insert_laps(InsertLapsRequest {
+                session_key,
+                laps: laps.clone(),
+            })
+            .await?;
+        session.mark_laps_sent(&laps);
+        debug!("{} live laps sent for session {}", nb_laps, session_key);
+    }
+
+    let messages = session.pending_race_control();
+    if !messages.is_empty() {
+        let nb_messages = messages.len();
+        api_client
+            .insert_race_control(InsertRaceControlRequest {
+                session_key,
+                messages,
+            })
+            .await?;
+        session.mark_race_control_sent(nb_messages);
+        debug!(
+            "{} live race control messages sent for session {}",
+            nb_messages, session_key
+        );
+    }
+
+    Ok(())
+}
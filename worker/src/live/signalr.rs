@@ -0,0 +1,183 @@
+use futures_util::{SinkExt, StreamExt};
+use reqwest::{Url, header};
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream, connect_async,
+    tungstenite::{Message, client::IntoClientRequest},
+};
+use tracing::{debug, trace};
+
+// Livetiming uses the legacy ASP.NET SignalR protocol with a single hub
+const HUB: &str = "Streaming";
+const CLIENT_PROTOCOL: &str = "1.5";
+const CONNECTION_DATA: &str = r#"[{"name":"Streaming"}]"#;
+
+/* ////////////////////////// */
+/* //// SignalR Messages //// */
+/* ////////////////////////// */
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Negotiation {
+    connection_token: String,
+}
+
+// Frames sent by the hub, either invocations on the client ('M') or the result of a call ('R')
+#[derive(Deserialize)]
+struct Frame {
+    #[serde(rename = "M", default)]
+    messages: Vec<Invocation>,
+    #[serde(rename = "R")]
+    result: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct Invocation {
+    #[serde(rename = "M")]
+    method: String,
+    #[serde(rename = "A", default)]
+    arguments: Vec<Value>,
+}
+
+// Update of a feed, 'date' being the time it was emitted at as sent by the hub
+pub struct FeedUpdate {
+    pub feed: String,
+    pub data: Value,
+    pub date: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/* //////////////////////// */
+/* //// Hub Connection //// */
+/* //////////////////////// */
+
+pub struct HubConnection {
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+}
+
+impl HubConnection {
+    // Negotiate a connection token then open the websocket, 'hub_url' being the SignalR endpoint
+    pub async fn connect(hub_url: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let hub_url = hub_url.trim_end_matches('/');
+
+        let mut url = Url::parse(&format!("{}/negotiate", hub_url))?;
+        url.query_pairs_mut()
+            .append_pair("clientProtocol", CLIENT_PROTOCOL)
+            .append_pair("connectionData", CONNECTION_DATA);
+
+        debug!("Negotiate connection with {}", url);
+        let res = reqwest::get(url).await?;
+        if !res.status().is_success() {
+            return Err("Failed to negotiate connection with Livetiming hub".into());
+        }
+
+        // The hub is behind a load balancer that needs its cookie back to keep the connection
+        let cookie = res
+            .headers()
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .filter_map(|c| c.to_str().ok()?.split(';').next())
+            .collect::<Vec<&str>>()
+            .join("; ");
+        let negotiation: Negotiation = res.json().await?;
+
+        let mut url = Url::parse(&format!("{}/connect", hub_url))?;
+        let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+        url.set_scheme(scheme)
+            .map_err(|_| "Failed to build Livetiming hub websocket URL")?;
+        url.query_pairs_mut()
+            .append_pair("clientProtocol", CLIENT_PROTOCOL)
+            .append_pair("transport", "webSockets")
+            .append_pair("connectionToken", &negotiation.connection_token)
+            .append_pair("connectionData", CONNECTION_DATA);
+
+        let mut request = url.as_str().into_client_request()?;
+        if !cookie.is_empty() {
+            request
+                .headers_mut()
+                .insert(header::COOKIE, cookie.parse()?);
+        }
+
+        debug!("Open websocket on {}", url);
+        let (socket, _) = connect_async(request).await?;
+
+        Ok(Self { socket })
+    }
+
+    // Subscribe to the feeds, the hub answers with the current state of each of them
+    pub async fn subscribe(
+        &mut self,
+        feeds: &[&str],
+    ) -> Result<Vec<FeedUpdate>, Box<dyn std::error::Error>> {
+        let invocation = serde_json::json!({
+            "H": HUB,
+            "M": "Subscribe",
+            "A": [feeds],
+            "I": 1,
+        });
+        self.socket
+            .send(Message::Text(invocation.to_string().into()))
+            .await?;
+
+        // Updates may be received before the result of the subscription, they are dropped
+        // as the result already holds the whole state
+        while let Some(message) = self.socket.next().await {
+            let Message::Text(text) = message? else {
+                continue;
+            };
+            let frame: Frame = serde_json::from_str(&text)?;
+
+            if let Some(Value::Object(result)) = frame.result {
+                return Ok(result
+                    .into_iter()
+                    .map(|(feed, data)| FeedUpdate {
+                        feed,
+                        data,
+                        date: None,
+                    })
+                    .collect());
+            }
+        }
+
+        Err("Livetiming hub closed the connection before subscription".into())
+    }
+
+    // Wait for the next updates, 'None' being returned once the hub closed the connection
+    pub async fn next(&mut self) -> Result<Option<Vec<FeedUpdate>>, Box<dyn std::error::Error>> {
+        while let Some(message) = self.socket.next().await {
+            let text = match message? {
+                Message::Text(text) => text,
+                Message::Close(_) => return Ok(None),
+                _ => continue,
+            };
+
+            // Keep alive frames are empty objects
+            let frame: Frame = serde_json::from_str(&text)?;
+            if frame.messages.is_empty() {
+                trace!("Keep alive received");
+                continue;
+            }
+
+            let updates = frame
+                .messages
+                .into_iter()
+                .filter(|m| m.method == "feed")
+                .filter_map(|m| {
+                    let mut arguments = m.arguments.into_iter();
+                    let feed = arguments.next()?.as_str()?.to_string();
+                    let data = arguments.next()?;
+                    let date = arguments
+                        .next()
+                        .and_then(|d| d.as_str()?.parse::<chrono::DateTime<chrono::Utc>>().ok());
+
+                    Some(FeedUpdate { feed, data, date })
+                })
+                .collect();
+
+            return Ok(Some(updates));
+        }
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use metrics_one_grpc::{
+    proto::{insert_laps_request::Lap, insert_race_control_request::Message},
+    utils::timestamp_to_datetime,
+};
+use metrics_one_livetiming::SessionState;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::{
+    fetch::{
+        laps,
+        livetiming::{self, StreamEntry},
+        race_control,
+    },
+    live::signalr::FeedUpdate,
+    models::{RaceControlMessages, Session, TimingData},
+};
+
+// Session states that end the live session, no more timing update is expected after them
+const FINISHED_STATUSES: [&str; 2] = ["Finalised", "Ends"];
+
+// Telemetry feeds are too large to keep their history for the whole session
+const HISTORY_FEEDS: [&str; 7] = [
+    "SessionInfo",
+    "SessionStatus",
+    "TrackStatus",
+    "TimingData",
+    "TimingAppData",
+    "DriverList",
+    "RaceControlMessages",
+];
+
+/* ////////////////////// */
+/* //// Live Session //// */
+/* ////////////////////// */
+
+// State of the session being followed, built from the updates of the hub
+// Updates are also recorded as stream entries so the feed builders can be reused,
+// their time being the offset from the start of the session as in its '.jsonStream' files
+// Without a start in 'SessionInfo', times are offsets from the first dated update received
+// Race control messages are kept until they are sent, so a failed insertion is retried
+pub struct LiveSession {
+    state: SessionState,
+    origin: Option<DateTime<Utc>>,
+    timing: Vec<StreamEntry<TimingData>>,
+    race_control: Vec<Message>,
+    sent_laps: HashSet<(i32, i32)>,
+}
+
+impl Default for LiveSession {
+    fn default() -> Self {
+        LiveSession {
+            state: SessionState::with_history_of(&HISTORY_FEEDS),
+            origin: None,
+            timing: Vec::new(),
+            race_control: Vec::new(),
+            sent_laps: HashSet::new(),
+        }
+    }
+}
+
+impl LiveSession {
+    // Laps already completed when joining a session can't be timed, so they are never sent
+    pub fn from_snapshot(updates: Vec<FeedUpdate>) -> Self {
+        let mut session = LiveSession::default();
+        for update in updates {
+            session.apply(update);
+        }
+
+        session.sent_laps = laps::build_laps(&session.timing)
+            .iter()
+            .map(|l| (l.driver_number, l.lap_number))
+            .collect();
+        session.race_control.clear();
+
+        session
+    }
+
+    // Returns the time of the update
+    pub fn apply(&mut self, update: FeedUpdate) -> i32 {
+        // Compressed feeds are sent as base64 encoded deflated JSON, under a '.z' name
+        let (feed, data) = match update.feed.strip_suffix(".z") {
+            Some(feed) => match update.data.as_str().map(livetiming::decompress::<Value>) {
+                Some(Ok(data)) => (feed.to_string(), data),
+                _ => {
+                    warn!("Failed to decompress '{}' update, skipping", update.feed);
+                    return 0;
+                }
+            },
+            None => (update.feed, update.data),
+        };
+
+        if feed == "SessionInfo"
+            && let Some(start) = session_start(&data)
+        {
+            self.origin = Some(start);
+        }
+
+        let time = match (update.date, self.origin) {
+            (Some(date), Some(origin)) => (date - origin).num_milliseconds() as i32,
+            (Some(date), None) => {
+                self.origin = Some(date);
+                0
+            }
+            (None, Some(origin)) => (Utc::now() - origin).num_milliseconds() as i32,
+            (None, None) => 0,
+        };
+
+        match feed.as_str() {
+            "TimingData" => record(&mut self.timing, time, &data),
+            "RaceControlMessages" => {
+                let mut entries: Vec<StreamEntry<RaceControlMessages>> = Vec::new();
+                record(&mut entries, time, &data);
+                self.race_control
+                    .extend(race_control::build_messages(entries));
+            }
+            _ => (),
+        }
+
+        self.state.apply(&feed, time, data);
+
+        time
+    }
+
+    pub fn key(&self) -> Option<i32> {
+        self.state.feed("SessionInfo")?["Key"]
+            .as_i64()
+            .map(|k| k as i32)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.state
+            .feed("SessionStatus")
+            .and_then(|s| s["Status"].as_str())
+            .is_some_and(|s| FINISHED_STATUSES.contains(&s))
+    }
+
+    // Laps not sent yet, the last lap of each driver is held back while the session is running
+    // as its last sector and finish line speed may still be received
+    pub fn pending_laps(&self) -> Vec<Lap> {
+        let laps = laps::build_laps(&self.timing);
+        let finished = self.is_finished();
+
+        let mut last_laps: HashMap<i32, i32> = HashMap::new();
+        for lap in laps.iter() {
+            let last = last_laps.entry(lap.driver_number).or_default();
+            *last = (*last).max(lap.lap_number);
+        }
+
+        laps.into_iter()
+            .filter(|l| !self.sent_laps.contains(&(l.driver_number, l.lap_number)))
+            .filter(|l| finished || last_laps.get(&l.driver_number) != Some(&l.lap_number))
+            .collect()
+    }
+
+    pub fn mark_laps_sent(&mut self, laps: &[Lap]) {
+        self.sent_laps
+            .extend(laps.iter().map(|l| (l.driver_number, l.lap_number)));
+    }
+
+    // Messages received and not sent yet, in the order they were received
+    pub fn pending_race_control(&self) -> Vec<Message> {
+        self.race_control.clone()
+    }
+
+    pub fn mark_race_control_sent(&mut self, nb_messages: usize) {
+        self.race_control
+            .drain(..nb_messages.min(self.race_control.len()));
+    }
+}
+
+/* ///////////////// */
+/* //// Helpers //// */
+/* ///////////////// */
+
+// Dates of 'SessionInfo' are local to the circuit
+fn session_start(data: &Value) -> Option<DateTime<Utc>> {
+    let session: Session = serde_json::from_value(data.clone()).ok()?;
+    let start = timestamp_to_datetime(session.start_date.as_ref()?).ok()?;
+
+    Some(start - TimeDelta::seconds(session.gmt_offset))
+}
+
+fn record<T>(entries: &mut Vec<StreamEntry<T>>, time: i32, data: &Value)
+where
+    T: serde::de::DeserializeOwned,
+{
+    match serde_json::from_value(data.clone()) {
+        Ok(data) => entries.push(StreamEntry { time, data }),
+        Err(err) => warn!(error = ?err, "Failed to parse live update, skipping"),
+    }
+}
@@ -0,0 +1,185 @@
+use std::path::PathBuf;
+
+use metrics_one_grpc::proto::{
+    insert_laps_request::Lap, insert_service_client::InsertServiceClient,
+};
+use metrics_one_queue::models::Session;
+use tokio::net::TcpListener;
+use tonic::transport::Channel;
+
+use crate::{
+    fetch,
+    live::{
+        FEEDS, persist,
+        signalr::{FeedUpdate, HubConnection},
+        state::LiveSession,
+    },
+    testing::{self, Interceptor, Recorder},
+};
+
+// Hub replaying the recorded session, as run by the 'stub_hub' example
+#[allow(dead_code)]
+#[path = "../../examples/stub_hub.rs"]
+mod stub_hub;
+
+// The recorded session lasts a few minutes, replayed in a fraction of a second
+const SPEED: f64 = 1000.0;
+
+#[tokio::test]
+async fn live_session_persists_laps_and_race_control() {
+    testing::init_env();
+
+    let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+    let hub_url = format!("http://{}/signalr", listener.local_addr().unwrap());
+    tokio::spawn(stub_hub::serve(
+        listener,
+        stub_hub::Settings {
+            directory: PathBuf::from(testing::FIXTURES_DIR).join(testing::SESSION_PATH),
+            speed: SPEED,
+        },
+    ));
+
+    let recorder = Recorder::default();
+    let mut api_client = recorder.serve().await;
+
+    // Followed as the live service does, updates not being forwarded to RabbitMQ
+    let mut connection = HubConnection::connect(&hub_url).await.unwrap();
+    let snapshot = connection.subscribe(&FEEDS).await.unwrap();
+    let mut session = LiveSession::from_snapshot(snapshot);
+
+    while let Some(updates) = connection.next().await.unwrap() {
+        for update in updates {
+            session.apply(update);
+        }
+    }
+    assert!(session.is_finished());
+
+    persist(&mut api_client, &mut session).await.unwrap();
+
+    // Same session ingested from its '.jsonStream' files, once it's over
+    let ingested = Recorder::default();
+    let params = Session {
+        key: 9472,
+        path: testing::SESSION_PATH.to_string(),
+        feeds: Some(vec!["laps".to_string(), "race_control".to_string()]),
+        job: None,
+    };
+    fetch::sessions::fetch_job(ingested.serve().await, params)
+        .await
+        .unwrap();
+
+    let recorded = recorder.recorded();
+    let ingested = ingested.recorded();
+
+    // Last laps are sent as well, the session being finalised
+    let [laps] = recorded.laps.as_slice() else {
+        panic!("Expected one laps request, got {}", recorded.laps.len());
+    };
+    assert_eq!(laps.session_key, 9472);
+
+    // Times are offsets from the start of the session, as the ones ingested
+    let times = |laps: &[Lap]| -> Vec<_> {
+        let mut times: Vec<_> = laps
+            .iter()
+            .map(|l| (l.driver_number, l.lap_number, l.time))
+            .collect();
+        times.sort();
+        times
+    };
+    let ingested_laps: Vec<Lap> = ingested.laps.iter().flat_map(|r| r.laps.clone()).collect();
+    assert_eq!(times(&laps.laps), times(&ingested_laps));
+
+    let laps: Vec<_> = laps
+        .laps
+        .iter()
+        .map(|l| {
+            let sectors = [l.sector_1, l.sector_2, l.sector_3];
+            (l.driver_number, l.lap_number, l.lap_time, sectors)
+        })
+        .collect();
+    assert_eq!(
+        laps,
+        vec![
+            (1, 1, Some(91500), [Some(30100), Some(29900), Some(31500)]),
+            (44, 1, Some(92800), [Some(30600), Some(30200), Some(32000)]),
+            (1, 2, Some(90800), [Some(29800), Some(29700), Some(31300)]),
+            (44, 2, Some(91900), [Some(30300), Some(30000), Some(31600)]),
+        ]
+    );
+
+    // Messages of the snapshot were sent before joining, only the following ones are persisted
+    let [race_control] = recorded.race_control.as_slice() else {
+        panic!(
+            "Expected one race control request, got {}",
+            recorded.race_control.len()
+        );
+    };
+    assert_eq!(race_control.session_key, 9472);
+
+    // The snapshot's message is the first one ingested
+    let live_times: Vec<_> = race_control.messages.iter().map(|m| m.time).collect();
+    let ingested_times: Vec<_> = ingested
+        .race_control
+        .iter()
+        .flat_map(|r| r.messages.iter())
+        .skip(1)
+        .map(|m| m.time)
+        .collect();
+    assert_eq!(live_times, ingested_times);
+
+    let messages: Vec<_> = race_control
+        .messages
+        .iter()
+        .map(|m| (m.message.as_str(), m.driver_number, m.deleted_lap))
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            (
+                "CAR 44 (HAM) TIME 1:32.800 DELETED - TRACK LIMITS AT TURN 4 LAP 1 15:01:33",
+                Some(44),
+                Some(1)
+            ),
+            ("CHEQUERED FLAG", None, None),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn race_control_is_kept_until_sent() {
+    testing::init_env();
+
+    let mut session = LiveSession::from_snapshot(vec![FeedUpdate {
+        feed: "SessionInfo".to_string(),
+        data: serde_json::json!({ "Key": 9472 }),
+        date: None,
+    }]);
+    session.apply(FeedUpdate {
+        feed: "RaceControlMessages".to_string(),
+        data: serde_json::json!({
+            "Messages": {
+                "1": { "Utc": "2024-03-02T15:02:00", "Category": "Flag", "Message": "RED FLAG" }
+            }
+        }),
+        date: Some(chrono::Utc::now()),
+    });
+
+    // Nothing listens on this port, so the insertion fails
+    let channel = Channel::from_static("http://127.0.0.1:1").connect_lazy();
+    let mut unreachable = InsertServiceClient::with_interceptor(channel, Ok as Interceptor);
+    assert!(persist(&mut unreachable, &mut session).await.is_err());
+    assert_eq!(session.pending_race_control().len(), 1);
+
+    let recorder = Recorder::default();
+    let mut api_client = recorder.serve().await;
+    persist(&mut api_client, &mut session).await.unwrap();
+    assert!(session.pending_race_control().is_empty());
+
+    let recorded = recorder.recorded();
+    let messages: Vec<_> = recorded
+        .race_control
+        .iter()
+        .flat_map(|r| r.messages.iter().map(|m| m.message.as_str()))
+        .collect();
+    assert_eq!(messages, vec!["RED FLAG"]);
+}
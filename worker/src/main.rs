@@ -1,66 +1,56 @@
+mod commands;
+mod consumer;
 mod fetch;
+mod live;
 mod models;
+mod scheduler;
 mod settings;
 
-use std::{collections::HashMap, sync::Arc, time::Duration};
+#[cfg(test)]
+mod testing;
 
-use lapin::types::{AMQPValue, FieldTable};
-use metrics_one_grpc::proto::insert_service_client::InsertServiceClient;
+use std::{sync::Arc, time::Duration};
+
+use clap::Parser;
+
+use metrics_one_grpc::{
+    interceptor::auth::ClientInterceptor, proto::insert_service_client::InsertServiceClient,
+};
 use metrics_one_utils::{
     grpc::{ShutdownSignalError, try_get_grpc_channel},
-    utils,
+    tls, utils,
 };
 use settings::ENV;
-use tokio_stream::StreamExt;
-use tracing::{Span, debug, error, info, info_span, trace};
+use tracing::{debug, error, info, info_span};
 
-use opentelemetry::{KeyValue, global, propagation::Extractor};
-use tracing_opentelemetry::OpenTelemetrySpanExt;
-
-use crate::fetch::meetings::fetch_job;
-
-// TODO: Move to common crate and move to traditional struct
-struct AmqpHeaderExtractor {
-    headers: HashMap<String, String>,
-}
-
-impl AmqpHeaderExtractor {
-    fn from_field_table(field_table: &FieldTable) -> Self {
-        let headers = field_table
-            .inner()
-            .iter()
-            .filter_map(|(k, v)| match v {
-                AMQPValue::LongString(s) => Some((k.to_string(), s.to_string())),
-                _ => None,
-            })
-            .collect();
-
-        Self { headers }
-    }
-}
-
-impl Extractor for AmqpHeaderExtractor {
-    fn get(&self, key: &str) -> Option<&str> {
-        self.headers.get(key).map(|v| v.as_str())
-    }
-
-    fn keys(&self) -> Vec<&str> {
-        self.headers.keys().map(|k| k.as_str()).collect()
-    }
-}
+use opentelemetry::global;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = commands::Cli::parse();
+
     dotenv::dotenv().ok();
 
     let _otel_guard =
         metrics_one_utils::otel::init_tracing_subscriber("metrics-one-worker", &ENV.rust_log);
 
+    // One-off commands run without connecting to the other services
+    if let Some(command) = cli.command {
+        return commands::run(command).await;
+    }
+
     let meter = global::meter("metrics-one-worker");
     let counter = meter.u64_counter("m1.messages.count").build();
 
     // TODO: Move above code to its own crate
 
+    // API service rejects inserts without a key, every job would fail
+    let Some(api_key) = ENV.api.key.as_deref().filter(|key| !key.is_empty()) else {
+        let message = "API.KEY is not set, create a key with the 'ingest' scope with 'metrics_one_api api-key create --name worker --scopes ingest'";
+        error!("{}", message);
+        return Err(message.into());
+    };
+
     // Setup of gRPC - TODO: Move to its own class
     let api_client = {
         let _span = info_span!("gRPC setup").entered();
@@ -68,16 +58,30 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         let addr = format!("http://{}:{}", ENV.api.host, ENV.api.port);
         debug!("Connection to API service on {} initiated", addr);
 
+        // TLS is handled by the connector, the address keeping its 'http://' scheme
+        let tls = match &ENV.api.tls {
+            Some(tls) => {
+                let identity = tls.cert.as_deref().zip(tls.key.as_deref());
+                Some(tls::client_config(&tls.ca, identity).map_err(|err| {
+                    error!(error = %err, "Failed to load TLS certificates");
+                    err as Box<dyn std::error::Error>
+                })?)
+            }
+            None => None,
+        };
+
         // Connection to API with gRPC
         // let channel = tonic::transport::Endpoint::from_static(&addr).connect().await?;
         // InsertServiceClient::with_interceptor(channel, metrics_one_grpc::interceptor::TracingInterceptor)
 
-        match try_get_grpc_channel(addr, Duration::from_secs(1)).await {
+        match try_get_grpc_channel(addr, Duration::from_secs(1), tls).await {
             Ok(res) => {
                 info!("Connection to API service established");
                 InsertServiceClient::with_interceptor(
                     res,
-                    metrics_one_grpc::interceptor::tracing::tracing_injector,
+                    ClientInterceptor::new(Some(api_key)).inspect_err(|err| {
+                        error!(error = ?err, "Invalid API key");
+                    })?,
                 )
             }
             Err(err) => {
@@ -93,7 +97,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     // Setup of RabbitMQ - TODO: Move to its own class
-    let mut rabbitmq_consumer = {
+    let (meetings_consumer, sessions_consumer, track_maps_consumer, rabbitmq_channel) = {
         let _span = info_span!("RabbitMQ setup").entered();
 
         // Connection to RabbitMQ
@@ -111,26 +115,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             })?,
         );
 
-        // Initializing RabbitMQ listenser
-        // TODO: Create a class to handle multiple queues
-        let consumer = channel
-            .basic_consume(
-                &ENV.rabbitmq.queue, // TODO: Move as constant to the common crate
-                "worker",            // TODO: Move to a constant
-                lapin::options::BasicConsumeOptions::default(),
-                lapin::types::FieldTable::default(),
-            )
-            .await
-            .inspect_err(|err| {
-                error!(error = ?err, "Consumer failed");
-            })?;
+        metrics_one_queue::declare_queue(&channel, metrics_one_queue::SESSIONS_QUEUE).await?;
+        metrics_one_queue::declare_queue(&channel, metrics_one_queue::TRACK_MAPS_QUEUE).await?;
+        metrics_one_queue::declare_fanout_exchange(&channel, metrics_one_queue::LIVE_EXCHANGE)
+            .await?;
 
-        info!(
-            "RabbitMQ consumer setup completed and listening to queue '{}'",
-            ENV.rabbitmq.queue
-        );
-
-        consumer
+        // Initializing RabbitMQ listensers
+        // TODO: Create a class to handle multiple queues
+        let meetings_consumer = consumer::get_consumer(&channel, &ENV.rabbitmq.queue).await?;
+        let sessions_consumer =
+            consumer::get_consumer(&channel, metrics_one_queue::SESSIONS_QUEUE).await?;
+        let track_maps_consumer =
+            consumer::get_consumer(&channel, metrics_one_queue::TRACK_MAPS_QUEUE).await?;
+
+        (
+            meetings_consumer,
+            sessions_consumer,
+            track_maps_consumer,
+            channel,
+        )
     };
 
     // Start listening on RabbitMQ
@@ -139,66 +142,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             info!("Shutdown signal received, shutting down the server...");
         }
         _ = async {
-            // TODO: Create a class to handle multiple queues
-            while let Some(delivery) = rabbitmq_consumer.next().await {
-
-                let delivery = match delivery {
-                    Ok(delivery) => delivery,
-                    Err(err) => {
-                        error!(error = ?err, "Error in RabbitMQ consumer, stopping consumption");
-                        counter.add(1, &[KeyValue::new("message.status", "failed")]);
-
-                        break;
+            tokio::join!(
+                consumer::consume(meetings_consumer, &counter, |payload| {
+                    fetch::meetings::fetch_job(api_client.clone(), payload)
+                }),
+                consumer::consume(sessions_consumer, &counter, |payload| {
+                    fetch::sessions::fetch_job(api_client.clone(), payload)
+                }),
+                consumer::consume(track_maps_consumer, &counter, |payload| {
+                    fetch::track_map::fetch_job(api_client.clone(), payload)
+                }),
+                async {
+                    // An empty URL disables live sessions too, as set by docker compose when unset
+                    match ENV.live_hub_url.as_deref().filter(|url| !url.is_empty()) {
+                        Some(url) => live::run(api_client.clone(), rabbitmq_channel.clone(), url).await,
+                        None => info!("No Livetiming hub configured, live sessions are disabled"),
                     }
-                };
-
-                // Get Trace context from request metadata
-                let parent_cx = if let Some(headers) = delivery.properties.headers() {
-                    global::get_text_map_propagator(|propagator| {
-                        propagator.extract(&AmqpHeaderExtractor::from_field_table(headers))
-                    })
-                } else {
-                    Span::current().context()
-                };
-
-                let span = info_span!("Message consumer");
-                span.set_parent(parent_cx);
-                let _enter = span.enter();
-
-                // Deserialize the message
-                let payload: metrics_one_queue::models::Meetings = match serde_json::from_slice(&delivery.data) {
-                    Ok(payload) => payload,
-                    Err(err) => {
-                        error!(error = ?err, "Failed to deserialize message payload, discarding message");
-                        counter.add(1, &[KeyValue::new("message.status", "failed")]);
-
-                        if let Err(err) = delivery.nack(lapin::options::BasicNackOptions::default()).await {
-                            error!(error = ?err, "Failed to nack message");
-                        }
-                        continue;
-                    }
-                };
-
-                // The message is now correctly deserialized, we can process it
-                match fetch_job(api_client.clone(), payload).await {
-                    Ok(_) => {
-                        trace!("Successfully processed message");
-                        counter.add(1, &[KeyValue::new("message.status", "success")]);
-
-                        if let Err(err) = delivery.ack(lapin::options::BasicAckOptions::default()).await {
-                            error!(error = ?err, "Failed to ack message");
-                        }
-                    }
-                    Err(err) => {
-                        error!(error = ?err, "Failed to process message");
-                        counter.add(1, &[KeyValue::new("message.status", "failed")]);
-
-                        if let Err(err) = delivery.nack(lapin::options::BasicNackOptions::default()).await {
-                            error!(error = ?err, "Failed to nack message");
-                        }
-                    }
-                }
-            }
+                },
+                scheduler::run(api_client.clone(), &rabbitmq_channel),
+            )
         } => {}
     }
 
@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+
+use metrics_one_grpc::serde::timestamp;
+use prost_types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+// Decoded entries of 'CarData.z.jsonStream', cars are keyed by racing number
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CarData {
+    #[serde(default)]
+    pub entries: Vec<CarDataEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CarDataEntry {
+    #[serde(with = "timestamp")]
+    pub utc: Option<Timestamp>,
+
+    #[serde(default)]
+    pub cars: HashMap<String, CarChannels>,
+}
+
+// Channels are keyed by index: '0' RPM, '2' speed, '3' gear, '4' throttle, '5' brake, '45' DRS
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CarChannels {
+    #[serde(default)]
+    pub channels: HashMap<String, i32>,
+}
+
+impl CarChannels {
+    pub fn get(&self, channel: &str) -> i32 {
+        self.channels.get(channel).copied().unwrap_or_default()
+    }
+}
@@ -1,5 +1,21 @@
+pub mod car_data;
 pub mod meeting;
+pub mod pit_stop;
+pub mod position;
+pub mod race_control;
 pub mod session;
+pub mod team_radio;
+pub mod timing_data;
+pub mod track_status;
+pub mod weather;
 
+pub use car_data::*;
 pub use meeting::*;
+pub use pit_stop::*;
+pub use position::*;
+pub use race_control::*;
 pub use session::*;
+pub use team_radio::*;
+pub use timing_data::*;
+pub use track_status::*;
+pub use weather::*;
@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/* /////////////////////////////// */
+/* //// PitLaneTimeCollection //// */
+/* /////////////////////////////// */
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PitLaneTimeCollection {
+    // Entries are keyed by racing number, but may also contain a '_deleted' list
+    #[serde(default)]
+    pub pit_times: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PitLaneTime {
+    pub racing_number: String,
+    pub duration: String,
+    pub lap: String,
+}
+
+/* /////////////////////// */
+/* //// PitStopSeries //// */
+/* /////////////////////// */
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PitStopSeries {
+    // Entries are keyed by racing number, each one being either a list or an index-keyed object
+    #[serde(default)]
+    pub pit_times: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PitStopEntry {
+    pub pit_stop: PitStop,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PitStop {
+    pub racing_number: String,
+    pub pit_stop_time: String,
+    pub pit_lane_time: String,
+    pub lap: String,
+}
+
+impl PitStopSeries {
+    pub fn entries(self) -> Vec<PitStop> {
+        self.pit_times
+            .into_values()
+            .flat_map(|v| match v {
+                serde_json::Value::Array(list) => list,
+                serde_json::Value::Object(map) => map.into_values().collect(),
+                _ => Vec::new(),
+            })
+            .filter_map(|v| serde_json::from_value::<PitStopEntry>(v).ok())
+            .map(|e| e.pit_stop)
+            .collect()
+    }
+}
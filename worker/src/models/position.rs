@@ -0,0 +1,33 @@
+use std::collections::HashMap;
+
+use metrics_one_grpc::serde::timestamp;
+use prost_types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+// Decoded entries of 'Position.z.jsonStream', cars are keyed by racing number
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PositionData {
+    #[serde(default)]
+    pub position: Vec<PositionBatch>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PositionBatch {
+    #[serde(with = "timestamp")]
+    pub timestamp: Option<Timestamp>,
+
+    #[serde(default)]
+    pub entries: HashMap<String, CarPosition>,
+}
+
+// Coordinates are in decimeters, status being either 'OnTrack' or 'OffTrack'
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CarPosition {
+    pub status: Option<String>,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
@@ -0,0 +1,57 @@
+use metrics_one_grpc::serde::timestamp;
+use prost_types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use super::Indexed;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RaceControlMessages {
+    pub messages: Indexed<RaceControlMessage>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RaceControlMessage {
+    #[serde(with = "timestamp")]
+    pub utc: Option<Timestamp>,
+
+    pub lap: Option<i32>,
+    pub category: String,
+    pub flag: Option<String>,
+    pub scope: Option<String>,
+    pub sector: Option<i32>,
+    pub racing_number: Option<String>,
+    pub status: Option<String>,
+    pub mode: Option<String>,
+    pub message: String,
+}
+
+impl RaceControlMessage {
+    // Racing number of the driver concerned, either given or written as 'CAR 44 (HAM)' in the message
+    pub fn driver_number(&self) -> Option<i32> {
+        if let Some(number) = &self.racing_number {
+            return number.parse().ok();
+        }
+
+        number_after(&self.message, "CAR")
+    }
+
+    // Lap of a deleted lap time, written as '... DELETED - TRACK LIMITS AT TURN 4 LAP 12 14:05:12'
+    pub fn deleted_lap(&self) -> Option<i32> {
+        if !self.message.contains("DELETED") {
+            return None;
+        }
+
+        number_after(&self.message, "LAP")
+    }
+}
+
+// Find the first number following an occurrence of 'word' in the message
+fn number_after(message: &str, word: &str) -> Option<i32> {
+    let words: Vec<&str> = message.split_whitespace().collect();
+
+    words
+        .windows(2)
+        .find_map(|w| (w[0] == word).then(|| w[1].parse().ok()).flatten())
+}
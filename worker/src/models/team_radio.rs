@@ -0,0 +1,23 @@
+use metrics_one_grpc::serde::timestamp;
+use prost_types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::models::timing_data::Indexed;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TeamRadio {
+    // The first stream entry holds a list of captures, following ones are keyed by index
+    pub captures: Indexed<Capture>,
+}
+
+// 'path' is relative to the session path, e.g. 'TeamRadio/MAXVER01_1_20240302_150405.mp3'
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Capture {
+    #[serde(with = "timestamp")]
+    pub utc: Option<Timestamp>,
+
+    pub racing_number: String,
+    pub path: String,
+}
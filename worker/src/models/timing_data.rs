@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+// Updates from 'TimingData.jsonStream', lines are keyed by racing number
+// Only the fields used by the feeds are parsed, the others are ignored
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TimingData {
+    #[serde(default)]
+    pub lines: HashMap<String, TimingLine>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TimingLine {
+    pub position: Option<String>,
+    pub retired: Option<bool>,
+    pub in_pit: Option<bool>,
+    pub pit_out: Option<bool>,
+    pub number_of_laps: Option<i32>,
+    pub last_lap_time: Option<TimingValue>,
+    pub sectors: Option<Indexed<TimingValue>>,
+    pub speeds: Option<Speeds>,
+}
+
+// Speeds in km/h at first and second intermediates, finish line and speed trap
+#[derive(Serialize, Deserialize)]
+pub struct Speeds {
+    #[serde(rename = "I1")]
+    pub i1: Option<TimingValue>,
+    #[serde(rename = "I2")]
+    pub i2: Option<TimingValue>,
+    #[serde(rename = "FL")]
+    pub fl: Option<TimingValue>,
+    #[serde(rename = "ST")]
+    pub st: Option<TimingValue>,
+}
+
+// Collections are sent as a list in the first entry, then as updates keyed by index
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Indexed<T> {
+    List(Vec<T>),
+    Map(HashMap<String, T>),
+}
+
+impl<T> Indexed<T> {
+    pub fn entries(&self) -> Vec<(usize, &T)> {
+        match self {
+            Indexed::List(items) => items.iter().enumerate().collect(),
+            Indexed::Map(items) => items
+                .iter()
+                .filter_map(|(i, item)| Some((i.parse().ok()?, item)))
+                .collect(),
+        }
+    }
+
+    // Items are returned by index
+    pub fn into_values(self) -> Vec<T> {
+        match self {
+            Indexed::List(items) => items,
+            Indexed::Map(items) => {
+                let mut items: Vec<(usize, T)> = items
+                    .into_iter()
+                    .filter_map(|(i, item)| Some((i.parse().ok()?, item)))
+                    .collect();
+                items.sort_by_key(|(i, _)| *i);
+                items.into_iter().map(|(_, item)| item).collect()
+            }
+        }
+    }
+}
+
+// Values are sent as formatted strings, e.g. '1:32.456' for lap times
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TimingValue {
+    pub value: Option<String>,
+}
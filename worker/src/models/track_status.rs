@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+// Statuses are sent as codes: '1' AllClear, '2' Yellow, '4' SCDeployed, '5' Red, '6' VSCDeployed, '7' VSCEnding
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TrackStatus {
+    pub status: String,
+    pub message: String,
+}
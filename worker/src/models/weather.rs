@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+// Values are sent as strings, e.g. '{"AirTemp":"25.3","Rainfall":"0",...}'
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct WeatherData {
+    pub air_temp: String,
+    pub track_temp: String,
+    pub humidity: String,
+    pub pressure: String,
+    pub wind_speed: String,
+    pub wind_direction: String,
+    pub rainfall: String,
+}
@@ -0,0 +1,242 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, TimeDelta, Utc};
+use cron::Schedule;
+use metrics_one_grpc::{
+    proto::insert_service_client::InsertServiceClient, utils::timestamp_to_datetime,
+};
+use serde::Serialize;
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+use tracing::{debug, error, info, instrument, trace, warn};
+
+use crate::{
+    fetch::{jobs, livetiming},
+    models::Meetings,
+    settings::ENV,
+};
+
+// Sessions over for longer are left to the backfill, so a restart doesn't fetch the whole season
+const SESSIONS_WINDOW: TimeDelta = TimeDelta::days(1);
+
+#[derive(Clone, Copy, Debug)]
+enum Task {
+    Calendar,
+    Sessions,
+}
+
+// Session of the current season, 'end_date' being in UTC
+struct ScheduledSession {
+    key: i32,
+    path: String,
+    end_date: DateTime<Utc>,
+}
+
+#[derive(Default)]
+struct Calendar {
+    sessions: Vec<ScheduledSession>,
+}
+
+/* /////////////////// */
+/* //// Scheduler //// */
+/* /////////////////// */
+
+// Run the periodic tasks for as long as the worker runs, at the times of their cron expression
+// An empty expression disables its task
+// Jobs are registered through the API, so they are coalesced with the ones of the API, the backfill
+// and the other workers, and sessions already ingested aren't sent again after a restart
+pub async fn run<F>(
+    mut api_client: InsertServiceClient<InterceptedService<Channel, F>>,
+    rabbitmq: &lapin::Channel,
+) where
+    F: tonic::service::Interceptor,
+{
+    let tasks: Vec<(Task, Schedule)> = [
+        (Task::Calendar, &ENV.scheduler.calendar),
+        (Task::Sessions, &ENV.scheduler.sessions),
+    ]
+    .into_iter()
+    .filter(|(_, expression)| !expression.is_empty())
+    .filter_map(|(task, expression)| match Schedule::from_str(expression) {
+        Ok(schedule) => Some((task, schedule)),
+        Err(err) => {
+            error!(error = %err, "Invalid schedule of {:?} task, task disabled", task);
+            None
+        }
+    })
+    .collect();
+
+    if tasks.is_empty() {
+        info!("No task scheduled, scheduler disabled");
+        return;
+    }
+
+    // Loaded on startup, so sessions are followed without waiting for the next refresh
+    let mut calendar = Calendar::default();
+    refresh_calendar(&mut api_client, rabbitmq, &mut calendar).await;
+
+    loop {
+        let Some((task, date)) = tasks
+            .iter()
+            .filter_map(|(task, schedule)| schedule.upcoming(Utc).next().map(|d| (*task, d)))
+            .min_by_key(|(_, date)| *date)
+        else {
+            return;
+        };
+
+        debug!("Next {:?} task scheduled at {}", task, date);
+        tokio::time::sleep((date - Utc::now()).to_std().unwrap_or_default()).await;
+
+        match task {
+            Task::Calendar => refresh_calendar(&mut api_client, rabbitmq, &mut calendar).await,
+            Task::Sessions => send_ended_sessions(&mut api_client, rabbitmq, &calendar).await,
+        }
+    }
+}
+
+// Request the current season calendar to be stored and keep its sessions to fetch them once over
+#[instrument(name = "[Task] Refresh Calendar", skip_all)]
+async fn refresh_calendar<F>(
+    api_client: &mut InsertServiceClient<InterceptedService<Channel, F>>,
+    rabbitmq: &lapin::Channel,
+    calendar: &mut Calendar,
+) where
+    F: tonic::service::Interceptor,
+{
+    let year = Utc::now().year();
+    let path = format!("{}/Index.json", year);
+
+    // Fetched with a conditional request, most refreshes finding the calendar unchanged
+    let modified = match load_sessions(&path).await {
+        Ok((sessions, modified)) => {
+            info!("{} sessions scheduled for {}", sessions.len(), year);
+            calendar.sessions = sessions;
+            modified
+        }
+        Err(err) => {
+            warn!(error = %err, "Failed to load calendar of {}", year);
+            return;
+        }
+    };
+
+    // A changed calendar is stored even if the last job is done, meetings stored being ignored
+    // An unchanged one is only sent again when its last job failed or got lost
+    let key = metrics_one_queue::models::Meetings::job_key(year);
+    let sent = send_job(
+        api_client,
+        rabbitmq,
+        &key,
+        modified,
+        metrics_one_queue::MEETINGS_QUEUE,
+        |id| metrics_one_queue::models::Meetings {
+            keys: Vec::new(),
+            year,
+            job: Some(id),
+        },
+    )
+    .await;
+
+    match sent {
+        Ok(Some(id)) => info!("Meetings fetch request of {} sent as job {}", year, id),
+        Ok(None) => debug!("Meetings of {} already stored or in flight", year),
+        Err(err) => error!(error = %err, "Failed to send meetings fetch request"),
+    }
+}
+
+// Returns whether the calendar changed since its last fetch too
+async fn load_sessions(
+    path: &str,
+) -> Result<(Vec<ScheduledSession>, bool), Box<dyn std::error::Error>> {
+    let (meetings, modified) = livetiming::get_json_modified::<Meetings>(path).await?;
+
+    let sessions = meetings
+        .meetings
+        .into_iter()
+        .flat_map(|m| m.sessions)
+        .filter_map(|s| {
+            // Dates are local to the circuit
+            let end_date = timestamp_to_datetime(s.end_date.as_ref()?).ok()?
+                - TimeDelta::seconds(s.gmt_offset);
+
+            Some(ScheduledSession {
+                key: s.key,
+                path: s.path?,
+                end_date,
+            })
+        })
+        .collect();
+
+    Ok((sessions, modified))
+}
+
+// Send the sessions over for 'session_delay' minutes to the queue, unless already ingested
+#[instrument(name = "[Task] Send Ended Sessions", skip_all)]
+async fn send_ended_sessions<F>(
+    api_client: &mut InsertServiceClient<InterceptedService<Channel, F>>,
+    rabbitmq: &lapin::Channel,
+    calendar: &Calendar,
+) where
+    F: tonic::service::Interceptor,
+{
+    let now = Utc::now();
+    let delay = TimeDelta::minutes(ENV.scheduler.session_delay);
+
+    for session in calendar.sessions.iter() {
+        let fetch_date = session.end_date + delay;
+        if fetch_date > now || now - fetch_date > SESSIONS_WINDOW {
+            continue;
+        }
+
+        let key = metrics_one_queue::models::Session::job_key(session.key, None);
+        let sent = send_job(
+            api_client,
+            rabbitmq,
+            &key,
+            false,
+            metrics_one_queue::SESSIONS_QUEUE,
+            |id| metrics_one_queue::models::Session {
+                key: session.key,
+                path: session.path.clone(),
+                feeds: None,
+                job: Some(id),
+            },
+        )
+        .await;
+
+        match sent {
+            Ok(Some(id)) => info!("Session {} fetch request sent as job {}", session.key, id),
+            Ok(None) => trace!("Session {} already ingested or in flight", session.key),
+            Err(err) => {
+                error!(error = %err, "Failed to send session {} fetch request", session.key)
+            }
+        }
+    }
+}
+
+// Send a job to the queue unless the same one is done or in flight, returning its id when sent
+// A job that never reached the queue is reported as failed, to be sent again on the next run
+async fn send_job<F, T, P>(
+    api_client: &mut InsertServiceClient<InterceptedService<Channel, F>>,
+    rabbitmq: &lapin::Channel,
+    key: &str,
+    force: bool,
+    queue: &str,
+    payload: P,
+) -> Result<Option<i32>, Box<dyn std::error::Error>>
+where
+    F: tonic::service::Interceptor,
+    T: Serialize,
+    P: FnOnce(i32) -> T,
+{
+    let job = jobs::register(api_client, key, force).await?;
+    if !job.is_new {
+        return Ok(None);
+    }
+
+    let result = metrics_one_queue::publish(rabbitmq, queue, &payload(job.id)).await;
+    if result.is_err() {
+        jobs::report(api_client, Some(job.id), &result).await;
+    }
+    result?;
+
+    Ok(Some(job.id))
+}
@@ -2,10 +2,23 @@ use config::{Config, ConfigError, Environment};
 use once_cell::sync::Lazy;
 use serde::Deserialize;
 
+// TLS of the API connection, trusting servers signed by 'ca'
+// 'cert' and 'key' are presented to the API when it requires client certificates (mTLS)
+// Files are reloaded when modified, so certificates can be renewed without a restart
 #[derive(Debug, Deserialize)]
-pub struct HostSettings {
+pub struct TlsSettings {
+    pub ca: String,
+    pub cert: Option<String>,
+    pub key: Option<String>,
+}
+
+// API service, 'key' being an API key with the 'ingest' scope
+#[derive(Debug, Deserialize)]
+pub struct ApiSettings {
     pub host: String,
     pub port: u16,
+    pub key: Option<String>,
+    pub tls: Option<TlsSettings>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -17,20 +30,105 @@ pub struct RabbitMqSettings {
     pub queue: String,
 }
 
+// Livetiming HTTP client, timeout being in seconds and rate limit in requests per second
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ClientSettings {
+    pub timeout: u64,
+    pub retries: u32,
+    pub user_agent: String,
+    pub cache_dir: Option<String>,
+    pub rate_limit: f64,
+    pub burst: u32,
+    pub max_concurrency: usize,
+}
+
+impl Default for ClientSettings {
+    fn default() -> Self {
+        ClientSettings {
+            timeout: 30,
+            retries: 3,
+            user_agent: format!("metrics-one-worker/{}", env!("CARGO_PKG_VERSION")),
+            cache_dir: None,
+            rate_limit: 5.0,
+            burst: 10,
+            max_concurrency: 4,
+        }
+    }
+}
+
+impl ClientSettings {
+    // A bucket that is never refilled, or can't hold a token, would block every request
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !(self.rate_limit.is_finite() && self.rate_limit > 0.0) {
+            return Err(ConfigError::Message(format!(
+                "CLIENT.RATE_LIMIT must be a positive number of requests per second, got {}",
+                self.rate_limit
+            )));
+        }
+        if self.burst == 0 {
+            return Err(ConfigError::Message(
+                "CLIENT.BURST must allow at least one request".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+// Cron expressions of the periodic tasks, with seconds and in UTC, an empty one disabling its task
+// Sessions are fetched 'session_delay' minutes after their end, once Livetiming files are complete
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct SchedulerSettings {
+    pub calendar: String,
+    pub sessions: String,
+    pub session_delay: i64,
+}
+
+impl Default for SchedulerSettings {
+    fn default() -> Self {
+        SchedulerSettings {
+            calendar: "0 0 4 * * *".to_string(),
+            sessions: "0 */5 * * * *".to_string(),
+            session_delay: 30,
+        }
+    }
+}
+
+// Credentials of an S3 compatible storage, used to mirror Livetiming files to a bucket
+#[derive(Debug, Deserialize)]
+pub struct S3Settings {
+    pub endpoint: String,
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Settings {
     pub rabbitmq: RabbitMqSettings,
-    pub api: HostSettings,
+    pub api: ApiSettings,
     pub livetiming_url: String,
+    pub livetiming_dir: Option<String>,
+    #[serde(default)]
+    pub client: ClientSettings,
+    pub live_hub_url: Option<String>,
+    #[serde(default)]
+    pub scheduler: SchedulerSettings,
+    pub s3: Option<S3Settings>,
     pub rust_log: String,
 }
 
 impl Settings {
     pub fn from_env() -> Result<Self, ConfigError> {
-        Config::builder()
+        let settings = Config::builder()
             .add_source(Environment::default())
             .build()?
-            .try_deserialize::<Settings>()
+            .try_deserialize::<Settings>()?;
+        settings.client.validate()?;
+
+        Ok(settings)
     }
 }
 
@@ -39,3 +137,32 @@ pub static ENV: Lazy<Settings> = Lazy::new(|| {
     // Use of 'expect' here because logger is not set after loading environment variables
     Settings::from_env().expect("Failed to parse environment variables")
 });
+
+#[cfg(test)]
+mod tests {
+    use super::ClientSettings;
+
+    fn client(rate_limit: f64, burst: u32) -> ClientSettings {
+        ClientSettings {
+            rate_limit,
+            burst,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rate_limit_must_be_positive() {
+        for rate_limit in [0.0, -0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(client(rate_limit, 10).validate().is_err(), "{}", rate_limit);
+        }
+
+        assert!(client(0.01, 10).validate().is_ok());
+        assert!(client(5.0, 10).validate().is_ok());
+    }
+
+    #[test]
+    fn burst_must_allow_a_request() {
+        assert!(client(5.0, 0).validate().is_err());
+        assert!(client(5.0, 1).validate().is_ok());
+    }
+}
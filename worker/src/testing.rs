@@ -0,0 +1,189 @@
+// Support of the tests, API service being replaced by a server recording the requests it receives
+
+use std::sync::{Arc, Mutex, MutexGuard, Once};
+
+use metrics_one_grpc::proto::{
+    self,
+    insert_service_client::InsertServiceClient,
+    insert_service_server::{InsertService, InsertServiceServer},
+};
+use tonic::{
+    Request, Response, Status,
+    service::interceptor::InterceptedService,
+    transport::{Channel, Server, server::TcpIncoming},
+};
+
+// Recorded Livetiming files, laid out as the API
+pub const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/livetiming");
+
+// Session recorded in the fixtures
+pub const SESSION_PATH: &str = "2024/2024-03-02_Bahrain_Grand_Prix/2024-03-02_Race/";
+
+pub type Interceptor = fn(Request<()>) -> Result<Request<()>, Status>;
+pub type ApiClient = InsertServiceClient<InterceptedService<Channel, Interceptor>>;
+
+// Settings are read once from the environment, so every test sets the same ones before anything else
+// Files are read from the fixtures, RabbitMQ and API service are never reached
+pub fn init_env() {
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        let variables = [
+            ("RABBITMQ.USER", "test"),
+            ("RABBITMQ.PASSWORD", "test"),
+            ("RABBITMQ.HOST", "127.0.0.1"),
+            ("RABBITMQ.PORT", "5672"),
+            ("RABBITMQ.QUEUE", "fetch.meetings"),
+            ("API.HOST", "127.0.0.1"),
+            ("API.PORT", "50051"),
+            ("LIVETIMING_URL", "http://127.0.0.1"),
+            ("LIVETIMING_DIR", FIXTURES_DIR),
+            ("RUST_LOG", "info"),
+        ];
+
+        for (key, value) in variables {
+            // SAFETY: variables are set once, by the first test reading the settings, before any read
+            unsafe { std::env::set_var(key, value) };
+        }
+    });
+}
+
+/* ////////////////// */
+/* //// Recorder //// */
+/* ////////////////// */
+
+#[derive(Default)]
+pub struct Recorded {
+    pub meetings: Vec<proto::InsertMeetingsRequest>,
+    pub laps: Vec<proto::InsertLapsRequest>,
+    pub race_control: Vec<proto::InsertRaceControlRequest>,
+}
+
+#[derive(Clone, Default)]
+pub struct Recorder {
+    recorded: Arc<Mutex<Recorded>>,
+}
+
+impl Recorder {
+    pub fn recorded(&self) -> MutexGuard<'_, Recorded> {
+        self.recorded.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    // Serve the recorder on an ephemeral port, returning a client connected to it
+    pub async fn serve(&self) -> ApiClient {
+        let incoming = TcpIncoming::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let addr = incoming.local_addr().unwrap();
+
+        tokio::spawn(
+            Server::builder()
+                .add_service(InsertServiceServer::new(self.clone()))
+                .serve_with_incoming(incoming),
+        );
+
+        let channel = Channel::from_shared(format!("http://{}", addr))
+            .unwrap()
+            .connect()
+            .await
+            .unwrap();
+
+        InsertServiceClient::with_interceptor(channel, Ok as Interceptor)
+    }
+}
+
+// Only the requests asserted by the tests are recorded, the others are accepted
+#[tonic::async_trait]
+impl InsertService for Recorder {
+    async fn insert_meetings(
+        &self,
+        request: Request<proto::InsertMeetingsRequest>,
+    ) -> Result<Response<proto::InsertMeetingsResponse>, Status> {
+        self.recorded().meetings.push(request.into_inner());
+        Ok(Response::new(Default::default()))
+    }
+
+    async fn insert_laps(
+        &self,
+        request: Request<proto::InsertLapsRequest>,
+    ) -> Result<Response<proto::InsertLapsResponse>, Status> {
+        self.recorded().laps.push(request.into_inner());
+        Ok(Response::new(Default::default()))
+    }
+
+    async fn insert_race_control(
+        &self,
+        request: Request<proto::InsertRaceControlRequest>,
+    ) -> Result<Response<proto::InsertRaceControlResponse>, Status> {
+        self.recorded().race_control.push(request.into_inner());
+        Ok(Response::new(Default::default()))
+    }
+
+    async fn insert_pit_stops(
+        &self,
+        _: Request<proto::InsertPitStopsRequest>,
+    ) -> Result<Response<proto::InsertPitStopsResponse>, Status> {
+        Ok(Response::new(Default::default()))
+    }
+
+    async fn insert_track_status(
+        &self,
+        _: Request<proto::InsertTrackStatusRequest>,
+    ) -> Result<Response<proto::InsertTrackStatusResponse>, Status> {
+        Ok(Response::new(Default::default()))
+    }
+
+    async fn insert_weather(
+        &self,
+        _: Request<proto::InsertWeatherRequest>,
+    ) -> Result<Response<proto::InsertWeatherResponse>, Status> {
+        Ok(Response::new(Default::default()))
+    }
+
+    async fn insert_team_radio(
+        &self,
+        _: Request<proto::InsertTeamRadioRequest>,
+    ) -> Result<Response<proto::InsertTeamRadioResponse>, Status> {
+        Ok(Response::new(Default::default()))
+    }
+
+    async fn insert_car_data(
+        &self,
+        _: Request<proto::InsertCarDataRequest>,
+    ) -> Result<Response<proto::InsertCarDataResponse>, Status> {
+        Ok(Response::new(Default::default()))
+    }
+
+    async fn insert_positions(
+        &self,
+        _: Request<proto::InsertPositionsRequest>,
+    ) -> Result<Response<proto::InsertPositionsResponse>, Status> {
+        Ok(Response::new(Default::default()))
+    }
+
+    async fn insert_track_map(
+        &self,
+        _: Request<proto::InsertTrackMapRequest>,
+    ) -> Result<Response<proto::InsertTrackMapResponse>, Status> {
+        Ok(Response::new(Default::default()))
+    }
+
+    async fn insert_session_results(
+        &self,
+        _: Request<proto::InsertSessionResultsRequest>,
+    ) -> Result<Response<proto::InsertSessionResultsResponse>, Status> {
+        Ok(Response::new(Default::default()))
+    }
+
+    async fn update_job(
+        &self,
+        _: Request<proto::UpdateJobRequest>,
+    ) -> Result<Response<proto::UpdateJobResponse>, Status> {
+        Ok(Response::new(Default::default()))
+    }
+
+    async fn register_job(
+        &self,
+        _: Request<proto::RegisterJobRequest>,
+    ) -> Result<Response<proto::RegisterJobResponse>, Status> {
+        Ok(Response::new(Default::default()))
+    }
+}